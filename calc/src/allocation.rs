@@ -0,0 +1,247 @@
+//! 给水焓升分配
+//!
+//! 除氧器及低压加热器的总焓升 `h_deao - h_cd` 分为 `Z_l + 1` 段，高压加热器的总焓升
+//! `h_fw - h_deao` 分为 `Z_h` 段，沿给水流向依次分配给各级加热器，可选：
+//! - 等焓升：各段焓升相等；
+//! - 等温升：各段给水温升相等，温度与比焓按蒸汽发生器二次侧压力下的过冷水换算；
+//! - 几何分配：各段焓升按公比 `q_fw` 递增，`Δh_k = Δh_1·q_fw^(k-1)`；
+//! - 自定义：按各级给定的权重分配。
+//!
+//! 各种分配方式的总焓升相同，各级出口给水温度及抽汽压力随之改变，可由[`sweep`]逐一计算后对比。
+use std::fmt::Display;
+
+use seuif97::*;
+
+use crate::Calculator;
+use crate::compare::CalcRun;
+use crate::parameters::CalcInputParameters;
+
+/// 低压侧的加热器级数：第一至四级低压加热器与除氧器
+const LP_STAGES: usize = 5;
+/// 高压侧的加热器级数：第六、七级高压加热器
+const HP_STAGES: usize = 2;
+
+/// 给水焓升分配方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Allocation {
+    /// 等焓升
+    EqualEnthalpy,
+    /// 等温升
+    EqualTemperature,
+    /// 几何分配
+    Geometric,
+    /// 自定义权重
+    Custom,
+}
+
+impl Allocation {
+    pub const ALL: [Self; 4] = [
+        Self::EqualEnthalpy,
+        Self::EqualTemperature,
+        Self::Geometric,
+        Self::Custom,
+    ];
+
+    /// 由输入参数中的数值编码确定：0为等焓升，1为等温升，2为几何分配，3为自定义，
+    /// 其他取值返回错误
+    pub fn from_params(params: &CalcInputParameters) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|allocation| allocation.code() == params.fw_alloc)
+            .ok_or_else(|| format!("给水焓升分配方式{}不是有效选项", params.fw_alloc))
+    }
+
+    /// 输入参数中的数值编码
+    pub fn code(self) -> f64 {
+        f64::from(self as u8)
+    }
+}
+
+impl Display for Allocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EqualEnthalpy => write!(f, "等焓升"),
+            Self::EqualTemperature => write!(f, "等温升"),
+            Self::Geometric => write!(f, "几何分配"),
+            Self::Custom => write!(f, "自定义"),
+        }
+    }
+}
+
+/// 将`h_start`至`h_end`的焓升分为`parts`段
+///
+/// # Arguments
+///
+/// * `weights` - 自定义时前几段的权重，其余各段的权重为1
+fn split(
+    params: &CalcInputParameters,
+    (h_start, h_end): (f64, f64),
+    parts: usize,
+    weights: &[f64],
+) -> Result<Vec<f64>, String> {
+    let total = h_end - h_start;
+    let shares: Vec<f64> = match Allocation::from_params(params)? {
+        Allocation::EqualEnthalpy => vec![1.0; parts],
+        Allocation::EqualTemperature => {
+            // 低于饱和温度的给水比焓几乎不随压力变化
+            let p = params.p_s;
+            let (t_start, t_end) = (ph(p, h_start, OT), ph(p, h_end, OT));
+            let h = |k: usize| pt(p, t_start + (t_end - t_start) * k as f64 / parts as f64, OH);
+            (1..=parts).map(|k| h(k) - h(k - 1)).collect()
+        }
+        Allocation::Geometric => {
+            if !params.q_fw.is_finite() || params.q_fw <= 0.0 {
+                return Err(format!("几何分配公比应为正数，当前为{}", params.q_fw));
+            }
+            (0..parts).map(|k| params.q_fw.powi(k as i32)).collect()
+        }
+        Allocation::Custom => {
+            if let Some(weight) = weights
+                .iter()
+                .find(|weight| !weight.is_finite() || **weight <= 0.0)
+            {
+                return Err(format!("给水焓升权重应为正数，当前为{weight}"));
+            }
+            (0..parts)
+                .map(|k| weights.get(k).copied().unwrap_or(1.0))
+                .collect()
+        }
+    };
+    // 换算比焓时的舍入误差按比例修正，保证总焓升不变
+    let sum: f64 = shares.iter().sum();
+    Ok(shares.iter().map(|share| total * share / sum).collect())
+}
+
+/// 各级给水焓升(kJ/kg)：(高压加热器各段, 除氧器及低压加热器各段)，均沿给水流向排列
+///
+/// 低压侧前四段为第一至四级低压加热器，其余各段合并为除氧器的焓升；高压侧前两段为第六、七级高压加热器。
+pub fn enthalpy_rises(
+    params: &CalcInputParameters,
+    h_cd: f64,
+    h_deao: f64,
+    h_fw: f64,
+) -> Result<(Vec<f64>, Vec<f64>), String> {
+    let parts = |z: f64, min: usize, name: &str| {
+        let parts = z.round();
+        if parts >= min as f64 {
+            Ok(parts as usize)
+        } else {
+            Err(format!("{name}{z}少于热平衡模型中的加热器级数{min}"))
+        }
+    };
+    let dh_fwh = split(
+        params,
+        (h_deao, h_fw),
+        parts(params.z_h, HP_STAGES, "高压给水加热器级数")?,
+        &[params.w_fw6, params.w_fw7],
+    )?;
+    let dh_fwl = split(
+        params,
+        (h_cd, h_deao),
+        parts(params.z_l + 1.0, LP_STAGES, "除氧器及低压给水加热器级数")?,
+        &[
+            params.w_fw1,
+            params.w_fw2,
+            params.w_fw3,
+            params.w_fw4,
+            params.w_fwd,
+        ],
+    )?;
+    Ok((dh_fwh, dh_fwl))
+}
+
+/// 以各种分配方式分别计算，计算失败的分配方式给出原因
+///
+/// 成功的计算记录以分配方式命名，可直接加入结果对比。
+pub fn sweep(params: &CalcInputParameters) -> Vec<(Allocation, Result<CalcRun, String>)> {
+    Allocation::ALL
+        .into_iter()
+        .map(|allocation| {
+            let params = CalcInputParameters {
+                fw_alloc: allocation.code(),
+                ..params.clone()
+            };
+            let run = Calculator::solve(params.clone()).map(|calculator| CalcRun {
+                name: format!("给水焓升{allocation}"),
+                params,
+                results: calculator.results,
+            });
+            (allocation, run)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rises(allocation: Allocation, params: CalcInputParameters) -> (Vec<f64>, Vec<f64>) {
+        let params = CalcInputParameters {
+            fw_alloc: allocation.code(),
+            ..params
+        };
+        enthalpy_rises(&params, 150.0, 700.0, 1000.0).unwrap()
+    }
+
+    #[test]
+    fn test_enthalpy_rises() {
+        let default = CalcInputParameters::from_default();
+        for allocation in Allocation::ALL {
+            let (dh_fwh, dh_fwl) = rises(allocation, default.clone());
+            assert_eq!((dh_fwh.len(), dh_fwl.len()), (2, 5));
+            assert!((dh_fwh.iter().sum::<f64>() - 300.0).abs() < 1e-9);
+            assert!((dh_fwl.iter().sum::<f64>() - 550.0).abs() < 1e-9);
+        }
+        let (_, equal) = rises(Allocation::EqualEnthalpy, default.clone());
+        assert!(equal.iter().all(|dh| (dh - 110.0).abs() < 1e-9));
+        // 水的比热容随温度升高而增大，等温升时高温段的焓升较大
+        let (_, temperature) = rises(Allocation::EqualTemperature, default.clone());
+        assert!(temperature.windows(2).all(|pair| pair[1] > pair[0]));
+
+        let (_, geometric) = rises(Allocation::Geometric, default.clone());
+        assert!((geometric[1] / geometric[0] - default.q_fw).abs() < 1e-9);
+
+        let (dh_fwh, _) = rises(
+            Allocation::Custom,
+            CalcInputParameters {
+                w_fw6: 2.0,
+                ..default.clone()
+            },
+        );
+        assert!((dh_fwh[0] - 200.0).abs() < 1e-9);
+        let params = CalcInputParameters {
+            fw_alloc: Allocation::Custom.code(),
+            w_fw1: 0.0,
+            ..default
+        };
+        assert!(enthalpy_rises(&params, 150.0, 700.0, 1000.0).is_err());
+
+        // 不是有效选项的编码不再按等焓升分配
+        for fw_alloc in [4.0, 1.5, -1.0] {
+            let params = CalcInputParameters {
+                fw_alloc,
+                ..CalcInputParameters::from_default()
+            };
+            assert!(enthalpy_rises(&params, 150.0, 700.0, 1000.0).is_err());
+            assert!(Calculator::solve(params).is_err());
+        }
+    }
+
+    #[test]
+    fn test_sweep() {
+        let runs = sweep(&CalcInputParameters::from_default());
+        assert_eq!(runs.len(), Allocation::ALL.len());
+        let pressures: Vec<f64> = runs
+            .iter()
+            .map(|(allocation, run)| {
+                let run = run
+                    .as_ref()
+                    .unwrap_or_else(|error| panic!("{allocation}: {error}"));
+                run.results.result2.lhes[0].p_hesx
+            })
+            .collect();
+        // 默认权重相同，自定义与等焓升一致；几何分配的第一级焓升较小，抽汽压力较低
+        assert!((pressures[3] - pressures[0]).abs() < 1e-9);
+        assert!(pressures[2] < pressures[0]);
+    }
+}
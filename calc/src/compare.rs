@@ -0,0 +1,442 @@
+//! 多次计算结果对比
+//!
+//! 以第一组计算为基准，列出取值不同的输入参数，并给出每个结果字段相对基准的绝对变化与相对变化。
+//! 相对变化按内部单位计算，不随显示单位制改变。
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::export::csv::line;
+use crate::fields::{
+    FW_COLUMNS, FieldMeta, HES_COLUMNS, INPUT_FIELDS, RESULT1_COLUMNS, RESULT2_TABLE1,
+    RESULT2_TABLE2, RHX_COLUMNS, field_map, field_value,
+};
+use crate::parameters::{CalcInputParameters, CalcResultParamters};
+use crate::units::UnitSystem;
+
+/// 结果中标记为变化最大的字段数
+pub const HIGHLIGHT_COUNT: usize = 10;
+
+/// 一次计算：输入参数及计算结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalcRun {
+    /// 名称
+    #[serde(default)]
+    pub name: String,
+    /// 计算前的输入参数
+    pub params: CalcInputParameters,
+    pub results: CalcResultParamters,
+}
+
+impl CalcRun {
+    /// 从文件读取计算记录
+    ///
+    /// 支持包含输入参数与结果的计算记录(run.json)，以及`results.json`，
+    /// 后者的输入参数取同目录下的`parameters.json`。
+    pub fn from_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let invalid = |error: &dyn std::fmt::Display| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("{}: {error}", path.display()),
+            )
+        };
+        let value: Value =
+            serde_json::from_str(&std::fs::read_to_string(path)?).map_err(|e| invalid(&e))?;
+        let mut run = if value.get("results").is_some() {
+            serde_json::from_value(value).map_err(|e| invalid(&e))?
+        } else {
+            let params_path = path.with_file_name("parameters.json");
+            let params = std::fs::read_to_string(&params_path)
+                .map_err(|_| invalid(&"缺少同目录下的parameters.json"))?;
+            // results.json 位于输出目录中，以目录名区分
+            let name = path
+                .parent()
+                .and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            CalcRun {
+                name,
+                params: serde_json::from_str(&params).map_err(|e| invalid(&e))?,
+                results: serde_json::from_value(value).map_err(|e| invalid(&e))?,
+            }
+        };
+        if run.name.is_empty() {
+            run.name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        }
+        Ok(run)
+    }
+
+    /// 保存计算记录
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer_pretty(writer, self).map_err(Error::other)
+    }
+}
+
+/// 对比表中的一行，`values`按计算顺序排列，第一个为基准
+#[derive(Debug, Clone)]
+pub struct CompareRow {
+    /// 所在表格，如`附表二`、`低压缸抽汽 第2级`
+    pub table: String,
+    pub field: &'static FieldMeta,
+    /// 各次计算的数值(内部单位)，缺失时为`NaN`
+    pub values: Vec<f64>,
+}
+
+impl CompareRow {
+    /// 第`i`次计算相对基准的变化
+    pub fn delta(&self, i: usize) -> f64 {
+        self.values[i] - self.values[0]
+    }
+
+    /// 第`i`次计算相对基准的相对变化，基准为0或缺失时为`None`
+    pub fn relative(&self, i: usize) -> Option<f64> {
+        let base = self.values[0];
+        let relative = self.delta(i) / base.abs();
+        (base.abs() > 1e-12 && relative.is_finite()).then_some(relative)
+    }
+
+    /// 各次计算中相对变化的最大幅度
+    pub fn max_relative(&self) -> f64 {
+        (1..self.values.len())
+            .filter_map(|i| self.relative(i))
+            .fold(0.0, |max, relative| max.max(relative.abs()))
+    }
+}
+
+/// 多次计算的对比结果
+#[derive(Debug, Clone, Default)]
+pub struct Comparison {
+    /// 各次计算的名称
+    pub names: Vec<String>,
+    /// 取值不同的输入参数
+    pub inputs: Vec<CompareRow>,
+    /// 全部结果字段
+    pub results: Vec<CompareRow>,
+}
+
+/// 将计算结果展开为(表格, 字段, 数值)，迭代过程只取最后一次
+fn result_values(results: &CalcResultParamters) -> Vec<(String, &'static FieldMeta, f64)> {
+    let mut values = Vec::new();
+    let mut push_rows = |table: String, rows: &'static [FieldMeta], map: &Map<String, Value>| {
+        for row in rows {
+            values.push((table.clone(), row, field_value(map, row.key)));
+        }
+    };
+    if let Some(last) = results.result1.last() {
+        push_rows(
+            String::from("热平衡计算"),
+            RESULT1_COLUMNS,
+            &field_map(last),
+        );
+    }
+    let r2 = &results.result2;
+    let map = field_map(r2);
+    push_rows(String::from("附表一"), RESULT2_TABLE1, &map);
+    push_rows(String::from("附表二"), RESULT2_TABLE2, &map);
+    let lp = r2.lfwx.len();
+    for (i, stage) in r2.lfwx.iter().enumerate() {
+        push_rows(
+            format!("低压加热器给水 第{}级", i + 1),
+            FW_COLUMNS,
+            &field_map(stage),
+        );
+    }
+    for (i, stage) in r2.hfwx.iter().enumerate() {
+        let table = format!("高压加热器给水 第{}级", i + lp + 2);
+        push_rows(table, FW_COLUMNS, &field_map(stage));
+    }
+    for (i, stage) in r2.lhes.iter().enumerate() {
+        push_rows(
+            format!("低压缸抽汽 第{}级", i + 1),
+            HES_COLUMNS,
+            &field_map(stage),
+        );
+    }
+    for (i, stage) in r2.hhes.iter().enumerate() {
+        let table = format!("高压缸抽汽 第{}级", i + lp + 2);
+        push_rows(table, HES_COLUMNS, &field_map(stage));
+    }
+    for (i, stage) in r2.rhx.iter().enumerate() {
+        push_rows(
+            format!("再热器抽汽 第{}级", i + 1),
+            RHX_COLUMNS,
+            &field_map(stage),
+        );
+    }
+    values
+}
+
+/// 以第一组计算为基准对比各次计算
+pub fn compare_runs(runs: &[CalcRun]) -> Comparison {
+    let names = runs.iter().map(|run| run.name.clone()).collect();
+
+    let params: Vec<_> = runs.iter().map(|run| field_map(&run.params)).collect();
+    let inputs = INPUT_FIELDS
+        .iter()
+        .map(|field| CompareRow {
+            table: String::from("输入参数"),
+            field,
+            values: params
+                .iter()
+                .map(|map| field_value(map, field.key))
+                .collect(),
+        })
+        .filter(|row| {
+            // 可选参数未给定时为NaN，给定与未给定之间也算作变化
+            (1..row.values.len()).any(|i| {
+                row.values[0].is_nan() != row.values[i].is_nan() || row.delta(i).abs() > 1e-12
+            })
+        })
+        .collect();
+
+    // 各次计算的级数可能不同，按(表格, 字段)对齐
+    let mut order = Vec::new();
+    let mut lookup = HashMap::new();
+    for (i, run) in runs.iter().enumerate() {
+        for (table, field, value) in result_values(&run.results) {
+            let key = (table.clone(), field.key);
+            if !lookup.contains_key(&key) {
+                order.push((table, field));
+                lookup.insert(key.clone(), vec![f64::NAN; runs.len()]);
+            }
+            lookup.get_mut(&key).unwrap()[i] = value;
+        }
+    }
+    let results = order
+        .into_iter()
+        .map(|(table, field)| {
+            let values = lookup.remove(&(table.clone(), field.key)).unwrap();
+            CompareRow {
+                table,
+                field,
+                values,
+            }
+        })
+        .collect();
+
+    Comparison {
+        names,
+        inputs,
+        results,
+    }
+}
+
+impl Comparison {
+    /// 相对变化最大的若干个结果字段，不含没有变化的字段
+    pub fn largest_changes(&self, count: usize) -> Vec<&CompareRow> {
+        let mut rows: Vec<_> = self
+            .results
+            .iter()
+            .filter(|row| row.max_relative() > 0.0)
+            .collect();
+        rows.sort_by(|a, b| b.max_relative().total_cmp(&a.max_relative()));
+        rows.truncate(count);
+        rows
+    }
+
+    /// 表头：基准之外的每次计算附带变化量与相对变化两列
+    fn headers(&self, leading: &[&str]) -> Vec<String> {
+        let mut headers: Vec<String> = leading.iter().map(|s| s.to_string()).collect();
+        for (i, name) in self.names.iter().enumerate() {
+            if i == 0 {
+                headers.push(format!("{name}(基准)"));
+            } else {
+                headers.extend([name.clone(), format!("Δ {name}"), format!("Δ% {name}")]);
+            }
+        }
+        headers
+    }
+
+    /// 一行中各次计算的数值、变化量与相对变化
+    fn value_cells(row: &CompareRow, units: &UnitSystem) -> Vec<String> {
+        let unit = row.field.unit;
+        let value = |v: f64| {
+            if v.is_nan() {
+                String::from("-")
+            } else {
+                units.format(unit, v)
+            }
+        };
+        let mut cells = vec![value(row.values[0])];
+        for i in 1..row.values.len() {
+            let delta =
+                units.to_display(unit, row.values[i]) - units.to_display(unit, row.values[0]);
+            cells.push(value(row.values[i]));
+            cells.push(if delta.is_nan() {
+                String::from("-")
+            } else {
+                format!("{delta:+.4}")
+            });
+            cells.push(
+                row.relative(i)
+                    .map_or(String::from("-"), |r| format!("{:+.3}%", r * 100.0)),
+            );
+        }
+        cells
+    }
+
+    /// 生成Markdown格式的对比报告，变化最大的结果字段加粗显示
+    pub fn to_markdown(&self, units: &UnitSystem) -> String {
+        let table_row = |cells: &[String]| format!("| {} |\n", cells.join(" | "));
+        let separator = |count: usize| format!("|{}\n", " --- |".repeat(count));
+        let mut markdown = String::from("# 计算结果对比\n\n");
+        if self.names.len() < 2 {
+            markdown.push_str("至少需要两组计算结果才能对比。\n");
+            return markdown;
+        }
+        markdown.push_str(&format!(
+            "以“{}”为基准，Δ为相对基准的变化量，Δ%为相对变化。\n\n",
+            self.names[0]
+        ));
+
+        markdown.push_str("## 输入参数差异\n\n");
+        if self.inputs.is_empty() {
+            markdown.push_str("各组输入参数相同。\n\n");
+        } else {
+            let headers = self.headers(&["参数", "单位"]);
+            markdown.push_str(&table_row(&headers));
+            markdown.push_str(&separator(headers.len()));
+            for row in &self.inputs {
+                let mut cells = vec![
+                    format!("{} ({})", row.field.label, row.field.symbol),
+                    units.label(row.field.unit).to_string(),
+                ];
+                cells.extend(Self::value_cells(row, units));
+                markdown.push_str(&table_row(&cells));
+            }
+            markdown.push('\n');
+        }
+
+        let largest = self.largest_changes(HIGHLIGHT_COUNT);
+        markdown.push_str("## 变化最大的结果\n\n");
+        if largest.is_empty() {
+            markdown.push_str("各组计算结果相同。\n\n");
+        }
+        for (rank, row) in largest.iter().enumerate() {
+            markdown.push_str(&format!(
+                "{}. {} {} ({}): {:.3}%\n",
+                rank + 1,
+                row.table,
+                row.field.label,
+                row.field.symbol,
+                row.max_relative() * 100.0
+            ));
+        }
+        markdown.push('\n');
+
+        markdown.push_str("## 全部结果\n\n");
+        let headers = self.headers(&["表格", "参数", "单位"]);
+        markdown.push_str(&table_row(&headers));
+        markdown.push_str(&separator(headers.len()));
+        for row in &self.results {
+            let highlighted = largest.iter().any(|r| std::ptr::eq(*r, row));
+            let label = format!("{} ({})", row.field.label, row.field.symbol);
+            let mut cells = vec![
+                row.table.clone(),
+                if highlighted {
+                    format!("**{label}**")
+                } else {
+                    label
+                },
+                units.label(row.field.unit).to_string(),
+            ];
+            cells.extend(Self::value_cells(row, units));
+            markdown.push_str(&table_row(&cells));
+        }
+        markdown
+    }
+
+    /// 生成CSV/TSV格式的对比表，输入参数差异在前
+    pub fn to_csv(&self, delimiter: char, units: &UnitSystem) -> String {
+        let mut content = line(&self.headers(&["表格", "字段", "名称", "单位"]), delimiter);
+        for row in self.inputs.iter().chain(&self.results) {
+            let mut cells = vec![
+                row.table.clone(),
+                row.field.key.to_string(),
+                row.field.label.to_string(),
+                units.label(row.field.unit).to_string(),
+            ];
+            cells.extend(Self::value_cells(row, units));
+            content.push_str(&line(&cells, delimiter));
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parameters::CalcResult1;
+
+    fn run(name: &str, n_hi: f64, eta_enpp: f64) -> CalcRun {
+        let mut results = CalcResultParamters::default();
+        results.result1.push(CalcResult1 {
+            eta_enpp,
+            q_r: 3.0,
+            ..CalcResult1::default()
+        });
+        CalcRun {
+            name: name.to_string(),
+            params: CalcInputParameters {
+                n_hi,
+                ..CalcInputParameters::from_default()
+            },
+            results,
+        }
+    }
+
+    #[test]
+    fn test_compare_runs() {
+        let runs = [run("基准", 0.82, 0.30), run("提高η_hi", 0.84, 0.33)];
+        let comparison = compare_runs(&runs);
+        assert_eq!(comparison.inputs.len(), 1);
+        assert_eq!(comparison.inputs[0].field.key, "n_hi");
+
+        let largest = comparison.largest_changes(HIGHLIGHT_COUNT);
+        assert_eq!(largest.len(), 1);
+        assert_eq!(largest[0].field.key, "eta_enpp");
+        assert!((largest[0].delta(1) - 0.03).abs() < 1e-12);
+        assert!((largest[0].relative(1).unwrap() - 0.1).abs() < 1e-12);
+
+        let units = UnitSystem::percent();
+        let markdown = comparison.to_markdown(&units);
+        assert!(markdown.contains("**核电厂效率 (η_eNPP)**"));
+        assert!(markdown.contains("| 30 | 33 | +3.0000 | +10.000% |"));
+        let csv = comparison.to_csv(',', &units);
+        assert!(
+            csv.starts_with("表格,字段,名称,单位,基准(基准),提高η_hi,Δ 提高η_hi,Δ% 提高η_hi\n")
+        );
+        assert!(csv.contains("输入参数,n_hi,高压缸内效率,%,82,84,+2.0000,+2.439%\n"));
+    }
+
+    #[test]
+    fn test_run_file() {
+        let dir = std::env::temp_dir().join(format!("npp-thc-runs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("run.json");
+        run("方案一", 0.82, 0.3).save_to_file(&path).unwrap();
+        let loaded = CalcRun::from_file(&path).unwrap();
+        assert_eq!(loaded.name, "方案一");
+        assert_eq!(loaded.results.result1[0].eta_enpp, 0.3);
+
+        // results.json 需要同目录下的 parameters.json
+        let results = dir.join("results.json");
+        std::fs::write(&results, serde_json::to_string(&loaded.results).unwrap()).unwrap();
+        assert!(CalcRun::from_file(&results).is_err());
+        std::fs::write(
+            dir.join("parameters.json"),
+            serde_json::to_string(&loaded.params).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(CalcRun::from_file(&results).unwrap().params.n_hi, 0.82);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,443 @@
+//! 冷凝器详细模型
+//!
+//! 简化模型直接给定冷却水温升`dt_sw`与传热端差`dt`，`t_cd = t_sw1 + dt_sw + dt`。
+//! 详细模型按HEI标准的形式计算总传热系数 `U = C√v·F_m·F_t·F_c`
+//! (管材、进水温度与清洁系数修正)，由冷凝器热负荷`Q`与冷却水流量`W`得到
+//! - 冷却水温升 `dt_sw = Q/(W·c_p)`；
+//! - 传热端差 `dt = dt_sw/(e^NTU - 1)`，其中 `NTU = U·A/(W·c_p)`；
+//!
+//! 进而得到凝结水饱和温度与背压`p_cd`。热负荷又取决于背压，
+//! 因此与全厂热平衡交替迭代至热负荷收敛。
+use std::fmt::Display;
+
+use seuif97::*;
+
+use crate::Calculator;
+use crate::feed_pump;
+use crate::parameters::CalcInputParameters;
+use crate::units::{Unit, UnitSystem};
+
+/// 外径25mm冷凝管的HEI基本传热系数常数，W/(m²·K·(m/s)^0.5)
+const BASE_COEFFICIENT: f64 = 2750.0;
+/// 冷却水比热容，kJ/(kg·K)
+const CP_WATER: f64 = 4.18;
+/// 与全厂热平衡交替迭代的最大次数
+pub const MAX_ITERATIONS: usize = 30;
+/// 热负荷的相对收敛容差
+const TOLERANCE: f64 = 1e-5;
+
+/// 冷凝管材料
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TubeMaterial {
+    AdmiraltyBrass,
+    CopperNickel9010,
+    CopperNickel7030,
+    StainlessSteel,
+    /// 海水冷却的核电厂常用钛管
+    #[default]
+    Titanium,
+}
+
+impl TubeMaterial {
+    pub const ALL: [Self; 5] = [
+        Self::AdmiraltyBrass,
+        Self::CopperNickel9010,
+        Self::CopperNickel7030,
+        Self::StainlessSteel,
+        Self::Titanium,
+    ];
+
+    /// 管材及壁厚修正系数F_m(HEI标准，常用壁厚)
+    pub fn factor(&self) -> f64 {
+        match self {
+            Self::AdmiraltyBrass => 1.0,
+            Self::CopperNickel9010 => 0.94,
+            Self::CopperNickel7030 => 0.88,
+            Self::StainlessSteel => 0.83,
+            Self::Titanium => 0.85,
+        }
+    }
+}
+
+impl Display for TubeMaterial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::AdmiraltyBrass => "海军黄铜",
+            Self::CopperNickel9010 => "B10铜镍合金",
+            Self::CopperNickel7030 => "B30铜镍合金",
+            Self::StainlessSteel => "不锈钢",
+            Self::Titanium => "钛",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 进水温度修正系数F_t，取HEI修正曲线在5 ~ 35℃内的线性近似
+fn temperature_factor(t_sw1: f64) -> f64 {
+    1.0 + 0.0063 * (t_sw1.clamp(5.0, 35.0) - 21.1)
+}
+
+/// 冷凝器的结构与运行参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CondenserDesign {
+    /// 传热面积(m²)
+    pub area: f64,
+    pub material: TubeMaterial,
+    /// 设计冷却水流量下的管内流速(m/s)
+    pub velocity: f64,
+    /// 清洁系数F_c
+    pub cleanliness: f64,
+    /// 冷却水流量(kg/s)
+    pub flow: f64,
+}
+
+impl Default for CondenserDesign {
+    /// 与简化模型默认参数(温升7℃、端差5℃)大致相当的百万千瓦级冷凝器
+    fn default() -> Self {
+        Self {
+            area: 80000.0,
+            material: TubeMaterial::default(),
+            velocity: 2.2,
+            cleanliness: 0.85,
+            flow: 68000.0,
+        }
+    }
+}
+
+/// 冷凝器的传热计算结果
+#[derive(Debug, Clone, Copy)]
+pub struct Performance {
+    /// 冷却水流量(kg/s)
+    pub flow: f64,
+    /// 管内流速(m/s)
+    pub velocity: f64,
+    /// 总传热系数(kW/(m²·K))
+    pub u: f64,
+    /// 传热单元数
+    pub ntu: f64,
+    pub dt_sw: f64,
+    pub dt: f64,
+    pub t_cd: f64,
+    pub p_cd: f64,
+}
+
+impl CondenserDesign {
+    fn validate(&self) -> Result<(), String> {
+        if !(self.area > 0.0 && self.velocity > 0.0 && self.flow > 0.0) {
+            return Err(String::from("传热面积、管内流速与冷却水流量应为正数"));
+        }
+        if !(self.cleanliness > 0.0 && self.cleanliness <= 1.0) {
+            return Err(String::from("清洁系数应在(0, 1]内"));
+        }
+        Ok(())
+    }
+
+    /// 给定冷却水流量、进口温度与热负荷(MW)下的传热计算，管内流速正比于流量
+    pub fn performance(&self, flow: f64, t_sw1: f64, duty: f64) -> Performance {
+        let velocity = self.velocity * flow / self.flow;
+        let u = BASE_COEFFICIENT
+            * velocity.sqrt()
+            * self.material.factor()
+            * temperature_factor(t_sw1)
+            * self.cleanliness
+            / 1000.0;
+        let capacity = flow * CP_WATER;
+        let ntu = u * self.area / capacity;
+        let dt_sw = duty * 1000.0 / capacity;
+        let dt = dt_sw / ntu.exp_m1();
+        let t_cd = t_sw1 + dt_sw + dt;
+        Performance {
+            flow,
+            velocity,
+            u,
+            ntu,
+            dt_sw,
+            dt,
+            t_cd,
+            p_cd: tx(t_cd, 0.0, OP),
+        }
+    }
+
+    /// 达到给定凝结水温度所需的冷却水流量(kg/s)，在设计流量的1% ~ 100倍内无法达到时返回`None`
+    pub fn required_flow(&self, t_sw1: f64, duty: f64, t_cd: f64) -> Option<f64> {
+        // 凝结水温度随流量增大单调下降，按对数二分
+        let t_cd_at = |flow: f64| self.performance(flow, t_sw1, duty).t_cd;
+        let (mut low, mut high) = (self.flow / 100.0, self.flow * 100.0);
+        if t_cd_at(high) > t_cd || t_cd_at(low) < t_cd {
+            return None;
+        }
+        for _ in 0..100 {
+            let middle = (low * high).sqrt();
+            if t_cd_at(middle) > t_cd {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        Some((low * high).sqrt())
+    }
+}
+
+/// 一次全厂热平衡计算中与冷凝器有关的结果
+#[derive(Debug, Clone, Copy)]
+pub struct PlantState {
+    pub dt_sw: f64,
+    pub dt: f64,
+    pub t_cd: f64,
+    pub p_cd: f64,
+    /// 冷凝器热负荷(MW)
+    pub duty: f64,
+    pub eta_enpp: f64,
+    /// 反应堆热功率(GW)
+    pub q_r: f64,
+    pub d_s: f64,
+}
+
+impl PlantState {
+    /// 热负荷包括低压缸排汽、逐级自流至冷凝器的低压加热器疏水及排入冷凝器的给水泵汽轮机排汽
+    fn new(calculator: &Calculator) -> Result<Self, String> {
+        let r1 = calculator
+            .results
+            .result1
+            .last()
+            .ok_or_else(|| String::from("计算结果为空"))?;
+        let r2 = &calculator.results.result2;
+        let g_les = r1.g_les1 + r1.g_les2 + r1.g_les3 + r1.g_les4;
+        let (_, to_cd, _) = feed_pump::shares(&calculator.params);
+        let duty = ((r1.g_slp - g_les) * (r2.h_lz - r2.h_cd)
+            + to_cd * r1.g_sfwp * (r2.h_fwptz - r2.h_cd)
+            + r2.g_dcd * (r2.h_dcd - r2.h_cd))
+            / 1000.0;
+        if !(duty.is_finite() && duty > 0.0 && r1.eta_enpp.is_finite()) {
+            return Err(String::from("计算结果不是有限值"));
+        }
+        Ok(Self {
+            dt_sw: r2.dt_sw,
+            dt: r2.dt,
+            t_cd: r2.t_cd,
+            p_cd: r2.p_cd,
+            duty,
+            eta_enpp: r1.eta_enpp,
+            q_r: r1.q_r,
+            d_s: r1.d_s,
+        })
+    }
+}
+
+/// 详细模型的计算结果
+#[derive(Debug, Clone)]
+pub struct CondenserReport {
+    pub design: CondenserDesign,
+    pub t_sw1: f64,
+    /// 简化模型(输入参数给定的温升与端差)的结果
+    pub simple: PlantState,
+    /// 详细模型的结果
+    pub detailed: PlantState,
+    pub performance: Performance,
+    /// 达到简化模型凝结水温度所需的冷却水流量(kg/s)
+    pub required_flow: Option<f64>,
+    pub iterations: usize,
+    pub converged: bool,
+    /// 代入详细模型温升与端差后的输入参数
+    pub params: CalcInputParameters,
+}
+
+/// 以详细模型计算冷凝器，与全厂热平衡交替迭代至热负荷收敛
+pub fn detailed_condenser(
+    params: &CalcInputParameters,
+    design: &CondenserDesign,
+) -> Result<CondenserReport, String> {
+    design.validate()?;
+    let calculator =
+        Calculator::solve(params.clone()).map_err(|error| format!("简化模型计算失败: {error}"))?;
+    let simple = PlantState::new(&calculator)?;
+
+    let mut duty = simple.duty;
+    for iteration in 1..=MAX_ITERATIONS {
+        let performance = design.performance(design.flow, params.t_sw1, duty);
+        if !performance.t_cd.is_finite() {
+            return Err(String::from("凝结水温度不是有限值"));
+        }
+        let adjusted = CalcInputParameters {
+            dt_sw: performance.dt_sw,
+            dt: performance.dt,
+            ..params.clone()
+        };
+        let calculator = Calculator::solve(adjusted.clone())?;
+        let detailed = PlantState::new(&calculator)?;
+        let converged = ((detailed.duty - duty) / duty).abs() < TOLERANCE;
+        if converged || iteration == MAX_ITERATIONS {
+            return Ok(CondenserReport {
+                design: *design,
+                t_sw1: params.t_sw1,
+                simple,
+                detailed,
+                performance,
+                required_flow: design.required_flow(params.t_sw1, detailed.duty, simple.t_cd),
+                iterations: iteration,
+                converged,
+                params: adjusted,
+            });
+        }
+        duty = detailed.duty;
+    }
+    unreachable!("最后一次迭代总会返回")
+}
+
+impl CondenserReport {
+    /// 简化模型与详细模型的对比，(名称, 简化模型, 详细模型)
+    pub fn comparison_rows(&self, units: &UnitSystem) -> Vec<(String, String, String)> {
+        let row = |name: &str, unit: Unit, value: fn(&PlantState) -> f64| {
+            let name = match units.label(unit) {
+                "" => name.to_string(),
+                label => format!("{name}({label})"),
+            };
+            (
+                name,
+                units.format(unit, value(&self.simple)),
+                units.format(unit, value(&self.detailed)),
+            )
+        };
+        vec![
+            row("冷却水温升ΔT_sw", Unit::DeltaCelsius, |state| {
+                state.dt_sw
+            }),
+            row("传热端差δT", Unit::DeltaCelsius, |state| state.dt),
+            row("凝结水饱和温度T_cd", Unit::Celsius, |state| {
+                state.t_cd
+            }),
+            row("冷凝器压力p_cd", Unit::MPa, |state| state.p_cd),
+            row("冷凝器热负荷", Unit::MW, |state| state.duty),
+            row("核电厂效率η_eNPP", Unit::Fraction, |state| {
+                state.eta_enpp
+            }),
+            row("反应堆热功率Q_R", Unit::GW, |state| state.q_r),
+            row("蒸汽产量D_s", Unit::KgPerS, |state| state.d_s),
+        ]
+    }
+
+    /// 详细模型的传热计算结果，(名称, 数值)
+    pub fn heat_transfer_rows(&self, units: &UnitSystem) -> Vec<(String, String)> {
+        let performance = &self.performance;
+        let flow = |value: f64| units.format(Unit::KgPerS, value);
+        let flow_label = units.label(Unit::KgPerS);
+        vec![
+            (
+                String::from("管材"),
+                format!(
+                    "{} (F_m = {})",
+                    self.design.material,
+                    self.design.material.factor()
+                ),
+            ),
+            (
+                String::from("进水温度修正系数F_t"),
+                format!("{:.4}", temperature_factor(self.t_sw1)),
+            ),
+            (
+                String::from("总传热系数U(kW/(m²·K))"),
+                format!("{:.4}", performance.u),
+            ),
+            (
+                String::from("传热单元数NTU"),
+                format!("{:.4}", performance.ntu),
+            ),
+            (
+                String::from("管内流速(m/s)"),
+                format!("{:.3}", performance.velocity),
+            ),
+            (format!("冷却水流量({flow_label})"), flow(performance.flow)),
+            (
+                format!("达到简化模型背压所需冷却水流量({flow_label})"),
+                self.required_flow
+                    .map_or_else(|| String::from("无法达到"), flow),
+            ),
+            (
+                String::from("迭代次数"),
+                if self.converged {
+                    self.iterations.to_string()
+                } else {
+                    format!("{} (未收敛)", self.iterations)
+                },
+            ),
+        ]
+    }
+
+    /// 生成Markdown格式的报告
+    pub fn to_markdown(&self, units: &UnitSystem) -> String {
+        let table_row = |cells: &[String]| format!("| {} |\n", cells.join(" | "));
+        let mut markdown = String::from("# 冷凝器详细模型\n\n");
+        markdown.push_str(&format!(
+            "传热面积 {} m²，设计流速 {} m/s，清洁系数 {}。\n\n",
+            self.design.area, self.design.velocity, self.design.cleanliness
+        ));
+        markdown.push_str("## 传热计算\n\n| 项目 | 数值 |\n| --- | --- |\n");
+        for (name, value) in self.heat_transfer_rows(units) {
+            markdown.push_str(&table_row(&[name, value]));
+        }
+        markdown.push_str(
+            "\n## 与简化模型对比\n\n| 参数 | 简化模型 | 详细模型 |\n| --- | --- | --- |\n",
+        );
+        for (name, simple, detailed) in self.comparison_rows(units) {
+            markdown.push_str(&table_row(&[name, simple, detailed]));
+        }
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_performance() {
+        let design = CondenserDesign::default();
+        let performance = design.performance(design.flow, 24.0, 2000.0);
+        assert!((performance.velocity - design.velocity).abs() < 1e-12);
+        assert!((performance.t_cd - (24.0 + performance.dt_sw + performance.dt)).abs() < 1e-12);
+        // 流量增大时温升与凝结水温度下降
+        let larger = design.performance(design.flow * 1.2, 24.0, 2000.0);
+        assert!(larger.dt_sw < performance.dt_sw);
+        assert!(larger.t_cd < performance.t_cd);
+        // 清洁系数降低时端差增大
+        let fouled = CondenserDesign {
+            cleanliness: 0.7,
+            ..design
+        }
+        .performance(design.flow, 24.0, 2000.0);
+        assert!(fouled.dt > performance.dt);
+
+        let flow = design
+            .required_flow(24.0, 2000.0, performance.t_cd)
+            .unwrap();
+        assert!((flow - design.flow).abs() / design.flow < 1e-6);
+        assert!(design.required_flow(24.0, 2000.0, 24.5).is_none());
+    }
+
+    #[test]
+    fn test_detailed_condenser() {
+        let params = CalcInputParameters::from_default();
+        let report = detailed_condenser(&params, &CondenserDesign::default()).unwrap();
+        assert!(report.converged);
+        // 默认冷凝器与简化模型的默认温升、端差相当
+        assert!((report.detailed.dt_sw - params.dt_sw).abs() < 1.0);
+        assert!((report.detailed.dt - params.dt).abs() < 1.5);
+        assert!((report.detailed.dt_sw - report.performance.dt_sw).abs() < 1e-9);
+        // 收敛后热负荷与传热计算一致
+        let check =
+            report
+                .design
+                .performance(report.design.flow, params.t_sw1, report.detailed.duty);
+        assert!((check.t_cd - report.detailed.t_cd).abs() < 1e-3);
+
+        let units = UnitSystem::default();
+        assert_eq!(report.comparison_rows(&units).len(), 8);
+        assert!(report.to_markdown(&units).contains("| --- |"));
+
+        let invalid = CondenserDesign {
+            cleanliness: 1.5,
+            ..CondenserDesign::default()
+        };
+        assert!(detailed_condenser(&params, &invalid).is_err());
+    }
+}
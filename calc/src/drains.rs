@@ -0,0 +1,373 @@
+//! 给水加热器疏水方式
+//!
+//! 每台表面式给水加热器的疏水可：
+//! - 逐级自流：以汽侧饱和水状态流入下一级加热器，最低一级流入冷凝器(低压)或除氧器(高压)；
+//! - 疏水泵打入下游：由疏水泵升压后汇入本级加热器出口的给水，不再流入下一级；
+//! - 疏水冷却器：冷却至进口给水温度加疏水冷却器端差`θ_dc`后逐级自流。
+//!
+//! 加热器的能量平衡(疏水出口比焓为`h_d`，进入本级的疏水为`G_k`、`h_k`)：
+//! `G_fw(h_fwxo - h_fwxi) = η_h[G_es(h_es - h_d) + ΣG_k(h_k - h_d)]`；
+//! 疏水泵打入下游时以加热器与汇合点为控制体，`h_d`取出口给水比焓，并计入疏水泵对疏水的做功。
+//! 疏水打入下游后各级管侧给水流量不同，因此与抽汽量交替迭代至收敛。
+use std::fmt::Display;
+
+use seuif97::*;
+
+/// 管侧流量与疏水泵流量交替迭代的最大次数
+const MAX_ITERATIONS: usize = 100;
+/// 疏水泵流量的相对收敛容差
+const TOLERANCE: f64 = 1e-10;
+
+/// 加热器疏水方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrainMode {
+    /// 逐级自流
+    Cascade,
+    /// 疏水泵打入下游给水
+    PumpedForward,
+    /// 经疏水冷却器冷却后逐级自流
+    Cooled,
+}
+
+impl DrainMode {
+    /// 由输入参数中的数值编码确定：0为逐级自流，1为疏水泵打入下游，2为疏水冷却器，
+    /// 其他取值在计算前由[`check_input_choices`](crate::fields::check_input_choices)拒绝
+    pub fn from_code(code: f64) -> Self {
+        if code == 1.0 {
+            Self::PumpedForward
+        } else if code == 2.0 {
+            Self::Cooled
+        } else {
+            Self::Cascade
+        }
+    }
+}
+
+impl Display for DrainMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cascade => write!(f, "逐级自流"),
+            Self::PumpedForward => write!(f, "疏水泵打入下游"),
+            Self::Cooled => write!(f, "疏水冷却器"),
+        }
+    }
+}
+
+/// 一级表面式给水加热器
+#[derive(Debug, Clone, Copy)]
+pub struct Stage {
+    /// 进口给水比焓(kJ/kg)
+    pub h_in: f64,
+    /// 进口给水温度(℃)
+    pub t_in: f64,
+    /// 出口给水比焓(kJ/kg)
+    pub h_out: f64,
+    /// 出口给水压力(MPa)，疏水泵出口压力
+    pub p_out: f64,
+    /// 抽汽比焓(kJ/kg)
+    pub h_es: f64,
+    /// 汽侧压力(MPa)
+    pub p_shell: f64,
+    /// 汽侧饱和疏水比焓(kJ/kg)
+    pub h_sat: f64,
+    pub mode: DrainMode,
+    /// 疏水冷却器端差(℃)
+    pub theta_dc: f64,
+    /// 外部流入本级的疏水流量(kg/s)与比焓(kJ/kg)，如再热器疏水
+    pub external: (f64, f64),
+}
+
+impl Stage {
+    /// 离开汽侧的疏水比焓
+    fn h_drain(&self) -> f64 {
+        match self.mode {
+            DrainMode::Cascade | DrainMode::PumpedForward => self.h_sat,
+            DrainMode::Cooled => {
+                let t_sat = px(self.p_shell, 0.0, OT);
+                let t_dc = self.t_in + self.theta_dc;
+                if t_dc < t_sat {
+                    pt(self.p_shell, t_dc, OH)
+                } else {
+                    self.h_sat
+                }
+            }
+        }
+    }
+}
+
+/// 加热器管侧给水流量的已知条件
+#[derive(Debug, Clone, Copy)]
+pub enum Flow {
+    /// 进入最低一级的流量(kg/s)，如低压加热器的凝结水量
+    Inlet(f64),
+    /// 离开最高一级并汇合所有打入下游疏水后的流量(kg/s)，如高压加热器的给水流量
+    Outlet(f64),
+}
+
+/// 一级加热器的计算结果
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StageResult {
+    /// 抽汽量(kg/s)
+    pub g_es: f64,
+    /// 管侧给水流量(kg/s)
+    pub g_fw: f64,
+    /// 离开汽侧的疏水流量(kg/s)
+    pub g_d: f64,
+    /// 离开汽侧的疏水比焓(kJ/kg)
+    pub h_d: f64,
+    /// 疏水泵打入下游的流量(kg/s)
+    pub pumped: f64,
+}
+
+/// 一组加热器的计算结果
+#[derive(Debug, Clone, Default)]
+pub struct TrainResult {
+    /// 各级结果，由低到高
+    pub stages: Vec<StageResult>,
+    /// 自流出最低一级的疏水流量(kg/s)与比焓(kJ/kg)
+    pub sink: (f64, f64),
+    /// 疏水泵打入下游的总流量(kg/s)
+    pub pumped: f64,
+    /// 疏水泵耗电功率(kW)
+    pub n_dp: f64,
+}
+
+/// 计算一组加热器的抽汽量与疏水流量
+///
+/// # Arguments
+///
+/// * `stages` - 各级加热器，由低到高
+///
+/// * `flow` - 管侧给水流量的已知条件
+///
+/// * `eta_h` - 加热器效率
+///
+/// * `eta_pump` - 疏水泵效率
+///
+/// * `eta_motor` - 疏水泵电动机效率
+///
+/// 管侧流量与疏水泵流量迭代`MAX_ITERATIONS`次仍未收敛时返回错误
+pub fn solve(
+    stages: &[Stage],
+    flow: Flow,
+    eta_h: f64,
+    eta_pump: f64,
+    eta_motor: f64,
+) -> Result<TrainResult, String> {
+    // 疏水泵对每千克疏水的做功(kJ/kg)
+    let pump_work: Vec<f64> = stages
+        .iter()
+        .map(|stage| match stage.mode {
+            DrainMode::PumpedForward => {
+                let rho = px(stage.p_shell, 0.0, OD);
+                (stage.p_out - stage.p_shell).max(0.0) * 1000.0 / rho / eta_pump
+            }
+            _ => 0.0,
+        })
+        .collect();
+
+    let mut pumped = vec![0.0; stages.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let flows: Vec<f64> = (0..stages.len())
+            .map(|i| match flow {
+                Flow::Inlet(g) => g + pumped[..i].iter().sum::<f64>(),
+                Flow::Outlet(g) => g - pumped[i..].iter().sum::<f64>(),
+            })
+            .collect();
+
+        // 由最高一级向下计算，上一级自流的疏水进入下一级
+        let mut cascade = (0.0, 0.0);
+        let mut results = vec![StageResult::default(); stages.len()];
+        for (i, stage) in stages.iter().enumerate().rev() {
+            let inflows = [cascade, stage.external];
+            let g_in: f64 = inflows.iter().map(|(g, _)| g).sum();
+            let h_d = stage.h_drain();
+            let h_ref = match stage.mode {
+                DrainMode::PumpedForward => stage.h_out,
+                _ => h_d,
+            };
+            let q_in: f64 = inflows.iter().map(|(g, h)| g * (h - h_ref)).sum();
+            let g_es = (flows[i] * (stage.h_out - stage.h_in) - eta_h * q_in - g_in * pump_work[i])
+                / (eta_h * (stage.h_es - h_ref) + pump_work[i]);
+            let g_d = g_es + g_in;
+            let pumped = if stage.mode == DrainMode::PumpedForward {
+                cascade = (0.0, 0.0);
+                g_d
+            } else {
+                cascade = (g_d, h_d);
+                0.0
+            };
+            results[i] = StageResult {
+                g_es,
+                g_fw: flows[i],
+                g_d,
+                h_d,
+                pumped,
+            };
+        }
+
+        let scale = flows.iter().fold(1.0_f64, |scale, g| scale.max(g.abs()));
+        let converged = results
+            .iter()
+            .zip(&pumped)
+            .all(|(stage, previous)| (stage.pumped - previous).abs() <= TOLERANCE * scale);
+        pumped = results.iter().map(|stage| stage.pumped).collect();
+        if converged {
+            return Ok(TrainResult {
+                sink: cascade,
+                pumped: pumped.iter().sum(),
+                n_dp: results
+                    .iter()
+                    .zip(&pump_work)
+                    .map(|(stage, work)| stage.pumped * work / eta_motor)
+                    .sum(),
+                stages: results,
+            });
+        }
+    }
+    Err(format!("加热器疏水流量迭代{MAX_ITERATIONS}次仍未收敛"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Calculator;
+    use crate::parameters::CalcInputParameters;
+
+    /// 两级低压加热器，温度与比焓取自默认工况的量级
+    fn stages(modes: [DrainMode; 2]) -> Vec<Stage> {
+        let stage = |t_in: f64, t_out: f64, p_es: f64, mode| {
+            let p_shell = p_es * 0.96;
+            Stage {
+                h_in: pt(1.5, t_in, OH),
+                t_in,
+                h_out: pt(1.5, t_out, OH),
+                p_out: 1.4,
+                h_es: px(p_es, 0.97, OH),
+                p_shell,
+                h_sat: px(p_shell, 0.0, OH),
+                mode,
+                theta_dc: 5.6,
+                external: (0.0, 0.0),
+            }
+        };
+        vec![
+            stage(36.0, 70.0, 0.035, modes[0]),
+            stage(70.0, 105.0, 0.13, modes[1]),
+        ]
+    }
+
+    #[test]
+    fn test_cascade() {
+        let stages = stages([DrainMode::Cascade; 2]);
+        let result = solve(&stages, Flow::Inlet(1000.0), 0.98, 0.75, 0.95).unwrap();
+        let [low, high] = [result.stages[0], result.stages[1]];
+        // 逐级自流：全部抽汽最终自流出最低一级
+        assert!((result.sink.0 - low.g_es - high.g_es).abs() < 1e-9);
+        assert_eq!(result.sink.1, stages[0].h_sat);
+        assert_eq!(result.pumped, 0.0);
+        // 第二级的能量平衡
+        let q = 1000.0 * (stages[1].h_out - stages[1].h_in);
+        assert!((0.98 * high.g_es * (stages[1].h_es - stages[1].h_sat) - q).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pumped_forward_and_cooler() {
+        let cascade = solve(
+            &stages([DrainMode::Cascade; 2]),
+            Flow::Inlet(1000.0),
+            0.98,
+            0.75,
+            0.95,
+        )
+        .unwrap();
+        let pumped = solve(
+            &stages([DrainMode::PumpedForward, DrainMode::Cascade]),
+            Flow::Inlet(1000.0),
+            0.98,
+            0.75,
+            0.95,
+        )
+        .unwrap();
+        // 最低一级疏水打入下游：没有疏水流出，第二级管侧流量增加
+        assert_eq!(pumped.sink.0, 0.0);
+        assert!((pumped.pumped - pumped.stages[0].g_d).abs() < 1e-12);
+        assert!((pumped.stages[1].g_fw - 1000.0 - pumped.pumped).abs() < 1e-6);
+        assert!(pumped.stages[1].g_es > cascade.stages[1].g_es);
+        assert!(pumped.n_dp > 0.0);
+
+        // 疏水冷却器回收疏水热量，抽汽量减少
+        let cooled = solve(
+            &stages([DrainMode::Cascade, DrainMode::Cooled]),
+            Flow::Inlet(1000.0),
+            0.98,
+            0.75,
+            0.95,
+        )
+        .unwrap();
+        assert!(cooled.stages[1].h_d < cascade.stages[1].h_d);
+        assert!(cooled.stages[1].g_es < cascade.stages[1].g_es);
+
+        // 已知出口流量时，打入下游的疏水不经过本级
+        let outlet = solve(
+            &stages([DrainMode::Cascade, DrainMode::PumpedForward]),
+            Flow::Outlet(1000.0),
+            0.98,
+            0.75,
+            0.95,
+        )
+        .unwrap();
+        assert!((outlet.stages[1].g_fw + outlet.pumped - 1000.0).abs() < 1e-6);
+        assert!((outlet.stages[0].g_fw - outlet.stages[1].g_fw).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_not_converged() {
+        // 抽汽焓降远小于给水焓升时，打入下游的疏水量随迭代发散
+        let mut stages = stages([DrainMode::Cascade, DrainMode::PumpedForward]);
+        stages[1].h_es = stages[1].h_out + 20.0;
+        assert!(solve(&stages, Flow::Outlet(1000.0), 0.98, 0.75, 0.95).is_err());
+    }
+
+    #[test]
+    fn test_plant_drain_modes() {
+        let solve = |code: f64| {
+            let default = CalcInputParameters::from_default();
+            let params = CalcInputParameters {
+                drain_1: code,
+                drain_2: code,
+                drain_3: code,
+                drain_4: code,
+                drain_6: code,
+                drain_7: code,
+                ..default
+            };
+            crate::Calculator::solve(params).unwrap()
+        };
+        let cascade = solve(0.0);
+        let pumped = solve(1.0);
+        let cooled = solve(2.0);
+        let eta = |calculator: &Calculator| calculator.results.result1.last().unwrap().eta_enpp;
+        // 逐级自流时低压加热器疏水全部流入冷凝器，没有疏水泵
+        let r1 = cascade.results.result1.last().unwrap();
+        let r2 = &cascade.results.result2;
+        let g_les = r1.g_les1 + r1.g_les2 + r1.g_les3 + r1.g_les4;
+        assert!((r2.g_dcd - g_les).abs() < 1e-6);
+        assert_eq!(r2.n_dp, 0.0);
+        // 疏水打入下游与疏水冷却器均减少冷源损失
+        assert_eq!(pumped.results.result2.g_dcd, 0.0);
+        assert!(pumped.results.result2.n_dp > 0.0);
+        assert!(eta(&pumped) > eta(&cascade));
+        assert!(eta(&cooled) > eta(&cascade));
+        assert!(cooled.results.result2.h_dcd < r2.h_dcd);
+
+        // 不是有效选项的编码不再按逐级自流计算
+        for drain_3 in [3.0, 1.5, -1.0] {
+            let params = CalcInputParameters {
+                drain_3,
+                ..CalcInputParameters::from_default()
+            };
+            assert!(crate::Calculator::solve(params).is_err(), "{drain_3}");
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! 计算结果导出
+pub mod csv;
+pub mod html;
+mod svg;
+pub mod tex;
+pub mod xlsx;
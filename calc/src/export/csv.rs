@@ -0,0 +1,214 @@
+//! CSV/TSV表格导出
+//!
+//! 每张表单独输出为一个文件，表头包含中文名称、符号与单位，便于在电子表格中进行后处理。
+use serde::Serialize;
+
+use crate::fields::{
+    FW_COLUMNS, FieldMeta, HES_COLUMNS, RESULT1_COLUMNS, RESULT2_TABLE1, RESULT2_TABLE2,
+    RHX_COLUMNS, field_map, field_value,
+};
+use crate::parameters::CalcResultParamters;
+use crate::units::UnitSystem;
+
+/// 导出的表格
+pub struct CsvTable {
+    /// 文件名（不含扩展名）
+    pub name: &'static str,
+    /// 文件内容
+    pub content: String,
+}
+
+/// 按分隔符转义单元格内容
+fn cell(text: &str, delimiter: char) -> String {
+    if text.contains(delimiter) || text.contains('"') || text.contains('\n') {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// 表头：名称 符号 (单位)
+fn header(row: &FieldMeta, units: &UnitSystem) -> String {
+    let unit = units.label(row.unit);
+    if unit.is_empty() {
+        format!("{} {}", row.label, row.symbol)
+    } else {
+        format!("{} {} ({})", row.label, row.symbol, unit)
+    }
+}
+
+/// 按分隔符拼接一行
+pub(crate) fn line(cells: &[String], delimiter: char) -> String {
+    let mut line = cells
+        .iter()
+        .map(|c| cell(c, delimiter))
+        .collect::<Vec<_>>()
+        .join(&delimiter.to_string());
+    line.push('\n');
+    line
+}
+
+/// 横向表格，每一行为一组数据
+fn row_table<T: Serialize>(
+    first_column: &str,
+    columns: &[FieldMeta],
+    rows: &[T],
+    row_name: impl Fn(usize) -> String,
+    delimiter: char,
+    units: &UnitSystem,
+) -> String {
+    let mut headers = vec![first_column.to_string()];
+    headers.extend(columns.iter().map(|column| header(column, units)));
+    let mut content = line(&headers, delimiter);
+    for (i, item) in rows.iter().enumerate() {
+        let map = field_map(item);
+        let mut cells = vec![row_name(i)];
+        cells.extend(columns.iter().map(|column| {
+            units
+                .to_display(column.unit, field_value(&map, column.key))
+                .to_string()
+        }));
+        content.push_str(&line(&cells, delimiter));
+    }
+    content
+}
+
+/// 将计算结果整理为多张表格
+///
+/// # Arguments
+///
+/// * `results` - 计算结果
+///
+/// * `delimiter` - 分隔符，`,`为CSV，`\t`为TSV
+///
+/// * `units` - 显示单位制
+pub fn result_tables(
+    results: &CalcResultParamters,
+    delimiter: char,
+    units: &UnitSystem,
+) -> Vec<CsvTable> {
+    let r2 = &results.result2;
+    let lp_name = |i: usize| format!("第{}级", i + 1);
+    let hp_name = |i: usize| format!("第{}级", i + r2.lfwx.len() + 2);
+
+    let mut scalars = line(
+        &["附表", "序号", "字段", "名称", "符号", "单位", "数值"].map(String::from),
+        delimiter,
+    );
+    let values = field_map(r2);
+    for (table, rows) in [("附表一", RESULT2_TABLE1), ("附表二", RESULT2_TABLE2)] {
+        for row in rows {
+            scalars.push_str(&line(
+                &[
+                    table.to_string(),
+                    row.no.to_string(),
+                    row.key.to_string(),
+                    row.label.to_string(),
+                    row.symbol.to_string(),
+                    units.label(row.unit).to_string(),
+                    units
+                        .to_display(row.unit, field_value(&values, row.key))
+                        .to_string(),
+                ],
+                delimiter,
+            ));
+        }
+    }
+
+    vec![
+        CsvTable {
+            name: "result1",
+            content: row_table(
+                "迭代次数",
+                RESULT1_COLUMNS,
+                &results.result1,
+                |i| (i + 1).to_string(),
+                delimiter,
+                units,
+            ),
+        },
+        CsvTable {
+            name: "result2",
+            content: scalars,
+        },
+        CsvTable {
+            name: "result2_lfwx",
+            content: row_table(
+                "低压加热器",
+                FW_COLUMNS,
+                &r2.lfwx,
+                lp_name,
+                delimiter,
+                units,
+            ),
+        },
+        CsvTable {
+            name: "result2_hfwx",
+            content: row_table(
+                "高压加热器",
+                FW_COLUMNS,
+                &r2.hfwx,
+                hp_name,
+                delimiter,
+                units,
+            ),
+        },
+        CsvTable {
+            name: "result2_lhes",
+            content: row_table(
+                "低压缸抽汽",
+                HES_COLUMNS,
+                &r2.lhes,
+                lp_name,
+                delimiter,
+                units,
+            ),
+        },
+        CsvTable {
+            name: "result2_hhes",
+            content: row_table(
+                "高压缸抽汽",
+                HES_COLUMNS,
+                &r2.hhes,
+                hp_name,
+                delimiter,
+                units,
+            ),
+        },
+        CsvTable {
+            name: "result2_rhx",
+            content: row_table("再热器", RHX_COLUMNS, &r2.rhx, lp_name, delimiter, units),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_escape() {
+        assert_eq!(cell("p_fwxi", ','), "p_fwxi");
+        assert_eq!(cell("kJ/(kg,K)", ','), "\"kJ/(kg,K)\"");
+        assert_eq!(cell("a\"b", '\t'), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn test_result_tables() {
+        let results = CalcResultParamters::default();
+        let tables = result_tables(&results, ',', &UnitSystem::default());
+        assert_eq!(tables.len(), 7);
+        let header = tables[0].content.lines().next().unwrap();
+        assert!(header.starts_with("迭代次数,核电厂效率 η_eNPP,反应堆热功率 Q_R (GW)"));
+        assert_eq!(
+            tables[1].content.lines().count(),
+            1 + RESULT2_TABLE1.len() + RESULT2_TABLE2.len()
+        );
+        let tables = result_tables(&results, ',', &"bar,%".parse().unwrap());
+        assert!(
+            tables[0]
+                .content
+                .starts_with("迭代次数,核电厂效率 η_eNPP (%),")
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! 独立HTML计算报告
+//!
+//! 生成单个HTML文件（内嵌CSS与SVG），无需本程序即可查看或归档。
+use crate::fields::{
+    FW_COLUMNS, FieldMeta, HES_COLUMNS, INPUT_FIELDS, RESULT1_COLUMNS, RESULT2_TABLE1,
+    RESULT2_TABLE2, RHX_COLUMNS, field_map, field_value,
+};
+use crate::parameters::{CalcInputParameters, CalcResultParamters};
+use crate::units::{Unit, UnitSystem};
+
+use super::svg::{cycle_state_points, diagram, saturation_line};
+
+const STYLE: &str = r#"
+body { font-family: "MiSans", "Microsoft YaHei", "PingFang SC", sans-serif; margin: 2em auto; max-width: 1100px; color: #1e1e2e; line-height: 1.5; }
+h1 { border-bottom: 2px solid #1e66f5; padding-bottom: .3em; }
+h2 { margin-top: 2em; color: #1e66f5; }
+table { border-collapse: collapse; width: 100%; margin: 1em 0; font-size: 14px; }
+caption { font-weight: bold; text-align: left; margin-bottom: .4em; }
+th, td { border: 1px solid #ccd0da; padding: 4px 8px; }
+th { background: #e6e9ef; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+tr:nth-child(even) td { background: #f5f6fa; }
+.scroll { overflow-x: auto; }
+.diagrams { display: flex; flex-wrap: wrap; gap: 1em; }
+svg.diagram { width: 540px; max-width: 100%; background: #fff; border: 1px solid #ccd0da; }
+svg .title { font-size: 16px; font-weight: bold; }
+svg .label { font-size: 13px; }
+svg .tick { font-size: 11px; fill: #4c4f69; }
+svg .axis { stroke: #4c4f69; stroke-width: 1; }
+svg .dome { fill: none; stroke: #8839ef; stroke-width: 1.5; stroke-dasharray: 4 3; }
+svg .cycle { fill: none; stroke: #d20f39; stroke-width: 2; }
+svg .point { fill: #1e66f5; }
+footer { margin-top: 3em; font-size: 12px; color: #6c6f85; }
+"#;
+
+/// 转义HTML特殊字符
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 格式化数值，保留4位小数
+fn num(val: f64) -> String {
+    format!("{:.4}", val)
+}
+
+/// 纵向表格（序号、名称、符号、数值、单位）
+fn scalar_table(
+    caption: &str,
+    rows: &[FieldMeta],
+    values: &serde_json::Map<String, serde_json::Value>,
+    units: &UnitSystem,
+) -> String {
+    let mut html = format!(
+        "<table>\n<caption>{}</caption>\n<tr><th>序号</th><th>名称</th><th>符号</th><th>数值</th><th>单位</th></tr>\n",
+        escape(caption)
+    );
+    for row in rows {
+        // 未给定的可选参数留空
+        let value = field_value(values, row.key);
+        let value = if value.is_nan() {
+            String::new()
+        } else {
+            num(units.to_display(row.unit, value))
+        };
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}</td><td>{}</td></tr>\n",
+            row.no,
+            escape(row.label),
+            escape(row.symbol),
+            value,
+            escape(units.label(row.unit))
+        ));
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+/// 横向表格，每一行为一组数据
+fn row_table<T: serde::Serialize>(
+    caption: &str,
+    columns: &[FieldMeta],
+    rows: &[T],
+    row_name: impl Fn(usize) -> String,
+    units: &UnitSystem,
+) -> String {
+    if rows.is_empty() {
+        return String::new();
+    }
+    let mut html = format!(
+        "<div class=\"scroll\"><table>\n<caption>{}</caption>\n<tr><th></th>",
+        escape(caption)
+    );
+    for column in columns {
+        let unit = match units.label(column.unit) {
+            "" => String::new(),
+            unit => format!("<br>({})", escape(unit)),
+        };
+        html.push_str(&format!(
+            "<th title=\"{}\">{}{}</th>",
+            escape(column.label),
+            escape(column.symbol),
+            unit
+        ));
+    }
+    html.push_str("</tr>\n");
+    for (i, item) in rows.iter().enumerate() {
+        let map = field_map(item);
+        html.push_str(&format!("<tr><th>{}</th>", escape(&row_name(i))));
+        for column in columns {
+            html.push_str(&format!(
+                "<td class=\"num\">{}</td>",
+                num(units.to_display(column.unit, field_value(&map, column.key)))
+            ));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table></div>\n");
+    html
+}
+
+/// 生成HTML计算报告
+///
+/// # Arguments
+///
+/// * `params` - 输入参数
+///
+/// * `results` - 计算结果
+///
+/// * `units` - 显示单位制
+pub fn generate_html_report(
+    params: &CalcInputParameters,
+    results: &CalcResultParamters,
+    units: &UnitSystem,
+) -> String {
+    let r2 = &results.result2;
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n");
+    html.push_str("<meta charset=\"utf-8\">\n<title>核电厂热力计算报告</title>\n");
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+    html.push_str("<h1>核电厂热力计算报告</h1>\n");
+
+    if let Some(r1) = results.result1.last() {
+        html.push_str(&format!(
+            "<p>核电厂效率 η<sub>eNPP</sub> = <b>{}</b>{}，反应堆热功率 Q<sub>R</sub> = <b>{}</b> GW，蒸汽产量 D<sub>s</sub> = <b>{}</b> kg/s，共迭代 {} 次。</p>\n",
+            num(units.to_display(Unit::Fraction, r1.eta_enpp)),
+            units.label(Unit::Fraction),
+            num(r1.q_r),
+            num(r1.d_s),
+            results.result1.len()
+        ));
+    }
+
+    html.push_str("<h2>输入参数</h2>\n");
+    html.push_str(&scalar_table(
+        "输入参数",
+        INPUT_FIELDS,
+        &field_map(params),
+        units,
+    ));
+
+    html.push_str("<h2>热平衡迭代过程</h2>\n");
+    html.push_str(&row_table(
+        "热平衡计算结果",
+        RESULT1_COLUMNS,
+        &results.result1,
+        |i| format!("第{}次", i + 1),
+        units,
+    ));
+
+    html.push_str("<h2>热力过程曲线</h2>\n");
+    // 状态点与饱和线按显示单位制换算
+    let t = |v: f64| units.to_display(Unit::Celsius, v);
+    let s = |v: f64| units.to_display(Unit::KjPerKgK, v);
+    let h = |v: f64| units.to_display(Unit::KjPerKg, v);
+    let (t_unit, s_unit, h_unit) = (
+        units.label(Unit::Celsius),
+        units.label(Unit::KjPerKgK),
+        units.label(Unit::KjPerKg),
+    );
+    let points = cycle_state_points(r2);
+    let sat = saturation_line();
+    let labels: Vec<&str> = points.iter().map(|p| p.label).collect();
+    let dome_ts: Vec<(f64, f64)> = sat
+        .liquid
+        .iter()
+        .chain(sat.vapor.iter().rev())
+        .map(|&(sv, tv, _)| (s(sv), t(tv)))
+        .collect();
+    let dome_hs: Vec<(f64, f64)> = sat
+        .liquid
+        .iter()
+        .chain(sat.vapor.iter().rev())
+        .map(|&(sv, _, hv)| (s(sv), h(hv)))
+        .collect();
+    let cycle_ts: Vec<(f64, f64)> = points.iter().map(|p| (s(p.s), t(p.t))).collect();
+    let cycle_hs: Vec<(f64, f64)> = points.iter().map(|p| (s(p.s), h(p.h))).collect();
+    html.push_str("<div class=\"diagrams\">\n");
+    html.push_str(&diagram(
+        "T-s 图",
+        &format!("s ({s_unit})"),
+        &format!("T ({t_unit})"),
+        &dome_ts,
+        &cycle_ts,
+        &labels,
+    ));
+    html.push_str(&diagram(
+        "h-s 图",
+        &format!("s ({s_unit})"),
+        &format!("h ({h_unit})"),
+        &dome_hs,
+        &cycle_hs,
+        &labels,
+    ));
+    html.push_str("</div>\n");
+    html.push_str(&format!("<table>\n<caption>状态点</caption>\n<tr><th>编号</th><th>状态点</th><th>T ({t_unit})</th><th>s ({s_unit})</th><th>h ({h_unit})</th></tr>\n"));
+    for (i, p) in points.iter().enumerate() {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+            i + 1,
+            p.label,
+            num(t(p.t)),
+            num(s(p.s)),
+            num(h(p.h))
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>附表</h2>\n");
+    let values = field_map(r2);
+    html.push_str(&scalar_table("附表一", RESULT2_TABLE1, &values, units));
+    html.push_str(&scalar_table("附表二", RESULT2_TABLE2, &values, units));
+    let lp_name = |i: usize| format!("第{}级", i + 1);
+    let hp_name = |i: usize| format!("第{}级", i + r2.lfwx.len() + 2);
+    html.push_str(&row_table(
+        "40. 低压加热器给水参数",
+        FW_COLUMNS,
+        &r2.lfwx,
+        lp_name,
+        units,
+    ));
+    html.push_str(&row_table(
+        "45. 高压加热器给水参数",
+        FW_COLUMNS,
+        &r2.hfwx,
+        hp_name,
+        units,
+    ));
+    html.push_str(&row_table(
+        "46. 高压缸抽汽参数",
+        HES_COLUMNS,
+        &r2.hhes,
+        hp_name,
+        units,
+    ));
+    html.push_str(&row_table(
+        "47. 低压缸抽汽参数",
+        HES_COLUMNS,
+        &r2.lhes,
+        lp_name,
+        units,
+    ));
+    html.push_str(&row_table(
+        "48. 再热器抽汽参数",
+        RHX_COLUMNS,
+        &r2.rhx,
+        lp_name,
+        units,
+    ));
+
+    html.push_str("<footer>由核电厂热力计算程序生成</footer>\n</body>\n</html>\n");
+    html
+}
@@ -0,0 +1,250 @@
+//! 热力过程曲线（T-s图、h-s图）的SVG绘制
+use seuif97::*;
+
+use crate::parameters::CalcResult2;
+
+/// 循环中的状态点
+pub(crate) struct StatePoint {
+    /// 状态点名称
+    pub label: &'static str,
+    /// 温度(℃)
+    pub t: f64,
+    /// 比熵(kJ/(kg·K))
+    pub s: f64,
+    /// 比焓(kJ/kg)
+    pub h: f64,
+}
+
+/// 饱和线上的点
+pub(crate) struct SaturationLine {
+    /// 饱和水线上的点(s, T, h)
+    pub liquid: Vec<(f64, f64, f64)>,
+    /// 饱和蒸汽线上的点(s, T, h)
+    pub vapor: Vec<(f64, f64, f64)>,
+}
+
+/// 按照工质流经的顺序，从附表结果中取出二回路的主要状态点
+pub(crate) fn cycle_state_points(r2: &CalcResult2) -> Vec<StatePoint> {
+    let point = |label, p: f64, h: f64| StatePoint {
+        label,
+        t: ph(p, h, OT),
+        s: ph(p, h, OS),
+        h,
+    };
+    let h_dea = px(r2.p_dea, 0.0, OH);
+    vec![
+        StatePoint {
+            label: "SG出口",
+            t: r2.t_fh,
+            s: r2.s_fh,
+            h: r2.h_fh,
+        },
+        point("高压缸进口", r2.p_hi, r2.h_hi),
+        point("高压缸排汽", r2.p_hz, r2.h_hz),
+        point("汽水分离器出口", r2.p_rh1i, r2.h_rh1i),
+        point("再热器出口", r2.p_rh2z, r2.h_rh2z),
+        point("低压缸进口", r2.p_li, r2.h_li),
+        point("低压缸排汽", r2.p_lz, r2.h_lz),
+        StatePoint {
+            label: "凝结水",
+            t: r2.t_cd,
+            s: tx(r2.t_cd, 0.0, OS),
+            h: r2.h_cd,
+        },
+        StatePoint {
+            label: "除氧器出口",
+            t: r2.t_dea,
+            s: px(r2.p_dea, 0.0, OS),
+            h: h_dea,
+        },
+        point("SG进口给水", r2.p_s, r2.h_fw),
+        StatePoint {
+            label: "SG饱和水",
+            t: px(r2.p_s, 0.0, OT),
+            s: px(r2.p_s, 0.0, OS),
+            h: r2.h_s,
+        },
+    ]
+}
+
+/// 计算水的饱和线（1 ~ 373℃）
+pub(crate) fn saturation_line() -> SaturationLine {
+    let temps: Vec<f64> = (0..=74).map(|i| 1.0 + 5.0 * i as f64).collect();
+    SaturationLine {
+        liquid: temps
+            .iter()
+            .map(|&t| (tx(t, 0.0, OS), t, tx(t, 0.0, OH)))
+            .collect(),
+        vapor: temps
+            .iter()
+            .map(|&t| (tx(t, 1.0, OS), t, tx(t, 1.0, OH)))
+            .collect(),
+    }
+}
+
+/// 曲线图的坐标范围与画布尺寸
+struct Frame {
+    x_min: f64,
+    x_max: f64,
+    y_min: f64,
+    y_max: f64,
+}
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 420.0;
+const MARGIN: f64 = 56.0;
+
+impl Frame {
+    fn from_points(points: &[(f64, f64)]) -> Self {
+        let finite = points
+            .iter()
+            .filter(|(x, y)| x.is_finite() && y.is_finite());
+        let (mut x_min, mut x_max, mut y_min, mut y_max) = (
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        );
+        for &(x, y) in finite {
+            x_min = x_min.min(x);
+            x_max = x_max.max(x);
+            y_min = y_min.min(y);
+            y_max = y_max.max(y);
+        }
+        if !x_min.is_finite() || x_max <= x_min {
+            (x_min, x_max) = (0.0, 1.0);
+        }
+        if !y_min.is_finite() || y_max <= y_min {
+            (y_min, y_max) = (0.0, 1.0);
+        }
+        let (dx, dy) = (0.05 * (x_max - x_min), 0.05 * (y_max - y_min));
+        Self {
+            x_min: x_min - dx,
+            x_max: x_max + dx,
+            y_min: y_min - dy,
+            y_max: y_max + dy,
+        }
+    }
+
+    fn x(&self, x: f64) -> f64 {
+        MARGIN + (x - self.x_min) / (self.x_max - self.x_min) * (WIDTH - 1.5 * MARGIN)
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        HEIGHT - MARGIN - (y - self.y_min) / (self.y_max - self.y_min) * (HEIGHT - 1.5 * MARGIN)
+    }
+
+    fn polyline(&self, points: &[(f64, f64)]) -> String {
+        points
+            .iter()
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .map(|&(x, y)| format!("{:.1},{:.1}", self.x(x), self.y(y)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// 绘制曲线图
+///
+/// # Arguments
+///
+/// * `title` - 图题
+///
+/// * `x_label` - 横坐标名称
+///
+/// * `y_label` - 纵坐标名称
+///
+/// * `dome` - 饱和线上的点
+///
+/// * `cycle` - 循环状态点，按顺序连线并闭合
+///
+/// * `labels` - 状态点名称
+pub(crate) fn diagram(
+    title: &str,
+    x_label: &str,
+    y_label: &str,
+    dome: &[(f64, f64)],
+    cycle: &[(f64, f64)],
+    labels: &[&str],
+) -> String {
+    let all: Vec<(f64, f64)> = dome.iter().chain(cycle.iter()).copied().collect();
+    let frame = Frame::from_points(&all);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {WIDTH} {HEIGHT}\" class=\"diagram\">\n"
+    );
+    svg.push_str(&format!(
+        "<text x=\"{}\" y=\"20\" text-anchor=\"middle\" class=\"title\">{}</text>\n",
+        WIDTH / 2.0,
+        title
+    ));
+    // 坐标轴与刻度
+    let (left, bottom) = (MARGIN, HEIGHT - MARGIN);
+    svg.push_str(&format!(
+        "<line x1=\"{left}\" y1=\"{bottom}\" x2=\"{}\" y2=\"{bottom}\" class=\"axis\"/>\n",
+        WIDTH - MARGIN / 2.0
+    ));
+    svg.push_str(&format!(
+        "<line x1=\"{left}\" y1=\"{bottom}\" x2=\"{left}\" y2=\"{}\" class=\"axis\"/>\n",
+        MARGIN / 2.0
+    ));
+    for i in 0..=5 {
+        let xv = frame.x_min + (frame.x_max - frame.x_min) * i as f64 / 5.0;
+        let yv = frame.y_min + (frame.y_max - frame.y_min) * i as f64 / 5.0;
+        svg.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" class=\"tick\">{:.2}</text>\n",
+            frame.x(xv),
+            bottom + 16.0,
+            xv
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\" class=\"tick\">{:.0}</text>\n",
+            left - 4.0,
+            frame.y(yv) + 4.0,
+            yv
+        ));
+    }
+    svg.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" class=\"label\">{}</text>\n",
+        WIDTH / 2.0,
+        HEIGHT - 12.0,
+        x_label
+    ));
+    svg.push_str(&format!(
+        "<text x=\"14\" y=\"{}\" text-anchor=\"middle\" class=\"label\" transform=\"rotate(-90 14 {})\">{}</text>\n",
+        HEIGHT / 2.0,
+        HEIGHT / 2.0,
+        y_label
+    ));
+    // 饱和线与循环
+    svg.push_str(&format!(
+        "<polyline points=\"{}\" class=\"dome\"/>\n",
+        frame.polyline(dome)
+    ));
+    let mut closed = cycle.to_vec();
+    if let Some(&first) = cycle.first() {
+        closed.push(first);
+    }
+    svg.push_str(&format!(
+        "<polyline points=\"{}\" class=\"cycle\"/>\n",
+        frame.polyline(&closed)
+    ));
+    for (i, &(x, y)) in cycle.iter().enumerate() {
+        if !(x.is_finite() && y.is_finite()) {
+            continue;
+        }
+        svg.push_str(&format!(
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" class=\"point\"><title>{}</title></circle>\n",
+            frame.x(x),
+            frame.y(y),
+            labels.get(i).copied().unwrap_or_default()
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" class=\"tick\">{}</text>\n",
+            frame.x(x) + 5.0,
+            frame.y(y) - 5.0,
+            i + 1
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
@@ -0,0 +1,1173 @@
+//! LaTeX 计算书
+//!
+//! 按照`Calculator::calculate`的计算顺序，将每一步写为
+//! “公式 → 代入数值 → 结果(单位)”的形式，并附上附表一、附表二。
+//! 为保证代入数值与公式一致，计算书始终采用内部单位，不随显示单位制换算。
+use seuif97::*;
+
+use crate::allocation::Allocation;
+use crate::drains::DrainMode;
+use crate::feed_pump::{self, Drive, Exhaust, Expansion, SteamSource};
+use crate::fields::{
+    FW_COLUMNS, FieldMeta, HES_COLUMNS, RESULT2_TABLE1, RESULT2_TABLE2, RHX_COLUMNS, field_map,
+    field_value,
+};
+use crate::parameters::{CalcFWParameters, CalcInputParameters, CalcResultParamters};
+use crate::units::UnitSystem;
+
+/// 格式化数值，保留4位小数
+fn num(val: f64) -> String {
+    format!("{:.4}", val)
+}
+
+/// 疏水泵打入下游的总流量(kg/s)
+fn pumped_drains(heaters: &[CalcFWParameters], modes: &[DrainMode]) -> f64 {
+    heaters
+        .iter()
+        .zip(modes)
+        .filter(|&(_, &mode)| mode == DrainMode::PumpedForward)
+        .map(|(heater, _)| heater.g_dx)
+        .sum()
+}
+
+/// 转义LaTeX正文中的特殊字符
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '_' | '%' | '&' | '#' | '$' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 将`Δp_fh`形式的符号转换为LaTeX数学符号
+pub(crate) fn tex_symbol(symbol: &str) -> String {
+    let (base, sub) = match symbol.split_once('_') {
+        Some((base, sub)) => (base, Some(sub)),
+        None => (symbol, None),
+    };
+    let mut tex = String::new();
+    for c in base.chars() {
+        match c {
+            'η' => tex.push_str("\\eta "),
+            'ξ' => tex.push_str("\\xi "),
+            'θ' => tex.push_str("\\theta "),
+            'Δ' => tex.push_str("\\Delta "),
+            'δ' => tex.push_str("\\delta "),
+            'ρ' => tex.push_str("\\rho "),
+            'α' => tex.push_str("\\alpha "),
+            'λ' => tex.push_str("\\lambda "),
+            _ => tex.push(c),
+        }
+    }
+    if let Some(sub) = sub {
+        tex.push_str(&format!("_{{\\mathrm{{{}}}}}", sub));
+    }
+    tex
+}
+
+/// 将单位转换为LaTeX数学模式下的写法
+pub(crate) fn tex_unit(unit: &str) -> String {
+    match unit {
+        "" => String::new(),
+        "℃" => "\\,^\\circ\\mathrm{C}".to_string(),
+        _ => format!(
+            "\\,\\mathrm{{{}}}",
+            unit.replace('·', "\\cdot ")
+                .replace('%', "\\%")
+                .replace('²', "^2")
+                .replace('³', "^3")
+        ),
+    }
+}
+
+/// 计算书内容
+struct Report {
+    out: String,
+}
+
+impl Report {
+    fn section(&mut self, title: &str) {
+        self.out
+            .push_str(&format!("\n\\section{{{}}}\n\n", escape(title)));
+    }
+
+    fn subsection(&mut self, title: &str) {
+        self.out
+            .push_str(&format!("\n\\subsection{{{}}}\n\n", escape(title)));
+    }
+
+    fn text(&mut self, text: &str) {
+        self.out.push_str(&format!("{}\n\n", escape(text)));
+    }
+
+    /// 写出一个计算步骤
+    ///
+    /// # Arguments
+    ///
+    /// * `desc` - 步骤说明
+    ///
+    /// * `symbol` - 计算量的符号，如`T_cd`
+    ///
+    /// * `formula` - LaTeX公式
+    ///
+    /// * `substituted` - 代入数值后的LaTeX公式
+    ///
+    /// * `value` - 计算结果
+    ///
+    /// * `unit` - 计算结果的单位
+    fn step(
+        &mut self,
+        desc: &str,
+        symbol: &str,
+        formula: &str,
+        substituted: &str,
+        value: f64,
+        unit: &str,
+    ) {
+        self.out
+            .push_str(&format!("\\noindent {}：\n", escape(desc)));
+        self.out.push_str("\\begin{align*}\n");
+        self.out
+            .push_str(&format!("{} &= {} \\\\\n", tex_symbol(symbol), formula));
+        self.out.push_str(&format!("&= {} \\\\\n", substituted));
+        self.out
+            .push_str(&format!("&= {}{}\n", num(value), tex_unit(unit)));
+        self.out.push_str("\\end{align*}\n\n");
+    }
+
+    /// 写出由水蒸气性质（IAPWS-IF97）查得的参数
+    fn property(&mut self, desc: &str, symbol: &str, args: &str, value: f64, unit: &str) {
+        self.out
+            .push_str(&format!("\\noindent {}：\n", escape(desc)));
+        self.out.push_str(&format!(
+            "\\[ {} = f_{{\\mathrm{{IF97}}}}\\left({}\\right) = {}{} \\]\n\n",
+            tex_symbol(symbol),
+            args,
+            num(value),
+            tex_unit(unit)
+        ));
+    }
+
+    /// 写出纵向表格（序号、名称、符号、数值、单位）
+    fn scalar_table(
+        &mut self,
+        caption: &str,
+        rows: &[FieldMeta],
+        values: &serde_json::Map<String, serde_json::Value>,
+    ) {
+        self.out.push_str("\\begin{longtable}{clccc}\n");
+        self.out
+            .push_str(&format!("\\caption{{{}}} \\\\\n", escape(caption)));
+        self.out
+            .push_str("\\toprule\n序号 & 名称 & 符号 & 数值 & 单位 \\\\\n\\midrule\n\\endhead\n");
+        for row in rows {
+            self.out.push_str(&format!(
+                "{} & {} & ${}$ & {} & ${}$ \\\\\n",
+                row.no,
+                escape(row.label),
+                tex_symbol(row.symbol),
+                num(field_value(values, row.key)),
+                tex_unit(UnitSystem::default().label(row.unit)).trim_start_matches("\\,")
+            ));
+        }
+        self.out.push_str("\\bottomrule\n\\end{longtable}\n\n");
+    }
+
+    /// 写出分级参数表格，每一级占一列
+    fn stage_table<T: serde::Serialize>(
+        &mut self,
+        caption: &str,
+        columns: &[FieldMeta],
+        stages: &[T],
+        stage_names: &[String],
+    ) {
+        if stages.is_empty() {
+            return;
+        }
+        let maps: Vec<_> = stages.iter().map(field_map).collect();
+        self.out.push_str(&format!(
+            "\\begin{{longtable}}{{lc{}c}}\n",
+            "r".repeat(maps.len())
+        ));
+        self.out
+            .push_str(&format!("\\caption{{{}}} \\\\\n", escape(caption)));
+        self.out.push_str("\\toprule\n名称 & 符号");
+        for name in stage_names.iter().take(maps.len()) {
+            self.out.push_str(&format!(" & {}", escape(name)));
+        }
+        self.out.push_str(" & 单位 \\\\\n\\midrule\n\\endhead\n");
+        for column in columns {
+            self.out.push_str(&format!(
+                "{} & ${}$",
+                escape(column.label),
+                tex_symbol(column.symbol)
+            ));
+            for map in &maps {
+                self.out
+                    .push_str(&format!(" & {}", num(field_value(map, column.key))));
+            }
+            self.out.push_str(&format!(
+                " & ${}$ \\\\\n",
+                tex_unit(UnitSystem::default().label(column.unit)).trim_start_matches("\\,")
+            ));
+        }
+        self.out.push_str("\\bottomrule\n\\end{longtable}\n\n");
+    }
+}
+
+/// 生成LaTeX计算书
+///
+/// `params`应为计算完成后的参数（其中的`ne_npp`、`g_cd`为迭代收敛值）
+pub fn generate_tex_report(params: &CalcInputParameters, results: &CalcResultParamters) -> String {
+    let p = params;
+    let r2 = &results.result2;
+    let mut report = Report { out: String::new() };
+
+    report
+        .out
+        .push_str("\\documentclass[a4paper,11pt]{ctexart}\n");
+    report
+        .out
+        .push_str("\\usepackage{amsmath,booktabs,longtable,geometry}\n");
+    report.out.push_str("\\geometry{margin=2.5cm}\n");
+    report.out.push_str("\\title{核电厂热力计算书}\n");
+    report.out.push_str("\\date{\\today}\n\n");
+    report
+        .out
+        .push_str("\\begin{document}\n\\maketitle\n\\tableofcontents\n");
+
+    report.section("一回路冷却剂参数");
+    let t_cs = px(p.p_c, 0.0, OT);
+    report.property(
+        "冷却剂压力对应的饱和温度",
+        "T_cs",
+        &format!(
+            "p_{{\\mathrm{{c}}}}={}\\,\\mathrm{{MPa}},\\ x=0",
+            num(p.p_c)
+        ),
+        t_cs,
+        "℃",
+    );
+    let t_co = t_cs - p.dt_sub;
+    report.step(
+        "反应堆出口冷却剂温度",
+        "T_co",
+        "T_{\\mathrm{cs}} - \\Delta T_{\\mathrm{sub}}",
+        &format!("{} - {}", num(t_cs), num(p.dt_sub)),
+        t_co,
+        "℃",
+    );
+    let t_ci = t_co - p.dt_c;
+    report.step(
+        "反应堆进口冷却剂温度",
+        "T_ci",
+        "T_{\\mathrm{co}} - \\Delta T_{\\mathrm{c}}",
+        &format!("{} - {}", num(t_co), num(p.dt_c)),
+        t_ci,
+        "℃",
+    );
+
+    report.section("蒸汽初参数");
+    let t_s = px(p.p_s, 1.0, OT);
+    report.property(
+        "蒸汽发生器饱和温度",
+        "T_s",
+        &format!(
+            "p_{{\\mathrm{{s}}}}={}\\,\\mathrm{{MPa}},\\ x=1",
+            num(p.p_s)
+        ),
+        t_s,
+        "℃",
+    );
+    let t_fh = px(p.p_s, p.x_fh, OT);
+    report.property(
+        "新蒸汽温度",
+        "T_fh",
+        &format!(
+            "p_{{\\mathrm{{s}}}}={},\\ X_{{\\mathrm{{fh}}}}={}",
+            num(p.p_s),
+            num(p.x_fh)
+        ),
+        t_fh,
+        "℃",
+    );
+    let h_fh = tx(t_fh, p.x_fh, OH);
+    report.property(
+        "新蒸汽比焓",
+        "h_fh",
+        &format!(
+            "T_{{\\mathrm{{fh}}}}={},\\ X_{{\\mathrm{{fh}}}}={}",
+            num(t_fh),
+            num(p.x_fh)
+        ),
+        h_fh,
+        "kJ/kg",
+    );
+    let s_fh = px(p.p_s, p.x_fh, OS);
+    report.property(
+        "新蒸汽比熵",
+        "s_fh",
+        &format!(
+            "p_{{\\mathrm{{s}}}}={},\\ X_{{\\mathrm{{fh}}}}={}",
+            num(p.p_s),
+            num(p.x_fh)
+        ),
+        s_fh,
+        "kJ/(kg·K)",
+    );
+    let dt_m = (t_co - t_ci) / f64::ln((t_co - t_s) / (t_ci - t_s));
+    report.step(
+        "一、二次侧对数平均温差",
+        "ΔT_m",
+        "\\frac{T_{\\mathrm{co}} - T_{\\mathrm{ci}}}{\\ln\\frac{T_{\\mathrm{co}} - T_{\\mathrm{s}}}{T_{\\mathrm{ci}} - T_{\\mathrm{s}}}}",
+        &format!(
+            "\\frac{{{} - {}}}{{\\ln\\frac{{{} - {}}}{{{} - {}}}}}",
+            num(t_co),
+            num(t_ci),
+            num(t_co),
+            num(t_s),
+            num(t_ci),
+            num(t_s)
+        ),
+        dt_m,
+        "℃",
+    );
+
+    report.section("蒸汽终参数");
+    let t_cd = p.t_sw1 + p.dt_sw + p.dt;
+    report.step(
+        "冷凝器凝结水饱和温度",
+        "T_cd",
+        "T_{\\mathrm{sw1}} + \\Delta T_{\\mathrm{sw}} + \\delta T",
+        &format!("{} + {} + {}", num(p.t_sw1), num(p.dt_sw), num(p.dt)),
+        t_cd,
+        "℃",
+    );
+    let p_cd = tx(t_cd, 0.0, OP);
+    report.property(
+        "冷凝器的运行压力",
+        "p_cd",
+        &format!("T_{{\\mathrm{{cd}}}}={},\\ x=0", num(t_cd)),
+        p_cd,
+        "MPa",
+    );
+
+    report.section("高压缸参数");
+    let dp_fh = p.dp_fh * p.p_s;
+    report.step(
+        "新蒸汽压损",
+        "Δp_fh",
+        "\\Delta p_{\\mathrm{fh}}\\% \\cdot p_{\\mathrm{s}}",
+        &format!("{} \\times {}", num(p.dp_fh), num(p.p_s)),
+        dp_fh,
+        "MPa",
+    );
+    let p_hi = p.p_s - dp_fh;
+    report.step(
+        "高压缸进口蒸汽压力",
+        "p_hi",
+        "p_{\\mathrm{s}} - \\Delta p_{\\mathrm{fh}}",
+        &format!("{} - {}", num(p.p_s), num(dp_fh)),
+        p_hi,
+        "MPa",
+    );
+    let h_hi = px(p_hi, 1.0, OH);
+    report.property(
+        "高压缸进口蒸汽比焓",
+        "h_hi",
+        &format!("p_{{\\mathrm{{hi}}}}={},\\ x=1", num(p_hi)),
+        h_hi,
+        "kJ/kg",
+    );
+    let x_hi = ph(p_hi, h_hi, OX);
+    report.property(
+        "高压缸进口蒸汽干度",
+        "X_hi",
+        &format!(
+            "p_{{\\mathrm{{hi}}}}={},\\ h_{{\\mathrm{{hi}}}}={}",
+            num(p_hi),
+            num(h_hi)
+        ),
+        x_hi,
+        "",
+    );
+    let s_hi = ph(p_hi, h_hi, OS);
+    report.property(
+        "高压缸进口蒸汽比熵",
+        "s_hi",
+        &format!(
+            "p_{{\\mathrm{{hi}}}}={},\\ h_{{\\mathrm{{hi}}}}={}",
+            num(p_hi),
+            num(h_hi)
+        ),
+        s_hi,
+        "kJ/(kg·K)",
+    );
+    let p_hz = p.dp_hz * p_hi;
+    report.step(
+        "高压缸排汽压力",
+        "p_hz",
+        "\\frac{p_{\\mathrm{hz}}}{p_{\\mathrm{hi}}} \\cdot p_{\\mathrm{hi}}",
+        &format!("{} \\times {}", num(p.dp_hz), num(p_hi)),
+        p_hz,
+        "MPa",
+    );
+    let h_hzs = ps(p_hz, s_hi, OH);
+    report.property(
+        "高压缸排汽理想比焓",
+        "h_hzs",
+        &format!(
+            "p_{{\\mathrm{{hz}}}}={},\\ s_{{\\mathrm{{hi}}}}={}",
+            num(p_hz),
+            num(s_hi)
+        ),
+        h_hzs,
+        "kJ/kg",
+    );
+    let h_hz = h_hi - p.n_hi * (h_hi - h_hzs);
+    report.step(
+        "高压缸排汽实际比焓",
+        "h_hz",
+        "h_{\\mathrm{hi}} - \\eta_{\\mathrm{hi}}(h_{\\mathrm{hi}} - h_{\\mathrm{hzs}})",
+        &format!(
+            "{} - {} \\times ({} - {})",
+            num(h_hi),
+            num(p.n_hi),
+            num(h_hi),
+            num(h_hzs)
+        ),
+        h_hz,
+        "kJ/kg",
+    );
+    let x_hz = ph(p_hz, h_hz, OX);
+    report.property(
+        "高压缸排汽干度",
+        "X_hz",
+        &format!(
+            "p_{{\\mathrm{{hz}}}}={},\\ h_{{\\mathrm{{hz}}}}={}",
+            num(p_hz),
+            num(h_hz)
+        ),
+        x_hz,
+        "",
+    );
+
+    report.section("蒸汽中间再热参数");
+    report.text(
+        "汽水分离再热系统总压降约为高压缸排汽压力的3%，各设备压降相同；汽水分离器除去蒸汽中98%的水分。",
+    );
+    let p_uw = 0.99 * p_hz;
+    report.step(
+        "汽水分离器出口疏水压力",
+        "p_uw",
+        "0.99\\,p_{\\mathrm{hz}}",
+        &format!("0.99 \\times {}", num(p_hz)),
+        p_uw,
+        "MPa",
+    );
+    let h_uw = px(p_uw, 0.0, OH);
+    report.property(
+        "汽水分离器出口疏水比焓",
+        "h_uw",
+        &format!("p_{{\\mathrm{{uw}}}}={},\\ x=0", num(p_uw)),
+        h_uw,
+        "kJ/kg",
+    );
+    let p_rh1i = 0.99 * p_hz;
+    report.step(
+        "一级再热器进口蒸汽压力",
+        "p_rh1i",
+        "0.99\\,p_{\\mathrm{hz}}",
+        &format!("0.99 \\times {}", num(p_hz)),
+        p_rh1i,
+        "MPa",
+    );
+    let x_rh1i = x_hz / (1.0 - 0.98 * (1.0 - x_hz));
+    report.step(
+        "一级再热器进口蒸汽干度",
+        "X_rh1i",
+        "\\frac{X_{\\mathrm{spi}}}{1 - 0.98(1 - X_{\\mathrm{spi}})}",
+        &format!(
+            "\\frac{{{}}}{{1 - 0.98 \\times (1 - {})}}",
+            num(x_hz),
+            num(x_hz)
+        ),
+        x_rh1i,
+        "",
+    );
+    let h_rh1i = px(p_rh1i, x_rh1i, OH);
+    report.property(
+        "一级再热器进口蒸汽比焓",
+        "h_rh1i",
+        &format!(
+            "p_{{\\mathrm{{rh1i}}}}={},\\ X_{{\\mathrm{{rh1i}}}}={}",
+            num(p_rh1i),
+            num(x_rh1i)
+        ),
+        h_rh1i,
+        "kJ/kg",
+    );
+    let p_rh2z = 0.97 * p_hz;
+    report.step(
+        "二级再热器出口压力",
+        "p_rh2z",
+        "0.97\\,p_{\\mathrm{hz}}",
+        &format!("0.97 \\times {}", num(p_hz)),
+        p_rh2z,
+        "MPa",
+    );
+    let t_rh2z = t_fh - p.t_rh2z;
+    report.step(
+        "二级再热器出口温度",
+        "T_rh2z",
+        "T_{\\mathrm{fh}} - \\Delta T_{\\mathrm{rh2z}}",
+        &format!("{} - {}", num(t_fh), num(p.t_rh2z)),
+        t_rh2z,
+        "℃",
+    );
+    let h_rh2z = pt(p_rh2z, t_rh2z, OH);
+    report.property(
+        "二级再热器出口蒸汽比焓",
+        "h_rh2z",
+        &format!(
+            "p_{{\\mathrm{{rh2z}}}}={},\\ T_{{\\mathrm{{rh2z}}}}={}",
+            num(p_rh2z),
+            num(t_rh2z)
+        ),
+        h_rh2z,
+        "kJ/kg",
+    );
+    let dh_rh = (h_rh2z - h_rh1i) / 2.0;
+    report.step(
+        "每级再热器平均焓升",
+        "Δh_rh",
+        "\\frac{h_{\\mathrm{rh2z}} - h_{\\mathrm{rh1i}}}{2}",
+        &format!("\\frac{{{} - {}}}{{2}}", num(h_rh2z), num(h_rh1i)),
+        dh_rh,
+        "kJ/kg",
+    );
+    let h_rh1z = h_rh1i + dh_rh;
+    report.step(
+        "一级再热器出口蒸汽比焓",
+        "h_rh1z",
+        "h_{\\mathrm{rh1i}} + \\Delta h_{\\mathrm{rh}}",
+        &format!("{} + {}", num(h_rh1i), num(dh_rh)),
+        h_rh1z,
+        "kJ/kg",
+    );
+
+    report.section("低压缸参数");
+    let p_li = (1.0 - p.dp_f) * p_rh2z;
+    report.step(
+        "低压缸进口蒸汽压力",
+        "p_li",
+        "(1 - \\Delta p_{\\mathrm{f}}) p_{\\mathrm{rh2z}}",
+        &format!("(1 - {}) \\times {}", num(p.dp_f), num(p_rh2z)),
+        p_li,
+        "MPa",
+    );
+    let h_li = h_rh2z;
+    let s_li = ph(p_li, h_li, OS);
+    report.property(
+        "低压缸进口蒸汽比熵",
+        "s_li",
+        &format!(
+            "p_{{\\mathrm{{li}}}}={},\\ h_{{\\mathrm{{li}}}}={}",
+            num(p_li),
+            num(h_li)
+        ),
+        s_li,
+        "kJ/(kg·K)",
+    );
+    let dp_cd = (1.0 / (1.0 - p.dp_cd) - 1.0) * p_cd;
+    report.step(
+        "低压缸排汽压损",
+        "Δp_cd",
+        "\\left(\\frac{1}{1 - \\Delta p_{\\mathrm{cd}}\\%} - 1\\right) p_{\\mathrm{cd}}",
+        &format!(
+            "\\left(\\frac{{1}}{{1 - {}}} - 1\\right) \\times {}",
+            num(p.dp_cd),
+            num(p_cd)
+        ),
+        dp_cd,
+        "MPa",
+    );
+    let p_lz = p_cd + dp_cd;
+    report.step(
+        "低压缸排汽压力",
+        "p_lz",
+        "p_{\\mathrm{cd}} + \\Delta p_{\\mathrm{cd}}",
+        &format!("{} + {}", num(p_cd), num(dp_cd)),
+        p_lz,
+        "MPa",
+    );
+    let h_lzs = ps(p_lz, s_li, OH);
+    report.property(
+        "低压缸排汽理想比焓",
+        "h_lzs",
+        &format!(
+            "p_{{\\mathrm{{lz}}}}={},\\ s_{{\\mathrm{{li}}}}={}",
+            num(p_lz),
+            num(s_li)
+        ),
+        h_lzs,
+        "kJ/kg",
+    );
+    let h_lz = h_li - p.n_li * (h_li - h_lzs);
+    report.step(
+        "低压缸排汽实际比焓",
+        "h_lz",
+        "h_{\\mathrm{li}} - \\eta_{\\mathrm{li}}(h_{\\mathrm{li}} - h_{\\mathrm{lzs}})",
+        &format!(
+            "{} - {} \\times ({} - {})",
+            num(h_li),
+            num(p.n_li),
+            num(h_li),
+            num(h_lzs)
+        ),
+        h_lz,
+        "kJ/kg",
+    );
+
+    report.section("给水的焓升分配");
+    let h_s = px(p.p_s, 0.0, OH);
+    report.property(
+        "蒸汽发生器运行压力下的饱和水比焓",
+        "h_s",
+        &format!("p_{{\\mathrm{{s}}}}={},\\ x=0", num(p.p_s)),
+        h_s,
+        "kJ/kg",
+    );
+    let h_cd = tx(t_cd, 0.0, OH);
+    report.property(
+        "冷凝器出口凝结水比焓",
+        "h_cd",
+        &format!("T_{{\\mathrm{{cd}}}}={},\\ x=0", num(t_cd)),
+        h_cd,
+        "kJ/kg",
+    );
+    let dh_fwop = (h_s - h_cd) / (p.z + 1.0);
+    report.step(
+        "每级加热器理论给水焓升",
+        "Δh_fwop",
+        "\\frac{h_{\\mathrm{s}} - h_{\\mathrm{cd}}}{Z + 1}",
+        &format!("\\frac{{{} - {}}}{{{} + 1}}", num(h_s), num(h_cd), p.z),
+        dh_fwop,
+        "kJ/kg",
+    );
+    let h_fwop = h_cd + p.z * dh_fwop;
+    report.step(
+        "最佳给水比焓",
+        "h_fwop",
+        "h_{\\mathrm{cd}} + Z \\Delta h_{\\mathrm{fwop}}",
+        &format!("{} + {} \\times {}", num(h_cd), p.z, num(dh_fwop)),
+        h_fwop,
+        "kJ/kg",
+    );
+    let t_fwop = ph(p.p_s, h_fwop, OT);
+    report.property(
+        "最佳给水温度",
+        "T_fwop",
+        &format!(
+            "p_{{\\mathrm{{s}}}}={},\\ h_{{\\mathrm{{fwop}}}}={}",
+            num(p.p_s),
+            num(h_fwop)
+        ),
+        t_fwop,
+        "℃",
+    );
+    let t_fw = p.dt_fw * t_fwop;
+    report.step(
+        "实际给水温度",
+        "T_fw",
+        "\\frac{T_{\\mathrm{fw}}}{T_{\\mathrm{fwop}}} \\cdot T_{\\mathrm{fwop}}",
+        &format!("{} \\times {}", num(p.dt_fw), num(t_fwop)),
+        t_fw,
+        "℃",
+    );
+    let h_fw = pt(p.p_s, t_fw, OH);
+    report.property(
+        "实际给水比焓",
+        "h_fw",
+        &format!(
+            "p_{{\\mathrm{{s}}}}={},\\ T_{{\\mathrm{{fw}}}}={}",
+            num(p.p_s),
+            num(t_fw)
+        ),
+        h_fw,
+        "kJ/kg",
+    );
+    let dh_fw = (h_fw - h_cd) / p.z;
+    report.step(
+        "每级加热器实际给水焓升",
+        "Δh_fw",
+        "\\frac{h_{\\mathrm{fw}} - h_{\\mathrm{cd}}}{Z}",
+        &format!("\\frac{{{} - {}}}{{{}}}", num(h_fw), num(h_cd), p.z),
+        dh_fw,
+        "kJ/kg",
+    );
+    let p_dea = 0.99 * p_hz;
+    report.step(
+        "除氧器运行压力",
+        "p_dea",
+        "0.99\\,p_{\\mathrm{hz}}",
+        &format!("0.99 \\times {}", num(p_hz)),
+        p_dea,
+        "MPa",
+    );
+    let h_deao = px(p_dea, 0.0, OH);
+    report.property(
+        "除氧器出口饱和水比焓",
+        "h_deao",
+        &format!("p_{{\\mathrm{{dea}}}}={},\\ x=0", num(p_dea)),
+        h_deao,
+        "kJ/kg",
+    );
+    let dh_fwh = (h_fw - h_deao) / p.z_h;
+    report.step(
+        "高压给水加热器每一级给水焓升",
+        "Δh_fwh",
+        "\\frac{h_{\\mathrm{fw}} - h_{\\mathrm{deao}}}{Z_{\\mathrm{h}}}",
+        &format!("\\frac{{{} - {}}}{{{}}}", num(h_fw), num(h_deao), p.z_h),
+        dh_fwh,
+        "kJ/kg",
+    );
+    let dh_fwl = (h_deao - h_cd) / (p.z_l + 1.0);
+    report.step(
+        "除氧器及低压加热器每一级给水焓升",
+        "Δh_fwl",
+        "\\frac{h_{\\mathrm{deao}} - h_{\\mathrm{cd}}}{Z_{\\mathrm{l}} + 1}",
+        &format!("\\frac{{{} - {}}}{{{} + 1}}", num(h_deao), num(h_cd), p.z_l),
+        dh_fwl,
+        "kJ/kg",
+    );
+    if let Ok(allocation) = Allocation::from_params(p)
+        && allocation != Allocation::EqualEnthalpy
+    {
+        report.text(&format!(
+            "以上为平均每一级给水焓升，各级给水焓升按{allocation}方式分配，总焓升不变。"
+        ));
+    }
+
+    report.section("给水回路系统中的压力");
+    let p_cwp = p.dp_cwp * p_dea;
+    report.step(
+        "凝水泵出口压力",
+        "p_cwp",
+        "k_{\\mathrm{cwp}} \\, p_{\\mathrm{dea}}",
+        &format!("{} \\times {}", num(p.dp_cwp), num(p_dea)),
+        p_cwp,
+        "MPa",
+    );
+    let dp_cws = p_cwp - p_dea;
+    report.step(
+        "凝水泵出口至除氧器的阻力压降",
+        "Δp_cws",
+        "p_{\\mathrm{cwp}} - p_{\\mathrm{dea}}",
+        &format!("{} - {}", num(p_cwp), num(p_dea)),
+        dp_cws,
+        "MPa",
+    );
+    let dp_fi = dp_cws / (p.z_l + 1.0);
+    report.step(
+        "每级低压加热器及除氧器的平均压降",
+        "Δp_fi",
+        "\\frac{\\Delta p_{\\mathrm{cws}}}{Z_{\\mathrm{l}} + 1}",
+        &format!("\\frac{{{}}}{{{} + 1}}", num(dp_cws), p.z_l),
+        dp_fi,
+        "MPa",
+    );
+    let p_fwpo = p.dp_fwpo * p.p_s;
+    report.step(
+        "给水泵出口压力",
+        "p_fwpo",
+        "k_{\\mathrm{fwpo}} \\, p_{\\mathrm{s}}",
+        &format!("{} \\times {}", num(p.dp_fwpo), num(p.p_s)),
+        p_fwpo,
+        "MPa",
+    );
+    let p_fwi = p.p_s + 0.1;
+    report.step(
+        "蒸汽发生器进口给水压力",
+        "p_fwi",
+        "p_{\\mathrm{s}} + 0.1",
+        &format!("{} + 0.1", num(p.p_s)),
+        p_fwi,
+        "MPa",
+    );
+
+    report.section("给水加热器");
+    for (i, fw) in r2.lfwx.iter().enumerate() {
+        let stage = i + 1;
+        report.subsection(&format!("第{stage}级低压给水加热器"));
+        report.step(
+            "出口给水比焓",
+            "h_fwxo",
+            "h_{\\mathrm{fwxi}} + \\Delta h_{\\mathrm{fwx}}",
+            &format!("{} + {}", num(fw.h_fwxi), num(fw.h_fwxo - fw.h_fwxi)),
+            fw.h_fwxo,
+            "kJ/kg",
+        );
+        report.step(
+            "汽侧疏水温度",
+            "T_roxk",
+            &format!("T_{{\\mathrm{{fwxo}}}} + \\theta_{{\\mathrm{{u{stage}}}}}"),
+            &format!("{} + {}", num(fw.t_fwxo), num(p.heater_theta_u(stage))),
+            fw.t_roxk,
+            "℃",
+        );
+    }
+    for (i, fw) in r2.hfwx.iter().enumerate() {
+        let stage = i + r2.lfwx.len() + 2;
+        report.subsection(&format!("第{stage}级高压给水加热器"));
+        report.step(
+            "出口给水比焓",
+            "h_fwxo",
+            "h_{\\mathrm{fwxi}} + \\Delta h_{\\mathrm{fwx}}",
+            &format!("{} + {}", num(fw.h_fwxi), num(fw.h_fwxo - fw.h_fwxi)),
+            fw.h_fwxo,
+            "kJ/kg",
+        );
+        report.step(
+            "汽侧疏水温度",
+            "T_roxk",
+            &format!("T_{{\\mathrm{{fwxo}}}} + \\theta_{{\\mathrm{{u{stage}}}}}"),
+            &format!("{} + {}", num(fw.t_fwxo), num(p.heater_theta_u(stage))),
+            fw.t_roxk,
+            "℃",
+        );
+    }
+
+    report.section("汽轮机抽汽");
+    let extractions = r2
+        .lhes
+        .iter()
+        .enumerate()
+        .map(|(i, es)| (i + 1, es, h_li, p.n_li, "li"))
+        .chain(
+            r2.hhes
+                .iter()
+                .enumerate()
+                .map(|(i, es)| (i + r2.lhes.len() + 2, es, h_hi, p.n_hi, "hi")),
+        );
+    for (stage, es, h_i, eta, cyl) in extractions {
+        report.subsection(&format!("第{}级给水加热器抽汽", stage));
+        report.step(
+            "抽汽压力",
+            "p_hesx",
+            "\\frac{p_{\\mathrm{sat}}(T_{\\mathrm{hesx}})}{1 - \\Delta p_{\\mathrm{ej}}}",
+            &format!(
+                "\\frac{{p_{{\\mathrm{{sat}}}}({})}}{{1 - {}}}",
+                num(es.t_hesx),
+                num(p.dp_ej)
+            ),
+            es.p_hesx,
+            "MPa",
+        );
+        report.step(
+            "抽汽比焓",
+            "h_hesx",
+            &format!(
+                "h_{{\\mathrm{{{cyl}}}}} - \\eta_{{\\mathrm{{{cyl}}}}}(h_{{\\mathrm{{{cyl}}}}} - h_{{\\mathrm{{hesxs}}}})"
+            ),
+            &format!(
+                "{} - {} \\times ({} - {})",
+                num(h_i),
+                num(eta),
+                num(h_i),
+                num(es.h_hesxs)
+            ),
+            es.h_hesx,
+            "kJ/kg",
+        );
+    }
+
+    if let Some(r1) = results.result1.last() {
+        report.section("热平衡计算");
+        report.text(&format!(
+            "以下为第{}次迭代（收敛）的计算结果。",
+            results.result1.len()
+        ));
+        let drive = Drive::from_params(p);
+        let (from_fh, to_cd, _) = feed_pump::shares(p);
+        let h_a = r2.h_fwpti - r2.h_fwptz;
+        if drive == Drive::Turbine {
+            report.text(&format!(
+                "给水泵由给水泵汽轮机驱动，汽源为{}，排汽进入{}，实际焓降{}。",
+                SteamSource::from_params(p),
+                Exhaust::from_params(p),
+                Expansion::from_params(p)
+            ));
+            report.step(
+                "给水泵汽轮机实际焓降",
+                "h_a",
+                "h_{\\mathrm{fwpti}} - h_{\\mathrm{fwptz}}",
+                &format!("{} - {}", num(r2.h_fwpti), num(r2.h_fwptz)),
+                h_a,
+                "kJ/kg",
+            );
+        } else {
+            report.text("给水泵由电动机驱动，耗电由发电机供给，不消耗蒸汽。");
+        }
+        let lp_modes = [p.drain_1, p.drain_2, p.drain_3, p.drain_4].map(DrainMode::from_code);
+        let hp_modes = [p.drain_6, p.drain_7].map(DrainMode::from_code);
+        report.text(&format!(
+            "第一至四级加热器疏水方式：{}；第六、七级加热器疏水方式：{}。",
+            lp_modes.map(|mode| mode.to_string()).join("、"),
+            hp_modes.map(|mode| mode.to_string()).join("、")
+        ));
+        let g_ldp = pumped_drains(&r2.lfwx, &lp_modes);
+        let g_hdp = pumped_drains(&r2.hfwx, &hp_modes);
+        let rho_fwp = 0.5 * (px(p_dea, 0.0, OD) + px(p_fwpo, 0.0, OD));
+        let n_fwpp = 1000.0 * (r1.g_fw - g_hdp) * r1.h_fwp / rho_fwp;
+        // 高压加热器疏水打入下游时不经过给水泵
+        let (n_fwpp_formula, g_fwp) = if g_hdp > 0.0 {
+            (
+                "\\frac{1000\\,(G_{\\mathrm{fw}} - G_{\\mathrm{hdp}}) H_{\\mathrm{fwp}}}{\\rho_{\\mathrm{fwp}}}",
+                format!("({} - {})", num(r1.g_fw), num(g_hdp)),
+            )
+        } else {
+            (
+                "\\frac{1000\\,G_{\\mathrm{fw}} H_{\\mathrm{fwp}}}{\\rho_{\\mathrm{fwp}}}",
+                num(r1.g_fw),
+            )
+        };
+        report.step(
+            "给水泵有效输出功率",
+            "N_fwpp",
+            n_fwpp_formula,
+            &format!(
+                "\\frac{{1000 \\times {} \\times {}}}{{{}}}",
+                g_fwp,
+                num(r1.h_fwp),
+                num(rho_fwp)
+            ),
+            n_fwpp,
+            "kW",
+        );
+        if drive == Drive::Turbine {
+            let eta_fwpt = p.n_fwpp * p.n_fwptm * p.n_fwptg;
+            report.step(
+                "给水泵汽轮机耗汽量",
+                "G_sfwp",
+                "\\frac{N_{\\mathrm{fwpp}}}{\\eta_{\\mathrm{fwpp}}\\eta_{\\mathrm{fwptm}}\\eta_{\\mathrm{fwptg}} h_{\\mathrm{a}}}",
+                &format!(
+                    "\\frac{{{}}}{{{} \\times {}}}",
+                    num(n_fwpp),
+                    num(eta_fwpt),
+                    num(h_a)
+                ),
+                r1.g_sfwp,
+                "kg/s",
+            );
+        } else {
+            report.step(
+                "电动给水泵耗电功率",
+                "N_fwpm",
+                "\\frac{N_{\\mathrm{fwpp}}}{\\eta_{\\mathrm{fwpp}}\\eta_{\\mathrm{fwpm}}}",
+                &format!(
+                    "\\frac{{{}}}{{{} \\times {}}}",
+                    num(n_fwpp),
+                    num(p.n_fwpp),
+                    num(p.n_fwpm)
+                ),
+                r2.n_fwpd * 1000.0,
+                "kW",
+            );
+        }
+        // 疏水泵打入下游时的能量平衡计入疏水泵做功，只列出自流的情况
+        if let (Some(fw4), Some(es4), false) = (
+            r2.lfwx.get(3),
+            r2.lhes.get(3),
+            lp_modes[3] == DrainMode::PumpedForward,
+        ) {
+            report.step(
+                "第四级抽汽量",
+                "G_les4",
+                "\\frac{G_{\\mathrm{fw4}}(h_{\\mathrm{fw4o}} - h_{\\mathrm{fw4i}})}{\\eta_{\\mathrm{h}}(h_{\\mathrm{les4}} - h_{\\mathrm{d4}})}",
+                &format!(
+                    "\\frac{{{} \\times ({} - {})}}{{{} \\times ({} - {})}}",
+                    num(fw4.g_fwx),
+                    num(fw4.h_fwxo),
+                    num(fw4.h_fwxi),
+                    num(p.n_h),
+                    num(es4.h_hesx),
+                    num(fw4.h_dx)
+                ),
+                r1.g_les4,
+                "kg/s",
+            );
+        }
+        // 给水泵汽轮机排入冷凝器时从凝结水量中扣除
+        let (g_slp_formula, g_slp_substituted) = if to_cd > 0.0 {
+            (
+                "G_{\\mathrm{cd}} - \\xi_{\\mathrm{d}} D_{\\mathrm{s}} - G_{\\mathrm{sfwp}}",
+                format!(
+                    "{} - {} \\times {} - {}",
+                    num(r1.g_cd),
+                    num(p.zeta_d),
+                    num(r1.d_s),
+                    num(r1.g_sfwp)
+                ),
+            )
+        } else {
+            (
+                "G_{\\mathrm{cd}} - \\xi_{\\mathrm{d}} D_{\\mathrm{s}}",
+                format!(
+                    "{} - {} \\times {}",
+                    num(r1.g_cd),
+                    num(p.zeta_d),
+                    num(r1.d_s)
+                ),
+            )
+        };
+        // 低压加热器疏水打入下游时不返回冷凝器
+        let (g_slp_formula, g_slp_substituted) = if g_ldp > 0.0 {
+            (
+                format!("{g_slp_formula} + G_{{\\mathrm{{ldp}}}}"),
+                format!("{g_slp_substituted} + {}", num(g_ldp)),
+            )
+        } else {
+            (g_slp_formula.to_string(), g_slp_substituted)
+        };
+        report.step(
+            "低压缸耗汽量",
+            "G_slp",
+            &g_slp_formula,
+            &g_slp_substituted,
+            r1.g_slp,
+            "kg/s",
+        );
+        // 汽源为再热蒸汽时，汽水分离再热器的流量包括给水泵汽轮机耗汽
+        let (g_rh_symbol, g_rh_value) = if drive == Drive::Turbine && from_fh == 0.0 {
+            (
+                "(G_{\\mathrm{slp}} + G_{\\mathrm{sfwp}})",
+                format!("({} + {})", num(r1.g_slp), num(r1.g_sfwp)),
+            )
+        } else {
+            ("G_{\\mathrm{slp}}", num(r1.g_slp))
+        };
+        report.step(
+            "汽水分离器疏水量",
+            "G_uw",
+            &format!(
+                "{g_rh_symbol} \\frac{{X_{{\\mathrm{{rh1i}}}} - X_{{\\mathrm{{spi}}}}}}{{X_{{\\mathrm{{spi}}}}}}"
+            ),
+            &format!(
+                "{} \\times \\frac{{{} - {}}}{{{}}}",
+                g_rh_value,
+                num(x_rh1i),
+                num(x_hz),
+                num(x_hz)
+            ),
+            r1.g_uw,
+            "kg/s",
+        );
+        let d_s = from_fh * r1.g_sfwp + r1.g_zc2 + r1.g_shp;
+        if from_fh > 0.0 {
+            report.step(
+                "新蒸汽耗量",
+                "D_s",
+                "G_{\\mathrm{sfwp}} + G_{\\mathrm{zc2}} + G_{\\mathrm{shp}}",
+                &format!("{} + {} + {}", num(r1.g_sfwp), num(r1.g_zc2), num(r1.g_shp)),
+                d_s,
+                "kg/s",
+            );
+        } else {
+            report.step(
+                "新蒸汽耗量",
+                "D_s",
+                "G_{\\mathrm{zc2}} + G_{\\mathrm{shp}}",
+                &format!("{} + {}", num(r1.g_zc2), num(r1.g_shp)),
+                d_s,
+                "kg/s",
+            );
+        }
+        let q_r = r1.q_r * 1000.0;
+        report.step(
+            "反应堆热功率",
+            "Q_R",
+            "\\frac{D_{\\mathrm{s}}(h_{\\mathrm{fh}} - h_{\\mathrm{fw}}) + \\xi_{\\mathrm{d}} D_{\\mathrm{s}}(h_{\\mathrm{s}} - h_{\\mathrm{fw}})}{1000\\,\\eta_{1}}",
+            &format!(
+                "\\frac{{{ds} \\times ({} - {hfw}) + {} \\times {ds} \\times ({} - {hfw})}}{{1000 \\times {}}}",
+                num(h_fh),
+                num(p.zeta_d),
+                num(h_s),
+                num(p.n_1),
+                ds = num(d_s),
+                hfw = num(h_fw),
+            ),
+            q_r,
+            "MW",
+        );
+        report.step(
+            "核电厂效率",
+            "η_eNPP",
+            "\\frac{N_{\\mathrm{e}}}{Q_{\\mathrm{R}}}",
+            &format!("\\frac{{{}}}{{{}}}", num(p.ne), num(q_r)),
+            p.ne / q_r,
+            "",
+        );
+    }
+
+    report.section("附表");
+    let values = field_map(r2);
+    report.scalar_table("附表一", RESULT2_TABLE1, &values);
+    report.scalar_table("附表二", RESULT2_TABLE2, &values);
+    let lp_names: Vec<String> = (1..=r2.lfwx.len()).map(|i| format!("第{}级", i)).collect();
+    let hp_names: Vec<String> = (0..r2.hfwx.len())
+        .map(|i| format!("第{}级", i + r2.lfwx.len() + 2))
+        .collect();
+    let rh_names: Vec<String> = (1..=r2.rhx.len()).map(|i| format!("第{}级", i)).collect();
+    report.stage_table(
+        "附表二 40. 低压加热器给水参数",
+        FW_COLUMNS,
+        &r2.lfwx,
+        &lp_names,
+    );
+    report.stage_table(
+        "附表二 45. 高压加热器给水参数",
+        FW_COLUMNS,
+        &r2.hfwx,
+        &hp_names,
+    );
+    report.stage_table(
+        "附表二 46. 高压缸抽汽参数",
+        HES_COLUMNS,
+        &r2.hhes,
+        &hp_names,
+    );
+    report.stage_table(
+        "附表二 47. 低压缸抽汽参数",
+        HES_COLUMNS,
+        &r2.lhes,
+        &lp_names,
+    );
+    report.stage_table("附表二 48. 再热器抽汽参数", RHX_COLUMNS, &r2.rhx, &rh_names);
+
+    report.out.push_str("\\end{document}\n");
+    report.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("G_fw 100% & #1"), "G\\_fw 100\\% \\& \\#1");
+        assert_eq!(escape("{$}"), "\\{\\$\\}");
+        assert_eq!(escape("C:\\calc"), "C:\\textbackslash{}calc");
+        assert_eq!(escape("~1"), "\\textasciitilde{}1");
+        assert_eq!(escape("m^3"), "m\\textasciicircum{}3");
+    }
+}
@@ -0,0 +1,276 @@
+//! Excel工作簿(.xlsx)导出
+//!
+//! 输入参数、迭代过程、附表及各级加热器/抽汽参数分别写入不同的工作表。
+use rust_xlsxwriter::{Format, FormatAlign, FormatBorder, Workbook, Worksheet, XlsxError};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::fields::{
+    FW_COLUMNS, FieldMeta, HES_COLUMNS, INPUT_FIELDS, RESULT1_COLUMNS, RESULT2_TABLE1,
+    RESULT2_TABLE2, RHX_COLUMNS, field_map, field_value,
+};
+use crate::parameters::{CalcInputParameters, CalcResultParamters};
+use crate::units::UnitSystem;
+
+/// 工作表中使用的单元格格式
+struct Formats {
+    header: Format,
+    text: Format,
+    number: Format,
+    integer: Format,
+}
+
+impl Formats {
+    fn new() -> Self {
+        let text = Format::new().set_border(FormatBorder::Thin);
+        Self {
+            header: text
+                .clone()
+                .set_bold()
+                .set_background_color(0xE6E9EF)
+                .set_align(FormatAlign::Center),
+            number: text.clone().set_num_format("0.0000"),
+            integer: text.clone().set_num_format("0"),
+            text,
+        }
+    }
+
+    /// 级数等整数量使用整数格式
+    fn for_value(&self, value: f64) -> &Format {
+        if value.fract() == 0.0 && value.abs() < 100.0 {
+            &self.integer
+        } else {
+            &self.number
+        }
+    }
+}
+
+fn write_header(
+    sheet: &mut Worksheet,
+    headers: &[&str],
+    formats: &Formats,
+) -> Result<(), XlsxError> {
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *header, &formats.header)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+    Ok(())
+}
+
+/// 纵向表格：表名(可选)、序号、字段、名称、符号、数值、单位
+fn write_scalar_rows(
+    sheet: &mut Worksheet,
+    start_row: u32,
+    table: Option<&str>,
+    rows: &[FieldMeta],
+    values: &Map<String, Value>,
+    formats: &Formats,
+    units: &UnitSystem,
+) -> Result<u32, XlsxError> {
+    let offset = u16::from(table.is_some());
+    let mut row_num = start_row;
+    for row in rows {
+        if let Some(table) = table {
+            sheet.write_string_with_format(row_num, 0, table, &formats.text)?;
+        }
+        let value = units.to_display(row.unit, field_value(values, row.key));
+        sheet.write_string_with_format(row_num, offset, row.no, &formats.text)?;
+        sheet.write_string_with_format(row_num, offset + 1, row.key, &formats.text)?;
+        sheet.write_string_with_format(row_num, offset + 2, row.label, &formats.text)?;
+        sheet.write_string_with_format(row_num, offset + 3, row.symbol, &formats.text)?;
+        // 未给定的可选参数留空
+        if !value.is_nan() {
+            sheet.write_number_with_format(row_num, offset + 4, value, formats.for_value(value))?;
+        }
+        sheet.write_string_with_format(
+            row_num,
+            offset + 5,
+            units.label(row.unit),
+            &formats.text,
+        )?;
+        row_num += 1;
+    }
+    Ok(row_num)
+}
+
+/// 分组表格：每一行为一个参数，每一组数据占一列
+fn write_group_sheet<T: Serialize>(
+    workbook: &mut Workbook,
+    name: &str,
+    columns: &[FieldMeta],
+    groups: &[T],
+    group_name: impl Fn(usize) -> String,
+    formats: &Formats,
+    units: &UnitSystem,
+) -> Result<(), XlsxError> {
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(name)?;
+    let group_names: Vec<String> = (0..groups.len()).map(group_name).collect();
+    let mut headers = vec!["字段", "名称", "符号", "单位"];
+    headers.extend(group_names.iter().map(String::as_str));
+    write_header(sheet, &headers, formats)?;
+    sheet.set_freeze_panes(1, 4)?;
+    let maps: Vec<_> = groups.iter().map(field_map).collect();
+    for (i, column) in columns.iter().enumerate() {
+        let row_num = i as u32 + 1;
+        sheet.write_string_with_format(row_num, 0, column.key, &formats.text)?;
+        sheet.write_string_with_format(row_num, 1, column.label, &formats.text)?;
+        sheet.write_string_with_format(row_num, 2, column.symbol, &formats.text)?;
+        sheet.write_string_with_format(row_num, 3, units.label(column.unit), &formats.text)?;
+        for (j, map) in maps.iter().enumerate() {
+            let value = units.to_display(column.unit, field_value(map, column.key));
+            sheet.write_number_with_format(row_num, j as u16 + 4, value, &formats.number)?;
+        }
+    }
+    sheet.set_column_width(1, 24)?;
+    sheet.set_column_width(3, 10)?;
+    for j in 0..maps.len() {
+        sheet.set_column_width(j as u16 + 4, 12)?;
+    }
+    Ok(())
+}
+
+/// 生成包含输入参数与计算结果的工作簿
+///
+/// # Arguments
+///
+/// * `params` - 输入参数
+///
+/// * `results` - 计算结果
+///
+/// * `units` - 显示单位制
+pub fn build_workbook(
+    params: &CalcInputParameters,
+    results: &CalcResultParamters,
+    units: &UnitSystem,
+) -> Result<Workbook, XlsxError> {
+    let r2 = &results.result2;
+    let formats = Formats::new();
+    let mut workbook = Workbook::new();
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("输入参数")?;
+    write_header(
+        sheet,
+        &["序号", "字段", "名称", "符号", "数值", "单位"],
+        &formats,
+    )?;
+    write_scalar_rows(
+        sheet,
+        1,
+        None,
+        INPUT_FIELDS,
+        &field_map(params),
+        &formats,
+        units,
+    )?;
+    sheet.set_column_width(2, 30)?;
+    sheet.set_column_width(4, 14)?;
+
+    write_group_sheet(
+        &mut workbook,
+        "迭代过程",
+        RESULT1_COLUMNS,
+        &results.result1,
+        |i| format!("第{}次", i + 1),
+        &formats,
+        units,
+    )?;
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("附表")?;
+    write_header(
+        sheet,
+        &["附表", "序号", "字段", "名称", "符号", "数值", "单位"],
+        &formats,
+    )?;
+    let values = field_map(r2);
+    let next_row = write_scalar_rows(
+        sheet,
+        1,
+        Some("附表一"),
+        RESULT2_TABLE1,
+        &values,
+        &formats,
+        units,
+    )?;
+    write_scalar_rows(
+        sheet,
+        next_row,
+        Some("附表二"),
+        RESULT2_TABLE2,
+        &values,
+        &formats,
+        units,
+    )?;
+    sheet.set_column_width(3, 36)?;
+    sheet.set_column_width(5, 14)?;
+
+    let lp_name = |i: usize| format!("第{}级", i + 1);
+    let hp_name = |i: usize| format!("第{}级", i + r2.lfwx.len() + 2);
+    write_group_sheet(
+        &mut workbook,
+        "低压加热器给水",
+        FW_COLUMNS,
+        &r2.lfwx,
+        lp_name,
+        &formats,
+        units,
+    )?;
+    write_group_sheet(
+        &mut workbook,
+        "高压加热器给水",
+        FW_COLUMNS,
+        &r2.hfwx,
+        hp_name,
+        &formats,
+        units,
+    )?;
+    write_group_sheet(
+        &mut workbook,
+        "低压缸抽汽",
+        HES_COLUMNS,
+        &r2.lhes,
+        lp_name,
+        &formats,
+        units,
+    )?;
+    write_group_sheet(
+        &mut workbook,
+        "高压缸抽汽",
+        HES_COLUMNS,
+        &r2.hhes,
+        hp_name,
+        &formats,
+        units,
+    )?;
+    write_group_sheet(
+        &mut workbook,
+        "再热器抽汽",
+        RHX_COLUMNS,
+        &r2.rhx,
+        lp_name,
+        &formats,
+        units,
+    )?;
+
+    Ok(workbook)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_workbook() {
+        let mut workbook = build_workbook(
+            &CalcInputParameters::from_default(),
+            &CalcResultParamters::default(),
+            &UnitSystem::default(),
+        )
+        .unwrap();
+        assert_eq!(workbook.worksheets().len(), 8);
+        assert!(workbook.worksheet_from_name("附表").is_ok());
+        assert!(workbook.save_to_buffer().is_ok());
+    }
+}
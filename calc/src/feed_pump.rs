@@ -0,0 +1,322 @@
+//! 给水泵驱动方式
+//!
+//! 给水泵可由电动机或给水泵汽轮机驱动：
+//! - 电动给水泵的耗电由发电机出线供给，发电机功率相应增加 `N_fwp/η_fwpm`；
+//! - 给水泵汽轮机的汽源为新蒸汽或再热后蒸汽，排汽进入冷凝器或除氧器，
+//!   耗汽量 `G_sfwp = N_fwp/(η_fwptm·η_fwptg·h_a)`；
+//! - 实际焓降默认与早期版本相同，按高压缸实际焓降估算 `h_a = η_fwpti·(h_hi - h_hz)`，
+//!   仅适用于新蒸汽驱动、排汽进入冷凝器；也可按进汽、排汽压力计算
+//!   `h_a = η_fwpti·(h_fwpti - h_fwptzs)`，适用于任意汽源与排汽去向。
+//!
+//! 输入参数以数值编码选项，与其余输入参数一样可导入、导出和保存为预设，
+//! 编码不是有效选项时拒绝计算。
+use std::fmt::Display;
+
+use seuif97::*;
+
+use crate::parameters::CalcInputParameters;
+
+/// 给水泵驱动方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drive {
+    /// 电动机驱动
+    Motor,
+    /// 给水泵汽轮机驱动
+    Turbine,
+}
+
+/// 给水泵汽轮机汽源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SteamSource {
+    /// 高压缸进口新蒸汽
+    MainSteam,
+    /// 汽水分离再热器出口蒸汽
+    Reheated,
+}
+
+/// 给水泵汽轮机排汽去向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exhaust {
+    /// 排入冷凝器
+    Condenser,
+    /// 排入除氧器作为加热蒸汽
+    Deaerator,
+}
+
+/// 给水泵汽轮机实际焓降的计算方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expansion {
+    /// 按高压缸实际焓降估算，仅用于新蒸汽驱动、排汽进入冷凝器
+    HighPressure,
+    /// 按进汽、排汽压力等熵膨胀计算
+    Isentropic,
+}
+
+impl Drive {
+    /// 0为电动，1为汽动，其他取值在计算前
+    /// 由[`check_input_choices`](crate::fields::check_input_choices)拒绝
+    pub fn from_params(params: &CalcInputParameters) -> Self {
+        if params.fwp_drive == 0.0 {
+            Self::Motor
+        } else {
+            Self::Turbine
+        }
+    }
+}
+
+impl SteamSource {
+    /// 0为新蒸汽，1为再热蒸汽
+    pub fn from_params(params: &CalcInputParameters) -> Self {
+        if params.fwpt_source == 0.0 {
+            Self::MainSteam
+        } else {
+            Self::Reheated
+        }
+    }
+}
+
+impl Exhaust {
+    /// 0为冷凝器，1为除氧器
+    pub fn from_params(params: &CalcInputParameters) -> Self {
+        if params.fwpt_exhaust == 0.0 {
+            Self::Condenser
+        } else {
+            Self::Deaerator
+        }
+    }
+}
+
+/// 给水泵汽轮机耗汽取自新蒸汽、排入冷凝器及排入除氧器的份额，用于质量与能量平衡，
+/// 电动给水泵时均为0
+pub fn shares(params: &CalcInputParameters) -> (f64, f64, f64) {
+    if Drive::from_params(params) == Drive::Motor {
+        return (0.0, 0.0, 0.0);
+    }
+    let exhaust = Exhaust::from_params(params);
+    (
+        f64::from(SteamSource::from_params(params) == SteamSource::MainSteam),
+        f64::from(exhaust == Exhaust::Condenser),
+        f64::from(exhaust == Exhaust::Deaerator),
+    )
+}
+
+impl Expansion {
+    /// 0为按高压缸焓降估算，1为按进排汽压力计算
+    pub fn from_params(params: &CalcInputParameters) -> Self {
+        if params.fwpt_expansion == 0.0 {
+            Self::HighPressure
+        } else {
+            Self::Isentropic
+        }
+    }
+}
+
+impl Display for Drive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Motor => write!(f, "电动"),
+            Self::Turbine => write!(f, "汽动"),
+        }
+    }
+}
+
+impl Display for SteamSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MainSteam => write!(f, "新蒸汽"),
+            Self::Reheated => write!(f, "再热蒸汽"),
+        }
+    }
+}
+
+impl Display for Exhaust {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Condenser => write!(f, "冷凝器"),
+            Self::Deaerator => write!(f, "除氧器"),
+        }
+    }
+}
+
+impl Display for Expansion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HighPressure => write!(f, "按高压缸焓降估算"),
+            Self::Isentropic => write!(f, "按进排汽压力计算"),
+        }
+    }
+}
+
+/// 给水泵汽轮机的进出口参数
+#[derive(Debug, Clone, Copy)]
+pub struct FeedPumpTurbine {
+    pub source: SteamSource,
+    pub exhaust: Exhaust,
+    pub expansion: Expansion,
+    /// 进汽压力(MPa)
+    pub p_in: f64,
+    /// 进汽比焓(kJ/kg)
+    pub h_in: f64,
+    /// 排汽压力(MPa)
+    pub p_out: f64,
+    /// 排汽实际比焓(kJ/kg)
+    pub h_out: f64,
+}
+
+impl FeedPumpTurbine {
+    /// 按输入参数选择汽源与排汽去向并计算排汽比焓
+    ///
+    /// # Arguments
+    ///
+    /// * `main` - 高压缸进口新蒸汽的压力(MPa)与比焓(kJ/kg)
+    ///
+    /// * `reheated` - 低压缸进口再热蒸汽的压力与比焓
+    ///
+    /// * `p_lz` - 低压缸排汽压力，排入冷凝器时的排汽压力
+    ///
+    /// * `hz` - 高压缸排汽的压力与实际比焓，排入除氧器时的排汽压力
+    pub fn new(
+        params: &CalcInputParameters,
+        main: (f64, f64),
+        reheated: (f64, f64),
+        p_lz: f64,
+        hz: (f64, f64),
+    ) -> Result<Self, String> {
+        let source = SteamSource::from_params(params);
+        let exhaust = Exhaust::from_params(params);
+        let expansion = Expansion::from_params(params);
+        let (p_in, h_in) = match source {
+            SteamSource::MainSteam => main,
+            SteamSource::Reheated => reheated,
+        };
+        let p_out = match exhaust {
+            Exhaust::Condenser => p_lz,
+            Exhaust::Deaerator => hz.0,
+        };
+        if p_out >= p_in {
+            return Err(format!(
+                "给水泵汽轮机排汽压力{p_out:.4} MPa不低于进汽压力{p_in:.4} MPa，\
+                 汽源为{source}时排汽不能进入{exhaust}"
+            ));
+        }
+        // 高压缸焓降只是新蒸汽驱动、排汽进入冷凝器时的经验估算，与其余汽源或排汽压力无关
+        if expansion == Expansion::HighPressure
+            && (source, exhaust) != (SteamSource::MainSteam, Exhaust::Condenser)
+        {
+            return Err(format!(
+                "汽源为{source}、排汽进入{exhaust}时不能{expansion}给水泵汽轮机焓降，\
+                 请选择{}",
+                Expansion::Isentropic
+            ));
+        }
+        let h_a = match expansion {
+            Expansion::HighPressure => params.n_fwpti * (main.1 - hz.1),
+            Expansion::Isentropic => params.n_fwpti * (h_in - ps(p_out, ph(p_in, h_in, OS), OH)),
+        };
+        Ok(Self {
+            source,
+            exhaust,
+            expansion,
+            p_in,
+            h_in,
+            p_out,
+            h_out: h_in - h_a,
+        })
+    }
+
+    /// 实际焓降(kJ/kg)
+    pub fn h_a(&self) -> f64 {
+        self.h_in - self.h_out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turbine() {
+        let params = CalcInputParameters::from_default();
+        let main = (5.7, px(5.7, 1.0, OH));
+        let reheated = (0.7, pt(0.7, 270.0, OH));
+        let hz = (0.75, px(0.75, 0.87, OH));
+        // 默认与早期版本相同：新蒸汽驱动，排汽进入冷凝器，焓降按高压缸焓降估算
+        let turbine = FeedPumpTurbine::new(&params, main, reheated, 0.006, hz).unwrap();
+        assert_eq!(turbine.source, SteamSource::MainSteam);
+        assert_eq!(turbine.exhaust, Exhaust::Condenser);
+        assert_eq!(turbine.expansion, Expansion::HighPressure);
+        assert!((turbine.h_a() - params.n_fwpti * (main.1 - hz.1)).abs() < 1e-9);
+
+        // 按高压缸焓降估算与其余汽源或排汽去向无关
+        for (fwpt_source, fwpt_exhaust) in [(1.0, 0.0), (0.0, 1.0)] {
+            let params = CalcInputParameters {
+                fwpt_source,
+                fwpt_exhaust,
+                ..params.clone()
+            };
+            assert!(FeedPumpTurbine::new(&params, main, reheated, 0.006, hz).is_err());
+        }
+
+        let params = CalcInputParameters {
+            fwpt_exhaust: 1.0,
+            fwpt_expansion: 1.0,
+            ..params
+        };
+        let turbine = FeedPumpTurbine::new(&params, main, reheated, 0.006, hz).unwrap();
+        assert_eq!(turbine.exhaust, Exhaust::Deaerator);
+        assert!((200.0..400.0).contains(&turbine.h_a()), "{turbine:?}");
+
+        let params = CalcInputParameters {
+            fwpt_source: 1.0,
+            fwpt_exhaust: 0.0,
+            ..params
+        };
+        let turbine = FeedPumpTurbine::new(&params, main, reheated, 0.006, hz).unwrap();
+        assert_eq!(turbine.exhaust, Exhaust::Condenser);
+        assert!((500.0..900.0).contains(&turbine.h_a()), "{turbine:?}");
+
+        // 再热蒸汽压力低于除氧器加热蒸汽压力
+        let params = CalcInputParameters {
+            fwpt_exhaust: 1.0,
+            ..params
+        };
+        assert!(FeedPumpTurbine::new(&params, main, reheated, 0.006, hz).is_err());
+    }
+
+    #[test]
+    fn test_drive_mass_balance() {
+        let solve = |params: CalcInputParameters| {
+            let calculator = crate::Calculator::solve(params).unwrap();
+            calculator.results.result1.last().unwrap().clone()
+        };
+        let default = CalcInputParameters::from_default();
+        let turbine = solve(default.clone());
+        let motor = solve(CalcInputParameters {
+            fwp_drive: 0.0,
+            ..default.clone()
+        });
+        assert!(turbine.g_sfwp > 0.0);
+        assert_eq!(motor.g_sfwp, 0.0);
+        // 电动给水泵耗电由发电机供给，高压缸耗汽量增加
+        assert!(motor.g_shp > turbine.g_shp);
+
+        let condenser = solve(CalcInputParameters {
+            fwpt_source: 1.0,
+            fwpt_exhaust: 0.0,
+            fwpt_expansion: 1.0,
+            ..default
+        });
+        assert!(condenser.eta_enpp.is_finite());
+        assert!(condenser.g_sfwp > 0.0);
+
+        // 不是有效选项的编码不再按另一选项计算
+        for (fwp_drive, fwpt_source) in [(2.0, 0.0), (1.0, 0.5), (1.0, -1.0)] {
+            let params = CalcInputParameters {
+                fwp_drive,
+                fwpt_source,
+                ..CalcInputParameters::from_default()
+            };
+            assert!(crate::Calculator::solve(params).is_err());
+        }
+    }
+}
@@ -0,0 +1,668 @@
+//! 字段元数据
+//!
+//! 输入参数与计算结果各字段的名称、符号、单位、默认值及推荐范围集中定义于此，
+//! 输入界面、结果显示、范围校验与各类导出均按这些表格生成。
+use std::fmt::Display;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::parameters::CalcInputParameters;
+use crate::units::Unit::{self, *};
+use crate::units::UnitSystem;
+
+/// 输入参数的类别，决定能否作为连续变量参与灵敏度分析与不确定性抽样
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    /// 连续变化的参数
+    Continuous,
+    /// 迭代初值
+    InitialGuess,
+    /// 整数级数
+    StageCount,
+    /// 蒸汽发生器热力设计参数，不影响热平衡
+    SteamGenerator,
+    /// 离散选项
+    Choice,
+    /// 可不给定，存储为`Option<f64>`，未给定时取全局值
+    Optional,
+}
+
+impl InputKind {
+    /// 不能作为连续变量扰动或抽样的原因，连续参数为`None`
+    pub fn skip_reason(self) -> Option<&'static str> {
+        match self {
+            Self::Continuous => None,
+            Self::InitialGuess => Some("迭代初值"),
+            Self::StageCount => Some("整数级数"),
+            Self::SteamGenerator => Some("不影响热平衡"),
+            Self::Choice => Some("离散选项"),
+            Self::Optional => Some("未给定时取全局值"),
+        }
+    }
+}
+
+/// 字段元数据
+#[derive(Debug, Clone, Copy)]
+pub struct FieldMeta {
+    /// 附表中的序号
+    pub no: &'static str,
+    /// 字段名
+    pub key: &'static str,
+    /// 中文名称
+    pub label: &'static str,
+    /// 符号
+    pub symbol: &'static str,
+    /// 内部存储单位
+    pub unit: Unit,
+    /// 默认值(内部单位)，仅输入参数有
+    pub default: Option<f64>,
+    /// 推荐范围(内部单位)，仅输入参数有
+    pub range: Option<(f64, f64)>,
+    /// 输入参数的类别，计算结果均为`Continuous`
+    pub kind: InputKind,
+    /// 离散选项按数值编码0、1、…排列的名称，其余参数为空
+    pub choices: &'static [&'static str],
+}
+
+const fn row(
+    no: &'static str,
+    key: &'static str,
+    label: &'static str,
+    symbol: &'static str,
+    unit: Unit,
+) -> FieldMeta {
+    FieldMeta {
+        no,
+        key,
+        label,
+        symbol,
+        unit,
+        default: None,
+        range: None,
+        kind: InputKind::Continuous,
+        choices: &[],
+    }
+}
+
+/// 输入参数，`range`为`None`时仅给定默认值
+const fn input(
+    no: &'static str,
+    key: &'static str,
+    label: &'static str,
+    symbol: &'static str,
+    unit: Unit,
+    default: f64,
+    range: Option<(f64, f64)>,
+) -> FieldMeta {
+    FieldMeta {
+        no,
+        key,
+        label,
+        symbol,
+        unit,
+        default: Some(default),
+        range,
+        kind: InputKind::Continuous,
+        choices: &[],
+    }
+}
+
+/// 可不给定的输入参数，默认不给定
+const fn optional(
+    no: &'static str,
+    key: &'static str,
+    label: &'static str,
+    symbol: &'static str,
+    unit: Unit,
+) -> FieldMeta {
+    FieldMeta {
+        no,
+        key,
+        label,
+        symbol,
+        unit,
+        default: None,
+        range: None,
+        kind: InputKind::Optional,
+        choices: &[],
+    }
+}
+
+impl FieldMeta {
+    /// 指定输入参数的类别
+    const fn with_kind(self, kind: InputKind) -> Self {
+        Self { kind, ..self }
+    }
+
+    /// 指定离散选项按数值编码排列的名称
+    const fn with_choices(self, choices: &'static [&'static str]) -> Self {
+        Self {
+            kind: InputKind::Choice,
+            choices,
+            ..self
+        }
+    }
+}
+
+/// 加热器疏水方式的选项
+const DRAIN_MODES: &[&str] = &["逐级自流", "疏水泵打入下游", "疏水冷却器"];
+
+/// 输入参数`CalcInputParameters`
+#[rustfmt::skip]
+pub const INPUT_FIELDS: &[FieldMeta] = &[
+    input("1", "ne", "核电厂输出电功率", "N_e", MW, 1000.0, None),
+    input("2", "n_1", "一回路能量利用系数", "η_1", Fraction, 0.996, Some((0.99, 1.0))),
+    input("3", "x_fh", "蒸汽发生器出口蒸汽干度", "X_fh", Fraction, 0.9975, None),
+    input("4", "zeta_d", "蒸汽发生器排污率", "ξ_d", Fraction, 0.0105, None),
+    input("5", "n_hi", "高压缸内效率", "η_hi", Fraction, 0.8207, None),
+    input("6", "n_li", "低压缸内效率", "η_li", Fraction, 0.8359, None),
+    input("7", "n_m", "汽轮机组机械效率", "η_m", Fraction, 0.985, Some((0.98, 0.99))),
+    input("8", "n_ge", "发电机效率", "η_ge", Fraction, 0.99, Some((0.98, 0.99))),
+    input("9", "dp_fh", "新蒸汽压损占p_s", "Δp_fh", Fraction, 0.05, Some((0.03, 0.07))),
+    input("10", "dp_rh", "再热蒸汽压损占p_hz", "Δp_rh", Fraction, 0.08, Some((0.0, 0.1))),
+    input("11", "dp_ej", "回热抽汽压损占p_ej", "Δp_ej", Fraction, 0.04, Some((0.03, 0.05))),
+    input("12", "dp_cd", "低压缸排汽压损占p_cd", "Δp_cd", Fraction, 0.05, None),
+    input("13", "dp_f", "低压缸进汽流动损失占入口压力", "Δp_f", Fraction, 0.01, Some((0.0, 0.1))),
+    input("14", "theta_hu", "高压给水加热器出口端差", "θ_hu", DeltaCelsius, 3.0, None),
+    input("15", "theta_lu", "低压给水加热器出口端差", "θ_lu", DeltaCelsius, 2.0, None),
+    input("16", "n_h", "加热器效率", "η_h", Fraction, 0.98, Some((0.97, 0.99))),
+    input("17", "n_fwpp", "给水泵效率", "η_fwpp", Fraction, 0.58, None),
+    input("18", "n_fwpti", "给水泵汽轮机内效率", "η_fwpti", Fraction, 0.8, Some((0.78, 0.82))),
+    input("19", "n_fwptm", "给水泵汽轮机机械效率", "η_fwptm", Fraction, 0.9, None),
+    input("20", "n_fwptg", "给水泵汽轮机减速器效率", "η_fwptg", Fraction, 0.98, None),
+    input("21", "t_sw1", "循环冷却水进口温度", "T_sw1", Celsius, 24.0, None),
+    input("22", "ne_npp", "假定核电厂效率", "η_eNPP", Fraction, 1.0, Some((0.0, 1.0))).with_kind(InputKind::InitialGuess),
+    input("23", "g_cd", "假定冷凝器凝水量", "G_cd", KgPerS, 1200.0, None).with_kind(InputKind::InitialGuess),
+    input("24", "p_c", "反应堆冷却剂系统运行压力", "p_c", MPa, 15.5, Some((15.0, 16.0))),
+    input("25", "dt_sub", "反应堆出口冷却剂过冷度", "ΔT_sub", DeltaCelsius, 15.0, Some((15.0, 20.0))),
+    input("26", "dt_c", "反应堆进出口冷却剂温升", "ΔT_c", DeltaCelsius, 35.0, Some((30.0, 40.0))),
+    input("27", "p_s", "蒸汽发生器饱和蒸汽压力", "p_s", MPa, 6.0, Some((5.0, 7.0))),
+    input("28", "dt_sw", "冷凝器中循环冷却水温升", "ΔT_sw", DeltaCelsius, 7.0, Some((6.0, 8.0))),
+    input("29", "dt", "冷凝器传热端差", "δT", DeltaCelsius, 5.0, Some((3.0, 10.0))),
+    input("30", "dp_hz", "高压缸排汽/进口压力比", "p_hz/p_hi", Fraction, 0.13, Some((0.12, 0.14))),
+    input("31", "t_rh2z", "二级再热出口与新蒸汽温差", "ΔT_rh2z", DeltaCelsius, 15.0, Some((13.0, 15.0))),
+    input("32", "z", "回热级数", "Z", Dimensionless, 7.0, None).with_kind(InputKind::StageCount),
+    input("33", "z_l", "低压给水加热器级数", "Z_l", Dimensionless, 4.0, None).with_kind(InputKind::StageCount),
+    input("34", "z_h", "高压给水加热器级数", "Z_h", Dimensionless, 2.0, None).with_kind(InputKind::StageCount),
+    input("35", "dt_fw", "实际/最佳给水温度比", "T_fw/T_fwop", Fraction, 0.85, Some((0.85, 0.9))),
+    input("36", "dp_fwpo", "给水泵出口压力(x倍p_s)", "k_fwpo", Dimensionless, 1.2, Some((1.15, 1.25))),
+    input("37", "dp_cwp", "凝水泵出口压力(x倍p_dea)", "k_cwp", Dimensionless, 3.1, Some((3.0, 3.2))),
+    input("38", "n_sg", "一回路环路数(蒸汽发生器台数)", "n_SG", Dimensionless, 3.0, None).with_kind(InputKind::SteamGenerator),
+    input("39", "k_sg", "蒸汽发生器总传热系数(0为按管束计算)", "K_SG", KwPerM2K, 0.0, None).with_kind(InputKind::SteamGenerator),
+    input("40", "d_sgo", "传热管外径", "d_o", Millimeter, 19.05, None).with_kind(InputKind::SteamGenerator),
+    input("41", "delta_sg", "传热管壁厚", "δ_t", Millimeter, 1.09, None).with_kind(InputKind::SteamGenerator),
+    input("42", "l_sg", "传热管平均长度", "L_t", Meter, 20.3, None).with_kind(InputKind::SteamGenerator),
+    input("43", "lambda_sg", "传热管导热系数", "λ_t", WPerMK, 17.0, None).with_kind(InputKind::SteamGenerator),
+    input("44", "alpha_2", "二次侧沸腾换热系数", "α_2", KwPerM2K, 35.0, None).with_kind(InputKind::SteamGenerator),
+    input("45", "r_sg", "传热管污垢热阻", "R_f", M2KPerKw, 0.03, None).with_kind(InputKind::SteamGenerator),
+    input("46", "h_rcp", "主泵扬程", "H_RCP", Meter, 100.0, None),
+    input("47", "n_rcp", "主泵效率", "η_RCP", Fraction, 0.8, Some((0.75, 0.85))),
+    input("48", "k_rcp", "主泵热量计入热平衡的份额(0为不计入)", "k_RCP", Fraction, 0.0, None),
+    input("49", "fwp_drive", "给水泵驱动方式(0电动,1汽动)", "D_fwp", Dimensionless, 1.0, None).with_choices(&["电动", "汽动"]),
+    input("50", "fwpt_source", "给水泵汽轮机汽源(0新蒸汽,1再热蒸汽)", "S_fwpt", Dimensionless, 0.0, None).with_choices(&["新蒸汽", "再热蒸汽"]),
+    input("51", "fwpt_exhaust", "给水泵汽轮机排汽去向(0冷凝器,1除氧器)", "E_fwpt", Dimensionless, 0.0, None).with_choices(&["冷凝器", "除氧器"]),
+    input("52", "n_fwpm", "给水泵电动机效率", "η_fwpm", Fraction, 0.95, None),
+    input("53", "drain_1", "第一级加热器疏水方式(0自流,1泵送,2疏水冷却器)", "D_1", Dimensionless, 0.0, None).with_choices(DRAIN_MODES),
+    input("54", "drain_2", "第二级加热器疏水方式(0自流,1泵送,2疏水冷却器)", "D_2", Dimensionless, 0.0, None).with_choices(DRAIN_MODES),
+    input("55", "drain_3", "第三级加热器疏水方式(0自流,1泵送,2疏水冷却器)", "D_3", Dimensionless, 0.0, None).with_choices(DRAIN_MODES),
+    input("56", "drain_4", "第四级加热器疏水方式(0自流,1泵送,2疏水冷却器)", "D_4", Dimensionless, 0.0, None).with_choices(DRAIN_MODES),
+    input("57", "drain_6", "第六级加热器疏水方式(0自流,1泵送,2疏水冷却器)", "D_6", Dimensionless, 0.0, None).with_choices(DRAIN_MODES),
+    input("58", "drain_7", "第七级加热器疏水方式(0自流,1泵送,2疏水冷却器)", "D_7", Dimensionless, 0.0, None).with_choices(DRAIN_MODES),
+    input("59", "theta_dc", "疏水冷却器端差", "θ_dc", DeltaCelsius, 5.6, Some((5.0, 10.0))),
+    optional("60", "theta_u1", "第一级加热器出口端差(留空取全局值)", "θ_u1", DeltaCelsius),
+    optional("61", "theta_u2", "第二级加热器出口端差(留空取全局值)", "θ_u2", DeltaCelsius),
+    optional("62", "theta_u3", "第三级加热器出口端差(留空取全局值)", "θ_u3", DeltaCelsius),
+    optional("63", "theta_u4", "第四级加热器出口端差(留空取全局值)", "θ_u4", DeltaCelsius),
+    optional("64", "theta_u6", "第六级加热器出口端差(留空取全局值)", "θ_u6", DeltaCelsius),
+    optional("65", "theta_u7", "第七级加热器出口端差(留空取全局值)", "θ_u7", DeltaCelsius),
+    optional("66", "theta_dc1", "第一级疏水冷却器端差(留空取全局值)", "θ_dc1", DeltaCelsius),
+    optional("67", "theta_dc2", "第二级疏水冷却器端差(留空取全局值)", "θ_dc2", DeltaCelsius),
+    optional("68", "theta_dc3", "第三级疏水冷却器端差(留空取全局值)", "θ_dc3", DeltaCelsius),
+    optional("69", "theta_dc4", "第四级疏水冷却器端差(留空取全局值)", "θ_dc4", DeltaCelsius),
+    optional("70", "theta_dc6", "第六级疏水冷却器端差(留空取全局值)", "θ_dc6", DeltaCelsius),
+    optional("71", "theta_dc7", "第七级疏水冷却器端差(留空取全局值)", "θ_dc7", DeltaCelsius),
+    input("72", "fw_alloc", "给水焓升分配方式(0等焓升,1等温升,2几何分配,3自定义)", "A_fw", Dimensionless, 0.0, None).with_choices(&["等焓升", "等温升", "几何分配", "自定义"]),
+    input("73", "q_fw", "几何分配给水焓升公比", "q_fw", Dimensionless, 1.1, Some((0.8, 1.25))),
+    input("74", "w_fw1", "第一级加热器给水焓升权重", "w_fw1", Dimensionless, 1.0, None),
+    input("75", "w_fw2", "第二级加热器给水焓升权重", "w_fw2", Dimensionless, 1.0, None),
+    input("76", "w_fw3", "第三级加热器给水焓升权重", "w_fw3", Dimensionless, 1.0, None),
+    input("77", "w_fw4", "第四级加热器给水焓升权重", "w_fw4", Dimensionless, 1.0, None),
+    input("78", "w_fwd", "除氧器给水焓升权重", "w_fwd", Dimensionless, 1.0, None),
+    input("79", "w_fw6", "第六级加热器给水焓升权重", "w_fw6", Dimensionless, 1.0, None),
+    input("80", "w_fw7", "第七级加热器给水焓升权重", "w_fw7", Dimensionless, 1.0, None),
+    input("81", "fwpt_expansion", "给水泵汽轮机焓降(0按高压缸焓降估算,1按进排汽压力计算)", "X_fwpt", Dimensionless, 0.0, None).with_choices(&["按高压缸焓降估算", "按进排汽压力计算"]),
+];
+
+/// 热平衡计算结果`CalcResult1`各列
+#[rustfmt::skip]
+pub const RESULT1_COLUMNS: &[FieldMeta] = &[
+    row("1", "eta_enpp", "核电厂效率", "η_eNPP", Fraction),
+    row("2", "q_r", "反应堆热功率", "Q_R", GW),
+    row("3", "d_s", "蒸汽发生器总蒸汽产量", "D_s", KgPerS),
+    row("4", "g_shp", "汽轮机高压缸耗气量", "G_shp", KgPerS),
+    row("5", "g_slp", "汽轮机低压缸耗气量", "G_slp", KgPerS),
+    row("6", "g_srh1", "第一级再热器耗气量", "G_srh1", KgPerS),
+    row("7", "g_srh2", "第二级再热器耗气量", "G_srh2", KgPerS),
+    row("8", "g_sdea", "除氧器耗气量", "G_sdea", KgPerS),
+    row("9", "g_sfwp", "给水泵汽轮机耗气量", "G_sfwp", KgPerS),
+    row("10", "g_fw", "给水泵给水量", "G_fw", KgPerS),
+    row("11", "h_fwp", "给水泵扬程", "H_fwp", MPa),
+    row("12.1", "g_hes7", "第七级抽汽量", "G_hes7", KgPerS),
+    row("12.2", "g_hes6", "第六级抽汽量", "G_hes6", KgPerS),
+    row("13.1", "g_les4", "第四级抽汽量", "G_les4", KgPerS),
+    row("13.2", "g_les3", "第三级抽汽量", "G_les3", KgPerS),
+    row("13.3", "g_les2", "第二级抽汽量", "G_les2", KgPerS),
+    row("13.4", "g_les1", "第一级抽汽量", "G_les1", KgPerS),
+    row("14", "g_cd", "凝结水量", "G_cd", KgPerS),
+    row("15", "g_uw", "汽水分离器疏水量", "G_uw", KgPerS),
+    row("16", "g_zc1", "一级再热器加热蒸汽量", "G_zc1", KgPerS),
+    row("17", "g_zc2", "二级再热器加热蒸汽量", "G_zc2", KgPerS),
+];
+
+/// 附表一
+#[rustfmt::skip]
+pub const RESULT2_TABLE1: &[FieldMeta] = &[
+    row("1", "ne", "核电厂输出功率", "N_e", MW),
+    row("2", "eta_1", "一回路能量利用系数", "η_1", Fraction),
+    row("3", "x_fh", "蒸汽发生器出口蒸汽干度", "X_fh", Fraction),
+    row("4", "zeta_d", "蒸汽发生器排污率", "ξ_d", Fraction),
+    row("5", "eta_hi", "高压缸内效率", "η_hi", Fraction),
+    row("6", "eta_li", "低压缸内效率", "η_li", Fraction),
+    row("7", "eta_m", "汽轮机组机械效率", "η_m", Fraction),
+    row("8", "eta_ge", "发电机效率", "η_ge", Fraction),
+    row("9", "dp_fh", "新蒸汽压损", "Δp_fh", Fraction),
+    row("10", "dp_rh", "再热蒸汽压损", "Δp_rh", MPa),
+    row("11", "dp_ej", "回热蒸汽压损", "Δp_ej", Fraction),
+    row("12", "dp_cd", "低压缸排气压损", "Δp_cd", Fraction),
+    row("13", "theta_hu", "高压给水加热器出口端差", "θ_hu", DeltaCelsius),
+    row("14", "theta_lu", "低压给水加热器出口端差", "θ_lu", DeltaCelsius),
+    row("15", "eta_h", "加热器效率", "η_h", Fraction),
+    row("16", "eta_fwpp", "给水泵效率", "η_fwpp", Fraction),
+    row("17", "eta_fwpti", "给水泵汽轮机内效率", "η_fwpti", Fraction),
+    row("18", "eta_fwptm", "给水泵汽轮机机械效率", "η_fwptm", Fraction),
+    row("19", "eta_fwptg", "给水泵汽轮机减速器效率", "η_fwptg", Fraction),
+    row("20", "t_sw1", "循环冷却水进口温度", "T_sw1", Celsius),
+];
+
+/// 附表二中的标量参数
+#[rustfmt::skip]
+pub const RESULT2_TABLE2: &[FieldMeta] = &[
+    row("1", "p_c", "反应堆冷却剂系统运行压力", "p_c", MPa),
+    row("2", "t_cs", "冷却剂压力对应的饱和温度", "T_cs", Celsius),
+    row("3", "dt_sub", "反应堆出口冷却剂过冷度", "ΔT_sub", DeltaCelsius),
+    row("4", "t_co", "反应堆出口冷却剂温度", "T_co", Celsius),
+    row("5", "dt_c", "反应堆进出口冷却剂温升", "ΔT_c", DeltaCelsius),
+    row("6", "t_ci", "反应堆进口冷却剂温度", "T_ci", Celsius),
+    row("7", "p_s", "蒸汽发生器饱和蒸汽压力", "p_s", MPa),
+    row("8", "t_fh", "蒸汽发生器饱和蒸汽温度", "T_fh", Celsius),
+    row("9", "dt_m", "一、二次侧对数平均温差", "ΔT_m", DeltaCelsius),
+    row("10", "dt_sw", "冷凝器中循环冷却水温升", "ΔT_sw", DeltaCelsius),
+    row("11", "dt", "冷凝器传热端差", "δT", DeltaCelsius),
+    row("12", "t_cd", "冷凝器凝结水饱和温度", "T_cd", Celsius),
+    row("13", "p_cd", "冷凝器的运行压力", "p_cd", MPa),
+    row("14", "p_hi", "高压缸进口的蒸汽压力", "p_hi", MPa),
+    row("15", "x_hi", "高压缸进口蒸汽干度", "X_hi", Fraction),
+    row("15.1", "h_fh", "蒸汽发生器出口蒸汽比焓", "h_fh", KjPerKg),
+    row("15.2", "s_fh", "蒸汽发生器出口蒸汽比熵", "s_fh", KjPerKgK),
+    row("15.3", "s_hi", "高压缸进口蒸汽比熵", "s_hi", KjPerKgK),
+    row("16", "p_hz", "高压缸排气压力", "p_hz", MPa),
+    row("17", "x_hz", "高压缸排气干度", "X_hz", Fraction),
+    row("17.1", "h_hi", "高压缸进口蒸汽比焓", "h_hi", KjPerKg),
+    row("17.2", "h_hzs", "高压缸出口理想比焓", "h_hzs", KjPerKg),
+    row("17.3", "h_hz", "高压缸出口蒸汽比焓", "h_hz", KjPerKg),
+    row("18", "p_spi", "汽水分离器进口蒸汽压力", "p_spi", MPa),
+    row("19", "x_spi", "汽水分离器进口蒸汽干度", "X_spi", Fraction),
+    row("19.1", "p_uw", "汽水分离器出口疏水压力", "p_uw", MPa),
+    row("19.2", "h_uw", "汽水分离器出口疏水比焓", "h_uw", KjPerKg),
+    row("20", "p_rh1i", "一级再热蒸汽进口压力", "p_rh1i", MPa),
+    row("21", "x_rh1i", "一级再热蒸汽进口干度", "X_rh1i", Fraction),
+    row("21.1", "h_rh1i", "一级再热器进口蒸汽比焓", "h_rh1i", KjPerKg),
+    row("22", "p_rh1hs", "一级再热加热蒸汽进口压力", "p_rh1hs", MPa),
+    row("23", "x_rh1hs", "一级再热加热蒸汽进口干度", "X_rh1hs", Fraction),
+    row("24", "p_rh2i", "二级再热蒸汽进口压力", "p_rh2i", MPa),
+    row("25", "t_rh2i", "二级再热蒸汽进口温度", "T_rh2i", Celsius),
+    row("26", "p_rh2z", "二级再热蒸汽出口压力", "p_rh2z", MPa),
+    row("27", "t_rh2z", "二级再热蒸汽出口温度", "T_rh2z", Celsius),
+    row("27.1", "h_rh2z", "二级再热器出口比焓", "h_rh2z", KjPerKg),
+    row("27.2", "dh_rh", "每级再热器平均焓升", "Δh_rh", KjPerKg),
+    row("27.3", "h_rh1z", "一级再热器出口蒸汽比焓", "h_rh1z", KjPerKg),
+    row("27.4", "h_rh2i", "二级再热器进口蒸汽比焓", "h_rh2i", KjPerKg),
+    row("28", "p_rh2hs", "二级再热加热蒸汽进口压力", "p_rh2hs", MPa),
+    row("29", "x_rh2hs", "二级再热加热蒸汽进口干度", "X_rh2hs", Fraction),
+    row("30", "p_li", "低压缸进口蒸汽压力", "p_li", MPa),
+    row("31", "t_li", "低压缸进口蒸汽温度", "T_li", Celsius),
+    row("32", "p_lz", "低压缸排汽压力", "p_lz", MPa),
+    row("33", "x_lz", "低压缸排汽干度", "X_lz", Fraction),
+    row("33.1", "s_li", "低压缸进口蒸汽比熵", "s_li", KjPerKgK),
+    row("33.2", "h_li", "低压缸进口蒸汽比焓", "h_li", KjPerKg),
+    row("33.3", "h_lzs", "低压缸出口理想比焓", "h_lzs", KjPerKg),
+    row("33.4", "h_lz", "低压缸出口蒸汽比焓", "h_lz", KjPerKg),
+    row("34", "z", "回热级数", "Z", Dimensionless),
+    row("35", "z_l", "低压给水加热器级数", "Z_l", Dimensionless),
+    row("36", "z_h", "高压给水加热器级数", "Z_h", Dimensionless),
+    row("37", "dh_fw", "第一次给水回热分配", "Δh_fw", KjPerKg),
+    row("37.1", "h_s", "蒸汽发生器运行压力饱和水比焓", "h_s", KjPerKg),
+    row("37.2", "h_cd", "冷凝器出口凝结水比焓", "h_cd", KjPerKg),
+    row("37.3", "dh_fwop", "每级加热器理论给水焓升", "Δh_fwop", KjPerKg),
+    row("37.4", "h_fwop", "最佳给水比焓", "h_fwop", KjPerKg),
+    row("37.5", "t_fwop", "最佳给水温度", "T_fwop", Celsius),
+    row("37.6", "t_fw", "实际给水温度", "T_fw", Celsius),
+    row("37.7", "h_fw", "实际给水比焓", "h_fw", KjPerKg),
+    row("38", "dh_fwh", "高压加热器给水焓升", "Δh_fwh", KjPerKg),
+    row("38.1", "p_dea", "除氧器运行压力", "p_dea", MPa),
+    row("38.2", "h_deao", "除氧器出口饱和水比焓", "h_deao", KjPerKg),
+    row("39", "dh_fwl", "除氧器及低压加热器给水焓升", "Δh_fwl", KjPerKg),
+    row("39.1", "p_cwp", "凝水泵出口给水压力", "p_cwp", MPa),
+    row("39.2", "h_cwp", "凝水泵出口给水比焓", "h_cwp", KjPerKg),
+    row("39.3", "dp_cws", "凝水泵出口至除氧器出口的阻力压降", "Δp_cws", MPa),
+    row("39.4", "dp_fi", "每级低压加热器及除氧器的阻力压降", "Δp_fi", MPa),
+    row("41", "h_deai", "除氧器进口给水比焓", "h_deai", KjPerKg),
+    row("42", "h_deao1", "除氧器出口给水比焓", "h_deao", KjPerKg),
+    row("43", "t_dea", "除氧器出口给水温度", "T_dea", Celsius),
+    row("44", "p_dea1", "除氧器运行压力", "p_dea", MPa),
+    row("44.1", "p_fwpo", "给水泵出口压力", "p_fwpo", MPa),
+    row("44.2", "h_fwpo", "给水泵出口流体比焓", "h_fwpo", KjPerKg),
+    row("44.3", "p_fwi", "蒸汽发生器进口给水压力", "p_fwi", MPa),
+    row("44.4", "n_fwpd", "给水泵驱动功率", "N_fwpd", MW),
+    row("44.5", "h_fwpti", "给水泵汽轮机进汽比焓", "h_fwpti", KjPerKg),
+    row("44.6", "h_fwptz", "给水泵汽轮机排汽比焓", "h_fwptz", KjPerKg),
+    row("44.7", "n_dp", "疏水泵耗电功率", "N_dp", MW),
+    row("44.8", "g_dcd", "低压加热器流入冷凝器的疏水流量", "G_dcd", KgPerS),
+    row("44.9", "h_dcd", "低压加热器流入冷凝器的疏水比焓", "h_dcd", KjPerKg),
+    row("46.1", "s_hi1", "高压缸进口蒸汽比熵", "s_hi", KjPerKgK),
+    row("46.2", "h_hi1", "高压缸进口蒸汽比焓", "h_hi", KjPerKg),
+    row("47.1", "s_li1", "低压缸进口蒸汽比熵", "s_li", KjPerKgK),
+    row("47.2", "h_li1", "低压缸进口蒸汽比焓", "h_li", KjPerKg),
+    row("49", "g_c", "一回路冷却剂总质量流量", "G_c", KgPerS),
+    row("49.1", "g_cloop", "每环路冷却剂质量流量", "G_c1", KgPerS),
+    row("49.2", "v_cloop", "每环路冷却剂体积流量", "V_c1", CubicMeterPerSecond),
+    row("49.3", "n_rcp1", "每台主泵轴功率", "N_RCP1", MW),
+    row("49.4", "n_rcp", "主泵总轴功率", "N_RCP", MW),
+    row("50", "k_sg", "蒸汽发生器总传热系数", "K_SG", KwPerM2K),
+    row("50.1", "alpha_1", "一次侧对流换热系数", "α_1", KwPerM2K),
+    row("50.2", "w_c", "传热管内冷却剂流速", "w_c", MeterPerSecond),
+    row("51", "f_sg", "蒸汽发生器总传热面积", "F_SG", SquareMeter),
+    row("51.1", "f_sg1", "每台蒸汽发生器传热面积", "F_SG1", SquareMeter),
+    row("52", "n_tsg", "每台蒸汽发生器传热管数", "N_t", Dimensionless),
+];
+
+/// 给水加热器`CalcFWParameters`各列
+#[rustfmt::skip]
+pub const FW_COLUMNS: &[FieldMeta] = &[
+    row("", "p_fwxi", "进口给水压力", "p_fwxi", MPa),
+    row("", "h_fwxi", "进口给水比焓", "h_fwxi", KjPerKg),
+    row("", "t_fwxi", "进口给水温度", "T_fwxi", Celsius),
+    row("", "p_fwxo", "出口给水压力", "p_fwxo", MPa),
+    row("", "h_fwxo", "出口给水比焓", "h_fwxo", KjPerKg),
+    row("", "t_fwxo", "出口给水温度", "T_fwxo", Celsius),
+    row("", "t_roxk", "汽侧疏水温度", "T_roxk", Celsius),
+    row("", "h_roxk", "汽侧疏水比焓", "h_roxk", KjPerKg),
+    row("", "g_fwx", "管侧给水流量", "G_fwx", KgPerS),
+    row("", "g_dx", "离开汽侧的疏水流量", "G_dx", KgPerS),
+    row("", "h_dx", "离开汽侧的疏水比焓", "h_dx", KjPerKg),
+    row("", "dh_fwx", "给水焓升", "Δh_fwx", KjPerKg),
+];
+
+/// 加热器抽汽`CalcHESParameters`各列
+#[rustfmt::skip]
+pub const HES_COLUMNS: &[FieldMeta] = &[
+    row("", "t_hesx", "抽汽温度", "T_hesx", Celsius),
+    row("", "p_hesx", "抽汽压力", "p_hesx", MPa),
+    row("", "x_hesx", "抽汽干度", "X_hesx", Fraction),
+    row("", "h_hesxs", "抽汽理想比焓", "h_hesxs", KjPerKg),
+    row("", "h_hesx", "抽汽比焓", "h_hesx", KjPerKg),
+];
+
+/// 再热器抽汽`CalcRHXParameters`各列
+#[rustfmt::skip]
+pub const RHX_COLUMNS: &[FieldMeta] = &[
+    row("", "p_rhx", "加热蒸汽进口压力", "p_rhx", MPa),
+    row("", "x_rhx", "加热蒸汽进口干度", "X_rhx", Fraction),
+    row("", "t_rhx", "加热蒸汽进口温度", "T_rhx", Celsius),
+    row("", "h_rhx", "加热蒸汽进口比焓", "h_rhx", KjPerKg),
+    row("", "h_zsx", "再热器疏水比焓", "h_zsx", KjPerKg),
+];
+
+/// 将结构体按字段名展开，便于按表格定义取值
+pub fn field_map<T: Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// 按字段名取数值，字段不存在或未给定时返回`NaN`
+pub fn field_value(map: &Map<String, Value>, key: &str) -> f64 {
+    map.get(key).and_then(Value::as_f64).unwrap_or(f64::NAN)
+}
+
+/// 按字段名替换输入参数中的若干数值，`NaN`表示可选参数未给定，
+/// 字段不存在或数值不能写入该字段时返回错误
+pub fn with_input_values(
+    params: &CalcInputParameters,
+    values: &[(&str, f64)],
+) -> Result<CalcInputParameters, String> {
+    let mut map = field_map(params);
+    for (key, value) in values {
+        if !map.contains_key(*key) {
+            return Err(format!("输入参数中没有字段{key}"));
+        }
+        map.insert(key.to_string(), (*value).into());
+    }
+    serde_json::from_value(map.into()).map_err(|error| format!("输入参数无效: {error}"))
+}
+
+/// 按字段名查找输入参数的元数据
+pub fn input_field(key: &str) -> Option<&'static FieldMeta> {
+    INPUT_FIELDS.iter().find(|field| field.key == key)
+}
+
+impl FieldMeta {
+    /// 是否为可不给定的输入参数
+    pub fn is_optional(&self) -> bool {
+        self.kind == InputKind::Optional
+    }
+
+    /// 离散选项的取值对应的选项序号，取值不是有效的数值编码时为`None`
+    pub fn choice_index(&self, value: f64) -> Option<usize> {
+        (value >= 0.0 && value.fract() == 0.0 && value < self.choices.len() as f64)
+            .then_some(value as usize)
+    }
+
+    /// 按单位制给出输入提示：有推荐范围时为`下限 ~ 上限`，否则为默认值
+    pub fn hint(&self, units: &UnitSystem) -> String {
+        if self.is_optional() {
+            return String::from("留空取全局值");
+        }
+        match (self.range, self.default) {
+            (Some((lo, hi)), _) => {
+                format!(
+                    "{} ~ {}",
+                    units.format(self.unit, lo),
+                    units.format(self.unit, hi)
+                )
+            }
+            (None, Some(default)) => units.format(self.unit, default),
+            (None, None) => String::new(),
+        }
+    }
+
+    /// 按单位制给出带单位的名称，如`反应堆冷却剂系统运行压力(MPa)`
+    pub fn label_with_unit(&self, units: &UnitSystem) -> String {
+        match units.label(self.unit) {
+            "" => self.label.to_string(),
+            unit => format!("{}({unit})", self.label),
+        }
+    }
+}
+
+/// 超出推荐范围的输入参数
+#[derive(Debug, Clone, Copy)]
+pub struct RangeWarning {
+    pub field: &'static FieldMeta,
+    /// 输入值(内部单位)
+    pub value: f64,
+}
+
+impl RangeWarning {
+    /// 按单位制生成提示信息
+    pub fn message(&self, units: &UnitSystem) -> String {
+        format!(
+            "{}({})={} 超出推荐范围 {}",
+            self.field.label,
+            self.field.key,
+            units.format(self.field.unit, self.value),
+            self.field.hint(units)
+        )
+    }
+}
+
+impl Display for RangeWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message(&UnitSystem::default()))
+    }
+}
+
+/// 检查输入参数是否位于推荐范围内，返回超出范围的参数
+pub fn check_input_ranges(params: &CalcInputParameters) -> Vec<RangeWarning> {
+    let values = field_map(params);
+    INPUT_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let (lo, hi) = field.range?;
+            let value = field_value(&values, field.key);
+            // 允许浮点误差
+            let tolerance = 1e-9 * (hi - lo).abs().max(1.0);
+            (!(lo - tolerance..=hi + tolerance).contains(&value))
+                .then_some(RangeWarning { field, value })
+        })
+        .collect()
+}
+
+/// 检查离散选项的取值，取值不是有效的数值编码时返回错误
+pub fn check_input_choices(params: &CalcInputParameters) -> Result<(), String> {
+    let values = field_map(params);
+    INPUT_FIELDS
+        .iter()
+        .filter(|field| !field.choices.is_empty())
+        .try_for_each(|field| {
+            let value = field_value(&values, field.key);
+            match field.choice_index(value) {
+                Some(_) => Ok(()),
+                None => Err(format!(
+                    "{}({})={value} 不是有效选项",
+                    field.label, field.key
+                )),
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_defaults() {
+        let defaults = field_map(&CalcInputParameters::from_default());
+        assert_eq!(INPUT_FIELDS.len(), defaults.len());
+        for field in INPUT_FIELDS {
+            if field.is_optional() {
+                assert!(defaults[field.key].is_null(), "{}", field.key);
+                continue;
+            }
+            let default = field_value(&defaults, field.key);
+            assert!(
+                (field.default.unwrap() - default).abs() < 1e-12,
+                "{}",
+                field.key
+            );
+        }
+        // 字段名不重复且覆盖全部输入参数：由各字段默认值构造的输入参数与`from_default`逐字段相同
+        let values: Vec<_> = INPUT_FIELDS
+            .iter()
+            .map(|field| (field.key, field.default.unwrap_or(f64::NAN)))
+            .collect();
+        let built =
+            field_map(&with_input_values(&CalcInputParameters::default(), &values).unwrap());
+        for (key, default) in &defaults {
+            let value = &built[key];
+            assert_eq!(value.is_null(), default.is_null(), "{key}");
+            assert!(
+                value.is_null()
+                    || (field_value(&built, key) - field_value(&defaults, key)).abs() < 1e-12,
+                "{key}"
+            );
+        }
+        assert!(check_input_ranges(&CalcInputParameters::from_default()).is_empty());
+    }
+
+    #[test]
+    fn test_with_input_values() {
+        let default = CalcInputParameters::from_default();
+        let params = with_input_values(&default, &[("p_s", 6.0), ("theta_u1", 4.0)]).unwrap();
+        assert_eq!(params.p_s, 6.0);
+        assert_eq!(params.theta_u1, Some(4.0));
+        let params = with_input_values(&params, &[("theta_u1", f64::NAN)]).unwrap();
+        assert_eq!(params.theta_u1, None);
+        assert!(with_input_values(&default, &[("p_x", 6.0)]).is_err());
+        assert!(with_input_values(&default, &[("p_s", f64::NAN)]).is_err());
+    }
+
+    #[test]
+    fn test_input_kinds() {
+        // 只有连续参数给出推荐范围
+        for field in INPUT_FIELDS {
+            if field.range.is_some() && field.key != "ne_npp" {
+                assert_eq!(field.kind, InputKind::Continuous, "{}", field.key);
+            }
+        }
+        let reason = |key| input_field(key).unwrap().kind.skip_reason();
+        assert_eq!(reason("g_cd"), Some("迭代初值"));
+        assert_eq!(reason("z_l"), Some("整数级数"));
+        assert_eq!(reason("fwpt_expansion"), Some("离散选项"));
+        assert_eq!(reason("theta_dc7"), Some("未给定时取全局值"));
+        assert_eq!(reason("theta_dc"), None);
+    }
+
+    #[test]
+    fn test_check_input_choices() {
+        assert!(check_input_choices(&CalcInputParameters::from_default()).is_ok());
+        let field = input_field("fwpt_source").unwrap();
+        assert_eq!(field.choice_index(1.0), Some(1));
+        for value in [-1.0, 0.5, 2.0, f64::NAN] {
+            assert_eq!(field.choice_index(value), None, "{value}");
+        }
+        let params = CalcInputParameters {
+            fwp_drive: 2.0,
+            ..CalcInputParameters::from_default()
+        };
+        assert_eq!(
+            check_input_choices(&params).unwrap_err(),
+            "给水泵驱动方式(0电动,1汽动)(fwp_drive)=2 不是有效选项"
+        );
+    }
+
+    #[test]
+    fn test_check_input_ranges() {
+        let params = CalcInputParameters {
+            p_c: 17.0,
+            dt_fw: 0.8,
+            ..CalcInputParameters::from_default()
+        };
+        let warnings = check_input_ranges(&params);
+        let keys: Vec<_> = warnings.iter().map(|w| w.field.key).collect();
+        assert_eq!(keys, vec!["p_c", "dt_fw"]);
+        let units: UnitSystem = "bar,%".parse().unwrap();
+        assert_eq!(
+            warnings[0].message(&units),
+            "反应堆冷却剂系统运行压力(p_c)=170 超出推荐范围 150 ~ 160"
+        );
+        assert_eq!(input_field("p_s").unwrap().hint(&units), "50 ~ 70");
+        assert_eq!(input_field("x_fh").unwrap().hint(&units), "99.75");
+        assert_eq!(
+            input_field("theta_u1").unwrap().hint(&units),
+            "留空取全局值"
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! 计算历史
+//!
+//! 每次计算成功后保存输入参数、结果、时间及收敛情况。可选地将每条记录以`<编号>.json`
+//! 保存在历史目录中，下次启动时读取。
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::compare::CalcRun;
+use crate::parameters::CalcResult1;
+
+/// 保留的未固定记录数，超出时删除最早的未固定记录
+pub const MAX_UNPINNED: usize = 50;
+
+/// 迭代收敛情况
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ConvergenceStats {
+    /// 迭代次数
+    pub iterations: usize,
+    /// 最后两次迭代核电厂效率之差
+    pub eta_residual: f64,
+    /// 最后两次迭代凝结水量的相对变化
+    pub g_cd_residual: f64,
+}
+
+impl ConvergenceStats {
+    /// 由一次计算的迭代过程统计
+    pub fn from_iterations(iterations: &[CalcResult1]) -> Self {
+        let mut stats = Self {
+            iterations: iterations.len(),
+            ..Self::default()
+        };
+        if let [.., prev, last] = iterations {
+            stats.eta_residual = (last.eta_enpp - prev.eta_enpp).abs();
+            stats.g_cd_residual = (last.g_cd - prev.g_cd).abs() / prev.g_cd.abs();
+        }
+        stats
+    }
+}
+
+/// 一条计算历史
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// 编号，按计算顺序递增
+    pub id: u64,
+    /// 计算时间，自1970-01-01 00:00:00 UTC起的秒数
+    pub timestamp: u64,
+    /// 固定的记录不会被自动删除
+    #[serde(default)]
+    pub pinned: bool,
+    pub stats: ConvergenceStats,
+    pub run: CalcRun,
+}
+
+impl HistoryEntry {
+    /// 计算时间，格式为`YYYY-MM-DD HH:MM:SS`(UTC)
+    pub fn time(&self) -> String {
+        let days = (self.timestamp / 86400) as i64;
+        let secs = self.timestamp % 86400;
+        // 由1970-01-01起的天数换算公历日期
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        format!(
+            "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )
+    }
+}
+
+/// 计算历史，按编号从小到大排列
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+    next_id: u64,
+    /// 历史目录，为`None`时只保存在内存中
+    dir: Option<PathBuf>,
+}
+
+impl History {
+    /// 读取历史目录中的记录，之后的修改均同步保存到该目录
+    pub fn open(dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let mut history = Self::default();
+        history.set_dir(Some(dir.into()))?;
+        Ok(history)
+    }
+
+    /// 全部记录
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: u64) -> Option<&HistoryEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// 下一条记录的编号
+    pub fn next_id(&self) -> u64 {
+        self.next_id.max(1)
+    }
+
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// 设置历史目录
+    ///
+    /// 目录中已有的记录与内存中的记录合并，并将内存中的记录写入目录；
+    /// 设为`None`时停止保存，已保存的文件保留。
+    pub fn set_dir(&mut self, dir: Option<PathBuf>) -> std::io::Result<()> {
+        self.dir = dir;
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(dir)?;
+        let mut loaded = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|extension| extension != "json") {
+                continue;
+            }
+            let reader = BufReader::new(File::open(&path)?);
+            let entry: HistoryEntry = serde_json::from_reader(reader).map_err(|error| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{}: {error}", path.display()),
+                )
+            })?;
+            loaded.push(entry);
+        }
+        // 内存中的记录重新编号，排在已保存的记录之后
+        let mut next_id = loaded.iter().map(|entry| entry.id + 1).max().unwrap_or(1);
+        for mut entry in std::mem::take(&mut self.entries) {
+            entry.id = next_id;
+            next_id += 1;
+            self.save(&entry)?;
+            loaded.push(entry);
+        }
+        loaded.sort_by_key(|entry| entry.id);
+        self.entries = loaded;
+        self.next_id = next_id;
+        self.prune()
+    }
+
+    /// 添加一次计算，返回新记录
+    pub fn push(&mut self, run: CalcRun) -> std::io::Result<&HistoryEntry> {
+        let entry = HistoryEntry {
+            id: self.next_id(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs()),
+            pinned: false,
+            stats: ConvergenceStats::from_iterations(&run.results.result1),
+            run,
+        };
+        self.next_id = entry.id + 1;
+        self.save(&entry)?;
+        self.entries.push(entry);
+        self.prune()?;
+        Ok(self.entries.last().unwrap())
+    }
+
+    /// 固定或取消固定
+    pub fn toggle_pin(&mut self, id: u64) -> std::io::Result<()> {
+        let Some(index) = self.entries.iter().position(|entry| entry.id == id) else {
+            return Ok(());
+        };
+        self.entries[index].pinned = !self.entries[index].pinned;
+        self.save(&self.entries[index])?;
+        self.prune()
+    }
+
+    pub fn delete(&mut self, id: u64) -> std::io::Result<()> {
+        self.entries.retain(|entry| entry.id != id);
+        self.remove_file(id)
+    }
+
+    /// 删除所有未固定的记录
+    pub fn clear_unpinned(&mut self) -> std::io::Result<()> {
+        let removed: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|entry| !entry.pinned)
+            .map(|entry| entry.id)
+            .collect();
+        self.entries.retain(|entry| entry.pinned);
+        removed.into_iter().try_for_each(|id| self.remove_file(id))
+    }
+
+    /// 未固定的记录超过`MAX_UNPINNED`条时删除最早的记录
+    fn prune(&mut self) -> std::io::Result<()> {
+        let unpinned = self.entries.iter().filter(|entry| !entry.pinned).count();
+        let removed: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|entry| !entry.pinned)
+            .take(unpinned.saturating_sub(MAX_UNPINNED))
+            .map(|entry| entry.id)
+            .collect();
+        self.entries.retain(|entry| !removed.contains(&entry.id));
+        removed.into_iter().try_for_each(|id| self.remove_file(id))
+    }
+
+    fn save(&self, entry: &HistoryEntry) -> std::io::Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        let writer = BufWriter::new(File::create(dir.join(format!("{}.json", entry.id)))?);
+        serde_json::to_writer(writer, entry).map_err(Error::other)
+    }
+
+    fn remove_file(&self, id: u64) -> std::io::Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        match std::fs::remove_file(dir.join(format!("{id}.json"))) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parameters::{CalcInputParameters, CalcResultParamters};
+
+    fn run(eta: &[f64]) -> CalcRun {
+        let mut results = CalcResultParamters::default();
+        for &eta_enpp in eta {
+            results.result1.push(CalcResult1 {
+                eta_enpp,
+                g_cd: 1000.0,
+                ..CalcResult1::default()
+            });
+        }
+        CalcRun {
+            name: String::new(),
+            params: CalcInputParameters::from_default(),
+            results,
+        }
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = History::default();
+        let entry = history.push(run(&[0.3, 0.32, 0.3205])).unwrap();
+        assert_eq!(entry.id, 1);
+        assert_eq!(entry.stats.iterations, 3);
+        assert!((entry.stats.eta_residual - 0.0005).abs() < 1e-12);
+        assert_eq!(entry.stats.g_cd_residual, 0.0);
+
+        history.toggle_pin(1).unwrap();
+        for _ in 0..MAX_UNPINNED + 1 {
+            history.push(run(&[0.3])).unwrap();
+        }
+        // 固定的记录保留，最早的未固定记录被删除
+        assert_eq!(history.entries().len(), MAX_UNPINNED + 1);
+        assert!(history.get(1).unwrap().pinned);
+        assert!(history.get(2).is_none());
+
+        history.clear_unpinned().unwrap();
+        assert_eq!(history.entries().len(), 1);
+        assert_eq!(history.next_id(), MAX_UNPINNED as u64 + 3);
+
+        let entry = HistoryEntry {
+            timestamp: 1_700_000_000,
+            ..history.entries()[0].clone()
+        };
+        assert_eq!(entry.time(), "2023-11-14 22:13:20");
+    }
+
+    #[test]
+    fn test_history_dir() {
+        let dir = std::env::temp_dir().join(format!("npp-thc-history-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // 启用目录前的记录同步写入目录
+        let mut history = History::default();
+        history.push(run(&[0.3])).unwrap();
+        history.set_dir(Some(dir.clone())).unwrap();
+        history.push(run(&[0.31])).unwrap();
+        history.toggle_pin(2).unwrap();
+
+        let mut history = History::open(&dir).unwrap();
+        assert_eq!(history.entries().len(), 2);
+        assert!(history.get(2).unwrap().pinned);
+        assert_eq!(history.next_id(), 3);
+
+        history.delete(1).unwrap();
+        assert_eq!(History::open(&dir).unwrap().entries().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
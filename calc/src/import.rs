@@ -0,0 +1,243 @@
+//! 输入参数导入
+//!
+//! 支持JSON、TOML、YAML以及两列CSV(字段,数值)格式，CSV中可选参数的数值留空表示未给定。
+//! 文件中缺失的字段取默认参数，并记录未知字段与缺失字段，便于检查手工编辑的参数文件。
+use std::fmt::Display;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::fields::{field_map, input_field};
+use crate::parameters::CalcInputParameters;
+
+/// 参数文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamsFormat {
+    Json,
+    Toml,
+    Yaml,
+    Csv,
+}
+
+impl ParamsFormat {
+    /// 支持的文件扩展名
+    pub const EXTENSIONS: [&'static str; 6] = ["json", "toml", "yaml", "yml", "csv", "tsv"];
+
+    /// 根据文件扩展名判断格式
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "csv" | "tsv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// 参数导入错误
+#[derive(Debug, Clone)]
+pub enum ImportError {
+    /// 无法识别的文件格式
+    UnknownFormat,
+    /// 文件内容解析失败
+    Parse(String),
+    /// 字段取值无效
+    InvalidValue(String),
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownFormat => write!(f, "无法识别的文件格式"),
+            Self::Parse(error) => write!(f, "解析失败: {error}"),
+            Self::InvalidValue(error) => write!(f, "字段取值无效: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// 导入结果
+#[derive(Debug, Clone)]
+pub struct ImportedParams {
+    /// 输入参数，缺失字段取默认值
+    pub params: CalcInputParameters,
+    /// 文件中无法识别的字段
+    pub unknown_fields: Vec<String>,
+    /// 文件中缺失、已使用默认值的字段
+    pub missing_fields: Vec<String>,
+}
+
+impl ImportedParams {
+    /// 导入情况说明，未知与缺失字段均为空时返回`None`
+    pub fn summary(&self) -> Option<String> {
+        let mut notes = Vec::new();
+        if !self.unknown_fields.is_empty() {
+            notes.push(format!("未知字段: {}", self.unknown_fields.join(", ")));
+        }
+        if !self.missing_fields.is_empty() {
+            notes.push(format!(
+                "缺失字段(已使用默认值): {}",
+                self.missing_fields.join(", ")
+            ));
+        }
+        (!notes.is_empty()).then(|| notes.join("；"))
+    }
+}
+
+/// 解析两列CSV/TSV(字段,数值)，跳过空行、`#`注释行和表头
+fn parse_csv(contents: &str) -> Result<Map<String, Value>, ImportError> {
+    let mut map = Map::new();
+    let mut first_line = true;
+    let contents = contents.trim_start_matches('\u{feff}');
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let is_header = std::mem::take(&mut first_line);
+        let mut cells = line
+            .split([',', '\t'])
+            .map(|cell| cell.trim().trim_matches('"').trim());
+        let key = cells.next().unwrap_or_default();
+        let value = cells.next().unwrap_or_default();
+        match value.parse::<f64>() {
+            Ok(value) => {
+                map.insert(key.to_string(), Value::from(value));
+            }
+            // 可选参数留空表示未给定
+            Err(_)
+                if value.is_empty()
+                    && input_field(key).is_some_and(|field| field.is_optional()) =>
+            {
+                map.insert(key.to_string(), Value::Null);
+            }
+            // 第一个非空行为表头
+            Err(_) if is_header => {}
+            Err(_) => {
+                return Err(ImportError::Parse(format!(
+                    "第{}行数值无效: {}",
+                    i + 1,
+                    line
+                )));
+            }
+        }
+    }
+    Ok(map)
+}
+
+/// 将文件内容解析为字段表
+fn parse_map(contents: &str, format: ParamsFormat) -> Result<Map<String, Value>, ImportError> {
+    let parse_error = |error: &dyn Display| ImportError::Parse(error.to_string());
+    let value: Value = match format {
+        ParamsFormat::Json => serde_json::from_str(contents).map_err(|e| parse_error(&e))?,
+        ParamsFormat::Toml => toml::from_str(contents).map_err(|e| parse_error(&e))?,
+        ParamsFormat::Yaml => serde_yaml::from_str(contents).map_err(|e| parse_error(&e))?,
+        ParamsFormat::Csv => return parse_csv(contents),
+    };
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => Err(ImportError::Parse(String::from("顶层必须为字段表"))),
+    }
+}
+
+/// 从文件内容导入输入参数
+///
+/// # Arguments
+///
+/// * `contents` - 文件内容
+///
+/// * `format` - 文件格式
+pub fn import_params(contents: &str, format: ParamsFormat) -> Result<ImportedParams, ImportError> {
+    let map = parse_map(contents, format)?;
+    let known = field_map(&CalcInputParameters::from_default());
+    let unknown_fields = map
+        .keys()
+        .filter(|key| !known.contains_key(*key))
+        .cloned()
+        .collect();
+    let missing_fields = known
+        .keys()
+        .filter(|key| !map.contains_key(*key))
+        .cloned()
+        .collect();
+    let params = serde_json::from_value(Value::Object(map))
+        .map_err(|error| ImportError::InvalidValue(error.to_string()))?;
+    Ok(ImportedParams {
+        params,
+        unknown_fields,
+        missing_fields,
+    })
+}
+
+/// 从文件导入输入参数，按扩展名判断格式
+pub fn import_params_from_file(path: impl AsRef<Path>) -> Result<ImportedParams, ImportError> {
+    let format = ParamsFormat::from_path(&path).ok_or(ImportError::UnknownFormat)?;
+    let contents =
+        std::fs::read_to_string(path).map_err(|error| ImportError::Parse(error.to_string()))?;
+    import_params(&contents, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_partial() {
+        let default = CalcInputParameters::from_default();
+        let sources = [
+            (r#"{"ne": 1200.0, "foo": 1}"#, ParamsFormat::Json),
+            ("ne = 1200.0\nfoo = 1\n", ParamsFormat::Toml),
+            ("ne: 1200\nfoo: 1\n", ParamsFormat::Yaml),
+            ("字段,数值\nne,1200\nfoo,1\n", ParamsFormat::Csv),
+        ];
+        for (contents, format) in sources {
+            let imported = import_params(contents, format).unwrap();
+            assert_eq!(imported.params.ne, 1200.0);
+            assert_eq!(imported.params.p_c, default.p_c);
+            assert_eq!(imported.unknown_fields, vec!["foo"]);
+            assert!(imported.missing_fields.contains(&String::from("p_c")));
+            assert!(!imported.missing_fields.contains(&String::from("ne")));
+        }
+    }
+
+    #[test]
+    fn test_import_csv_header() {
+        let sources = [
+            "\u{feff}字段,数值\nne,1200\n",
+            "\n\n# 导出的输入参数\n\n字段\t数值\r\nne\t1200\r\n",
+            "\u{feff}\n\"field\",\"value\"\nne,1200\n",
+        ];
+        for contents in sources {
+            let imported = import_params(contents, ParamsFormat::Csv).unwrap();
+            assert_eq!(imported.params.ne, 1200.0);
+            assert!(imported.unknown_fields.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_import_csv_optional() {
+        let contents = "field,value\ntheta_u1,\ntheta_u2,4.5\ntheta_dc1,\"\"\n";
+        let imported = import_params(contents, ParamsFormat::Csv).unwrap();
+        assert_eq!(imported.params.theta_u1, None);
+        assert_eq!(imported.params.theta_u2, Some(4.5));
+        assert_eq!(imported.params.theta_dc1, None);
+        assert!(!imported.missing_fields.contains(&String::from("theta_u1")));
+    }
+
+    #[test]
+    fn test_import_invalid() {
+        assert!(import_params(r#"{"ne": "abc"}"#, ParamsFormat::Json).is_err());
+        assert!(import_params("field,value\np_c,15\n", ParamsFormat::Csv).is_ok());
+        assert!(import_params("ne,1000\np_c,abc\n", ParamsFormat::Csv).is_err());
+        assert!(import_params("field,value\nne,1000\nname,value\n", ParamsFormat::Csv).is_err());
+        // 只有可选参数可以留空
+        assert!(import_params("field,value\np_c,\n", ParamsFormat::Csv).is_err());
+        assert_eq!(
+            ParamsFormat::from_path("params.YML"),
+            Some(ParamsFormat::Yaml)
+        );
+    }
+}
@@ -1,4 +1,22 @@
+pub mod allocation;
+pub mod compare;
+pub mod condenser;
+pub mod drains;
+pub mod export;
+pub mod feed_pump;
+pub mod fields;
+pub mod history;
+pub mod import;
+pub mod offdesign;
+pub mod optimize;
 pub mod parameters;
+pub mod presets;
+pub mod primary_loop;
+pub mod seasonal;
+pub mod sensitivity;
+pub mod steam_generator;
+pub mod uncertainty;
+pub mod units;
 
 use std::fs::File;
 use std::io::BufWriter;
@@ -9,6 +27,21 @@ use parameters::{
     CalcResult2, CalcResultParamters,
 };
 use seuif97::*;
+use units::UnitSystem;
+
+/// 热平衡计算的最大迭代次数，超过时视为不收敛
+pub const MAX_ITERATIONS: usize = 100;
+
+/// 一次计算的主要输出
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Outputs {
+    pub eta_enpp: f64,
+    /// 反应堆热功率(GW)
+    pub q_r: f64,
+    /// 蒸汽发生器总蒸汽产量(kg/s)
+    pub d_s: f64,
+    pub iterations: usize,
+}
 
 #[derive(Default)]
 pub struct Calculator {
@@ -32,8 +65,38 @@ impl Calculator {
         self.params = params;
     }
 
+    /// 按给定参数计算，返回完成计算的计算器
+    pub(crate) fn solve(params: CalcInputParameters) -> Result<Self, String> {
+        let mut calculator = Self::new(params);
+        calculator.calculate().map_err(|error| error.to_string())?;
+        Ok(calculator)
+    }
+
+    /// 按给定参数计算，结果非有限值时视为失败
+    pub(crate) fn evaluate(params: CalcInputParameters) -> Result<Outputs, String> {
+        let calculator = Self::solve(params)?;
+        let iterations = calculator.results.result1.len();
+        let last = calculator
+            .results
+            .result1
+            .last()
+            .ok_or_else(|| String::from("计算结果为空"))?;
+        if !(last.eta_enpp.is_finite() && last.q_r.is_finite() && last.d_s.is_finite()) {
+            return Err(String::from("计算结果不是有限值"));
+        }
+        Ok(Outputs {
+            eta_enpp: last.eta_enpp,
+            q_r: last.q_r,
+            d_s: last.d_s,
+            iterations,
+        })
+    }
+
     /// 计算核电厂的热力学参数
     pub fn calculate(&mut self) -> Result<(), Box<dyn Error>> {
+        // 离散选项的取值须为有效的数值编码
+        fields::check_input_choices(&self.params)?;
+
         // 一回路冷却剂参数
         let t_cs = px(self.params.p_c, 0.0, OT); // 工作压力对应饱和温度（冷却剂压力对应饱和温度）
         let t_co = t_cs - self.params.dt_sub; // 反应堆出口冷却剂温度
@@ -116,8 +179,10 @@ impl Calculator {
         let p_dea = 0.99 * p_hz; // 除氧器运行压力，略低于高压缸排汽压力
         let t_deao = px(p_dea, 0.0, OT); // 除氧器出口温度
         let h_deao = tx(t_deao, 0.0, OH); // 除氧器出口对应饱和水比焓
-        let dh_fwh = (h_fw - h_deao) / self.params.z_h; // 高压给水加热器每一级给水焓升
-        let dh_fwl = (h_deao - h_cd) / (self.params.z_l + 1.0); // 除氧器及低压加热器每一级给水焓升
+        let dh_fwh = (h_fw - h_deao) / self.params.z_h; // 高压给水加热器平均每一级给水焓升
+        let dh_fwl = (h_deao - h_cd) / (self.params.z_l + 1.0); // 除氧器及低压加热器平均每一级给水焓升
+        // 按分配方式确定的各级给水焓升
+        let (dh_fwhx, dh_fwlx) = allocation::enthalpy_rises(&self.params, h_cd, h_deao, h_fw)?;
 
         // 给水回路系统中的压力选择
         let p_cwp = self.params.dp_cwp * p_dea; // 取凝水泵出口压力为除氧器运行压力的dp_cwp倍
@@ -128,16 +193,16 @@ impl Calculator {
 
         // 一级低压给水加热器
         let (p_fw1i, h_fw1i, t_fw1i, p_fw1o, h_fw1o, t_fw1o, t_ro1k, h_ro1k, p_ro1k) =
-            self.calc_fwxl(p_cwp, h_cwp, t_cwp, dp_fi, dh_fwl);
+            self.calc_fwxl(p_cwp, h_cwp, t_cwp, dp_fi, dh_fwlx[0], 1);
         // 二级低压给水加热器
         let (p_fw2i, h_fw2i, t_fw2i, p_fw2o, h_fw2o, t_fw2o, t_ro2k, h_ro2k, p_ro2k) =
-            self.calc_fwxl(p_fw1o, h_fw1o, t_fw1o, dp_fi, dh_fwl);
+            self.calc_fwxl(p_fw1o, h_fw1o, t_fw1o, dp_fi, dh_fwlx[1], 2);
         // 三级低压给水加热器
         let (p_fw3i, h_fw3i, t_fw3i, p_fw3o, h_fw3o, t_fw3o, t_ro3k, h_ro3k, p_ro3k) =
-            self.calc_fwxl(p_fw2o, h_fw2o, t_fw2o, dp_fi, dh_fwl);
+            self.calc_fwxl(p_fw2o, h_fw2o, t_fw2o, dp_fi, dh_fwlx[2], 3);
         // 四级低压给水加热器
         let (p_fw4i, h_fw4i, t_fw4i, p_fw4o, h_fw4o, t_fw4o, t_ro4k, h_ro4k, p_ro4k) =
-            self.calc_fwxl(p_fw3o, h_fw3o, t_fw3o, dp_fi, dh_fwl);
+            self.calc_fwxl(p_fw3o, h_fw3o, t_fw3o, dp_fi, dh_fwlx[3], 4);
 
         // 除氧器
         let h_deai = h_fw4o; // 进口给水比焓
@@ -155,33 +220,34 @@ impl Calculator {
                 h_fwpo,
                 t_fwpo,
                 p_fwpo - (p_fwpo - p_fwi) / 2.0,
-                dh_fwh,
+                dh_fwhx[0],
+                6,
             );
         // 七级高压给水加热器
         let (p_fw7i, h_fw7i, t_fw7i, p_fw7o, h_fw7o, t_fw7o, t_ro7k, h_ro7k, p_ro7k) =
-            self.calc_fwxh(p_fw6o, h_fw6o, t_fw6o, p_fwi, dh_fwh);
+            self.calc_fwxh(p_fw6o, h_fw6o, t_fw6o, p_fwi, dh_fwhx[1], 7);
 
         // 高压缸抽汽
         // 六级给水加热器抽气参数
         let (p_hes6, h_hes6s, h_hes6, x_hes6, t_hes6) =
-            self.calc_esx(t_fw6o, p_ro6k, s_hi, h_hi, true);
+            self.calc_esx(t_fw6o, p_ro6k, s_hi, h_hi, 6);
         // 七级给水加热器抽气参数
         let (p_hes7, h_hes7s, h_hes7, x_hes7, t_hes7) =
-            self.calc_esx(t_fw7o, p_ro7k, s_hi, h_hi, true);
+            self.calc_esx(t_fw7o, p_ro7k, s_hi, h_hi, 7);
 
         // 低压缸抽汽
         // 一级给水加热器抽汽参数
         let (p_les1, h_les1s, h_les1, x_les1, t_les1) =
-            self.calc_esx(t_fw1o, p_ro1k, s_li, h_li, false);
+            self.calc_esx(t_fw1o, p_ro1k, s_li, h_li, 1);
         // 二级给水加热器抽汽参数
         let (p_les2, h_les2s, h_les2, x_les2, t_les2) =
-            self.calc_esx(t_fw2o, p_ro2k, s_li, h_li, false);
+            self.calc_esx(t_fw2o, p_ro2k, s_li, h_li, 2);
         // 三级给水加热器抽汽参数
         let (p_les3, h_les3s, h_les3, x_les3, t_les3) =
-            self.calc_esx(t_fw3o, p_ro3k, s_li, h_li, false);
+            self.calc_esx(t_fw3o, p_ro3k, s_li, h_li, 3);
         // 四级给水加热器抽汽参数
         let (p_les4, h_les4s, h_les4, x_les4, t_les4) =
-            self.calc_esx(t_fw4o, p_ro4k, s_li, h_li, false);
+            self.calc_esx(t_fw4o, p_ro4k, s_li, h_li, 4);
 
         // 再热器抽汽
         // 一级再热器抽汽参数
@@ -189,10 +255,114 @@ impl Calculator {
         // 二级再热器抽汽参数
         let (p_rh2, x_rh2, t_rh2, h_rh2, h_zs2) = self.calc_rhx(p_hi, x_hi);
         // 蒸汽发生器总蒸汽产量的计算
-        let h_a = h_hi - h_hz; // 给水泵汽轮机中蒸汽的绝热焓降
-        loop {
+        // 给水泵汽轮机进出口参数，电动给水泵时为None
+        let turbine = match feed_pump::Drive::from_params(&self.params) {
+            feed_pump::Drive::Turbine => Some(feed_pump::FeedPumpTurbine::new(
+                &self.params,
+                (p_hi, h_hi),
+                (p_li, h_li),
+                p_lz,
+                (p_hz, h_hz),
+            )?),
+            feed_pump::Drive::Motor => None,
+        };
+        // 给水泵汽轮机耗汽取自新蒸汽、排入冷凝器及除氧器的份额
+        let (from_fh, to_cd, to_dea) = feed_pump::shares(&self.params);
+        let h_fwptz = turbine.map_or(0.0, |turbine| turbine.h_out); // 给水泵汽轮机排汽比焓
+        let mut n_fwpd = 0.0; // 给水泵驱动功率(kW)
+        // 各级表面式给水加热器，由低到高
+        let stage = |n, h_in, t_in, h_out, p_out, h_es, p_shell, h_sat, mode| drains::Stage {
+            h_in,
+            t_in,
+            h_out,
+            p_out,
+            h_es,
+            p_shell,
+            h_sat,
+            mode: drains::DrainMode::from_code(mode),
+            theta_dc: self.params.heater_theta_dc(n),
+            external: (0.0, 0.0),
+        };
+        let lp_stages = [
+            stage(
+                1,
+                h_fw1i,
+                t_fw1i,
+                h_fw1o,
+                p_fw1o,
+                h_les1,
+                p_ro1k,
+                h_ro1k,
+                self.params.drain_1,
+            ),
+            stage(
+                2,
+                h_fw2i,
+                t_fw2i,
+                h_fw2o,
+                p_fw2o,
+                h_les2,
+                p_ro2k,
+                h_ro2k,
+                self.params.drain_2,
+            ),
+            stage(
+                3,
+                h_fw3i,
+                t_fw3i,
+                h_fw3o,
+                p_fw3o,
+                h_les3,
+                p_ro3k,
+                h_ro3k,
+                self.params.drain_3,
+            ),
+            stage(
+                4,
+                h_fw4i,
+                t_fw4i,
+                h_fw4o,
+                p_fw4o,
+                h_les4,
+                p_ro4k,
+                h_ro4k,
+                self.params.drain_4,
+            ),
+        ];
+        let mut hp_stages = [
+            stage(
+                6,
+                h_fw6i,
+                t_fw6i,
+                h_fw6o,
+                p_fw6o,
+                h_hes6,
+                p_ro6k,
+                h_ro6k,
+                self.params.drain_6,
+            ),
+            stage(
+                7,
+                h_fw7i,
+                t_fw7i,
+                h_fw7o,
+                p_fw7o,
+                h_hes7,
+                p_ro7k,
+                h_ro7k,
+                self.params.drain_7,
+            ),
+        ];
+        let mut lp = drains::TrainResult::default();
+        let mut hp = drains::TrainResult::default();
+        // 计入热平衡的主泵热量与反应堆热功率之比，传给二次侧的热量为η_1·Q_R·(1 + k)
+        let k_pump = 1.0 + primary_loop::pump_heat_ratio(&self.params, t_co, t_ci);
+        for iteration in 1.. {
+            if iteration > MAX_ITERATIONS {
+                return Err(format!("热平衡迭代{MAX_ITERATIONS}次仍未收敛").into());
+            }
             let mut q_r = self.params.ne / self.params.ne_npp; // 反应堆热功率(MW)
-            let mut d_s = (1000.0 * q_r * self.params.n_1)
+            let mut d_s = (1000.0 * q_r * self.params.n_1 * k_pump)
                 / ((h_fh - h_s) + (1.0 + self.params.zeta_d) * (h_s - h_fw)); // GS蒸汽产量(kg/s)
             let mut g_fw = (1.0 + self.params.zeta_d) * d_s; // GS给水流量(kg/s)
             let h_fwp = p_fwpo - p_dea; // 给水泵扬程(MPa)
@@ -214,25 +384,32 @@ impl Calculator {
                 mut g_sdea,
             );
             // loop {
-            let n_fwpp = 1000.0 * g_fw * h_fwp / rho_fwp; // 给水泵有效输出功率(kW)
-            let n_fwpt = n_fwpp
-                / (self.params.n_fwpp
-                    * self.params.n_fwpti
-                    * self.params.n_fwptm
-                    * self.params.n_fwptg); // 给水泵理论功率(kW)
-            g_fwps = n_fwpt / h_a; // 给水泵汽轮机耗汽量(kg/s)
+            // 给水泵有效输出功率(kW)，高压加热器疏水打入下游的部分不经过给水泵，取上一次迭代的值
+            let n_fwpp = 1000.0 * (g_fw - hp.pumped) * h_fwp / rho_fwp;
+            let n_fwp = n_fwpp / self.params.n_fwpp; // 给水泵轴功率(kW)
+            // 电动给水泵的耗电功率(kW)，由发电机供给
+            let n_fwpm = match turbine {
+                Some(_) => 0.0,
+                None => n_fwp / self.params.n_fwpm,
+            };
+            let n_fwpt = n_fwp / (self.params.n_fwptm * self.params.n_fwptg); // 给水泵汽轮机功率(kW)
+            g_fwps = turbine.map_or(0.0, |turbine| n_fwpt / turbine.h_a()); // 给水泵汽轮机耗汽量(kg/s)
+            n_fwpd = if turbine.is_some() { n_fwpt } else { n_fwpm };
             // 低压给水加热器抽汽量
-            g_les4 = self.params.g_cd * (h_fw4o - h_fw4i) / (self.params.n_h * (h_les4 - h_ro4k)); // 第四级抽汽量
-            g_les3 = (self.params.g_cd * (h_fw3o - h_fw3i)
-                - self.params.n_h * g_les4 * (h_ro4k - h_ro3k))
-                / (self.params.n_h * (h_les3 - h_ro3k)); // 第三级抽汽量
-            g_les2 = (self.params.g_cd * (h_fw2o - h_fw2i)
-                - self.params.n_h * (g_les3 + g_les4) * (h_ro3k - h_ro2k))
-                / (self.params.n_h * (h_les2 - h_ro2k)); // 第二级抽汽量
-            g_les1 = (self.params.g_cd * (h_fw1o - h_fw1i)
-                - self.params.n_h * (g_les2 + g_les3 + g_les4) * (h_ro2k - h_ro1k))
-                / (self.params.n_h * (h_les1 - h_ro1k)); // 第一级抽汽量
-            g_sl = self.params.g_cd - self.params.zeta_d * d_s - g_fwps; // 低压缸耗气量
+            lp = drains::solve(
+                &lp_stages,
+                drains::Flow::Inlet(self.params.g_cd),
+                self.params.n_h,
+                self.params.n_fwpp,
+                self.params.n_fwpm,
+            )?;
+            g_les1 = lp.stages[0].g_es; // 第一级抽汽量
+            g_les2 = lp.stages[1].g_es; // 第二级抽汽量
+            g_les3 = lp.stages[2].g_es; // 第三级抽汽量
+            g_les4 = lp.stages[3].g_es; // 第四级抽汽量
+            // 低压缸耗气量，打入下游的低压加热器疏水不返回冷凝器
+            g_sl = self.params.g_cd - self.params.zeta_d * d_s - to_cd * g_fwps + lp.pumped;
+            let g_rh = g_sl + (1.0 - from_fh) * g_fwps; // 汽水分离再热器出口蒸汽流量
             // g_sl = (0.6 * 1000.0 * self.params.ne / (self.params.n_m * self.params.n_ge)
             //     + g_les4 * (h_les4 - h_lz)
             //     + g_les3 * (h_les3 - h_lz)
@@ -240,26 +417,33 @@ impl Calculator {
             //     + g_les1 * (h_les1 - h_lz))
             //     / (h_li - h_lz); // 低压缸耗气量(kg/s)
             // 再热器加热蒸汽量
-            g_zc1 = g_sl * dh_rh / (self.params.n_h * (h_rh1 - h_zs1));
-            g_zc2 = g_sl * dh_rh / (self.params.n_h * (h_rh2 - h_zs2));
-            // 高压给水加热器抽汽量
-            g_hes7 = (g_fw * (h_fw7o - h_fw7i) - self.params.n_h * g_zc2 * (h_zs2 - h_ro7k))
-                / (self.params.n_h * (h_hes7 - h_ro7k));
-            g_hes6 = (g_fw * (h_fw6o - h_fw6i)
-                - self.params.n_h * g_zc1 * (h_zs1 - h_ro6k)
-                - self.params.n_h * (g_zc2 + g_hes7) * (h_ro7k - h_ro6k))
-                / (self.params.n_h * (h_hes6 - h_ro6k));
-            g_uw = g_sl * (x_rh1i - x_spi) / x_spi; // 汽水分离器疏水流量(kg/s)
+            g_zc1 = g_rh * dh_rh / (self.params.n_h * (h_rh1 - h_zs1));
+            g_zc2 = g_rh * dh_rh / (self.params.n_h * (h_rh2 - h_zs2));
+            // 高压给水加热器抽汽量，再热器疏水分别流入第六、七级加热器
+            hp_stages[0].external = (g_zc1, h_zs1);
+            hp_stages[1].external = (g_zc2, h_zs2);
+            hp = drains::solve(
+                &hp_stages,
+                drains::Flow::Outlet(g_fw),
+                self.params.n_h,
+                self.params.n_fwpp,
+                self.params.n_fwpm,
+            )?;
+            g_hes6 = hp.stages[0].g_es;
+            g_hes7 = hp.stages[1].g_es;
+            g_uw = g_rh * (x_rh1i - x_spi) / x_spi; // 汽水分离器疏水流量(kg/s)
             // let g_h1 = g_sl + g_uw;
             // 除氧器耗汽量
-            g_sdea = (g_fw * h_deao
+            g_sdea = ((g_fw - hp.pumped) * h_deao
                     - g_uw * h_uw // h_psi???
-                    - self.params.g_cd * h_fw4o
-                    - (g_zc1 + g_zc2 + g_hes6 + g_hes7) * h_ro6k)
+                    - (self.params.g_cd + lp.pumped) * h_fw4o
+                    - hp.sink.0 * hp.sink.1
+                    - to_dea * g_fwps * h_fwptz)
                 / h_hz;
             // let g_t = g_sdea + g_sl * x_rh1i / x_hz; // 高压缸出口排气总流量
             // 高压缸耗汽量
-            g_sh = (0.4 * 1000.0 * self.params.ne / (self.params.n_m * self.params.n_ge)
+            g_sh = (0.4 * (1000.0 * self.params.ne + n_fwpm + lp.n_dp + hp.n_dp)
+                / (self.params.n_m * self.params.n_ge)
                 + g_hes7 * (h_hes7 - h_hz)
                 + g_hes6 * (h_hes6 - h_hz)
                 + g_zc1 * (h_rh1 - h_hz))
@@ -273,9 +457,9 @@ impl Calculator {
             //     + g_hes7
             //     + g_zc1;
             // 对假设冷凝水流量验证
-            d_s = g_fwps + g_zc2 + g_sh; // 新蒸汽耗量
+            d_s = from_fh * g_fwps + g_zc2 + g_sh; // 新蒸汽耗量
             let g_fw1 = (1.0 + self.params.zeta_d) * d_s; // 给水流量
-            let g_cd1 = g_fw1 - g_sdea - g_uw - (g_hes6 + g_hes7 + g_zc1 + g_zc2);
+            let g_cd1 = g_fw1 - hp.pumped - g_sdea - g_uw - hp.sink.0 - to_dea * g_fwps - lp.pumped;
             // if (g_cd1 - self.params.g_cd).abs() / self.params.g_cd < 1e-2 {
             //     break;
             // } else {
@@ -284,7 +468,7 @@ impl Calculator {
             // }
             // }
             q_r = (d_s * (h_fh - h_fw) + self.params.zeta_d * d_s * (h_s - h_fw))
-                / (1000.0 * self.params.n_1); // 新反应堆热功率(MW)
+                / (1000.0 * self.params.n_1 * k_pump); // 新反应堆热功率(MW)
             let n_ennp1 = self.params.ne / q_r;
             self.results.result1.push(CalcResult1 {
                 eta_enpp: self.params.ne_npp,
@@ -295,7 +479,7 @@ impl Calculator {
                 g_sfwp: g_fwps,
                 g_cd: self.params.g_cd,
                 g_sdea,
-                q_r: q_r / 1000.0, // kW -> MW
+                q_r: q_r / 1000.0, // MW -> GW
                 d_s,
                 g_fw,
                 h_fwp,
@@ -319,6 +503,34 @@ impl Calculator {
             }
         }
 
+        // 抽汽量为负说明给定参数下的热平衡在物理上不成立
+        if let Some(r1) = self.results.result1.last() {
+            let flows = [
+                ("除氧器耗气量G_sdea", r1.g_sdea),
+                ("给水泵汽轮机耗气量G_sfwp", r1.g_sfwp),
+                ("第七级抽汽量G_hes7", r1.g_hes7),
+                ("第六级抽汽量G_hes6", r1.g_hes6),
+                ("第四级抽汽量G_les4", r1.g_les4),
+                ("第三级抽汽量G_les3", r1.g_les3),
+                ("第二级抽汽量G_les2", r1.g_les2),
+                ("第一级抽汽量G_les1", r1.g_les1),
+                ("一级再热器加热蒸汽量G_zc1", r1.g_zc1),
+                ("二级再热器加热蒸汽量G_zc2", r1.g_zc2),
+            ];
+            if let Some((name, flow)) = flows.iter().find(|(_, flow)| *flow < 0.0) {
+                return Err(format!("{name}为负值({flow:.2} kg/s)，请检查输入参数").into());
+            }
+        }
+
+        // 一回路冷却剂流量、主泵功率与蒸汽发生器热力设计
+        let q_r = self
+            .results
+            .result1
+            .last()
+            .map_or(f64::NAN, |r1| r1.q_r * 1000.0);
+        let primary = primary_loop::primary_loop(&self.params, q_r, t_co, t_ci);
+        let sg = steam_generator::size(&self.params, q_r, primary.g_c, t_co, t_ci, dt_m);
+
         // 存储附表结果
         self.results.result2 = CalcResult2 {
             ne: self.params.ne,
@@ -420,6 +632,10 @@ impl Calculator {
                     t_fwxo: t_fw1o,
                     t_roxk: t_ro1k,
                     h_roxk: h_ro1k,
+                    g_fwx: lp.stages[0].g_fw,
+                    g_dx: lp.stages[0].g_d,
+                    h_dx: lp.stages[0].h_d,
+                    dh_fwx: dh_fwlx[0],
                 },
                 CalcFWParameters {
                     p_fwxi: p_fw2i,
@@ -430,6 +646,10 @@ impl Calculator {
                     t_fwxo: t_fw2o,
                     t_roxk: t_ro2k,
                     h_roxk: h_ro2k,
+                    g_fwx: lp.stages[1].g_fw,
+                    g_dx: lp.stages[1].g_d,
+                    h_dx: lp.stages[1].h_d,
+                    dh_fwx: dh_fwlx[1],
                 },
                 CalcFWParameters {
                     p_fwxi: p_fw3i,
@@ -440,6 +660,10 @@ impl Calculator {
                     t_fwxo: t_fw3o,
                     t_roxk: t_ro3k,
                     h_roxk: h_ro3k,
+                    g_fwx: lp.stages[2].g_fw,
+                    g_dx: lp.stages[2].g_d,
+                    h_dx: lp.stages[2].h_d,
+                    dh_fwx: dh_fwlx[2],
                 },
                 CalcFWParameters {
                     p_fwxi: p_fw4i,
@@ -450,6 +674,10 @@ impl Calculator {
                     t_fwxo: t_fw4o,
                     t_roxk: t_ro4k,
                     h_roxk: h_ro4k,
+                    g_fwx: lp.stages[3].g_fw,
+                    g_dx: lp.stages[3].g_d,
+                    h_dx: lp.stages[3].h_d,
+                    dh_fwx: dh_fwlx[3],
                 },
             ],
             h_deai,
@@ -459,6 +687,12 @@ impl Calculator {
             p_fwpo,
             h_fwpo,
             p_fwi,
+            n_fwpd: n_fwpd / 1000.0, // kW -> MW
+            h_fwpti: turbine.map_or(0.0, |turbine| turbine.h_in),
+            h_fwptz,
+            n_dp: (lp.n_dp + hp.n_dp) / 1000.0, // kW -> MW
+            g_dcd: lp.sink.0,
+            h_dcd: lp.sink.1,
             hfwx: vec![
                 CalcFWParameters {
                     p_fwxi: p_fw6i,
@@ -469,6 +703,10 @@ impl Calculator {
                     t_fwxo: t_fw6o,
                     t_roxk: t_ro6k,
                     h_roxk: h_ro6k,
+                    g_fwx: hp.stages[0].g_fw,
+                    g_dx: hp.stages[0].g_d,
+                    h_dx: hp.stages[0].h_d,
+                    dh_fwx: dh_fwhx[0],
                 },
                 CalcFWParameters {
                     p_fwxi: p_fw7i,
@@ -479,6 +717,10 @@ impl Calculator {
                     t_fwxo: t_fw7o,
                     t_roxk: t_ro7k,
                     h_roxk: h_ro7k,
+                    g_fwx: hp.stages[1].g_fw,
+                    g_dx: hp.stages[1].g_d,
+                    h_dx: hp.stages[1].h_d,
+                    dh_fwx: dh_fwhx[1],
                 },
             ],
             s_hi1: s_hi,
@@ -547,10 +789,26 @@ impl Calculator {
                     h_zsx: h_zs2,
                 },
             ],
+            g_c: primary.g_c,
+            g_cloop: primary.g_cloop,
+            v_cloop: primary.v_cloop,
+            n_rcp1: primary.n_rcp1,
+            n_rcp: primary.n_rcp,
+            k_sg: sg.k_sg,
+            alpha_1: sg.alpha_1,
+            w_c: sg.w_c,
+            f_sg: sg.f_sg,
+            f_sg1: sg.f_sg1,
+            n_tsg: sg.n_tsg,
         };
         // 生成Python和Rust代码
-        self.calc_code_py = self.generate_calc_code_py();
-        self.calc_code_rs = self.generate_calc_code_rs();
+        if let Some(reason) = self.calc_code_unsupported() {
+            self.calc_code_py = format!("# {reason}\n");
+            self.calc_code_rs = format!("// {reason}\n");
+        } else {
+            self.calc_code_py = self.generate_calc_code_py();
+            self.calc_code_rs = self.generate_calc_code_rs();
+        }
 
         Ok(())
     }
@@ -568,6 +826,8 @@ impl Calculator {
     ///
     /// * `dh_fw` - 每级给水焓升
     ///
+    /// * `stage` - 加热器级号
+    ///
     fn calc_fwxl(
         &self,
         p_fwxi: f64,
@@ -575,12 +835,13 @@ impl Calculator {
         t_fwxi: f64,
         dp_fi: f64,
         dh_fw: f64,
+        stage: usize,
     ) -> (f64, f64, f64, f64, f64, f64, f64, f64, f64) {
         let p_fwxo = p_fwxi - dp_fi; // 出口给水压力
         let h_fwxo = h_fwxi + dh_fw; // 出口给水比焓
         // println!("h_fwxi: {}, h_fwxo: {}, dh_fw: {}", h_fwxi, h_fwxo, dh_fw);
         let t_fwxo = ph(p_fwxo, h_fwxo, OT); // 出口给水温度
-        let t_roxk = t_fwxo + self.params.theta_lu; // 出口疏水温度
+        let t_roxk = t_fwxo + self.params.heater_theta_u(stage); // 出口疏水温度
         let h_roxk = tx(t_roxk, 0.0, OH); // 出口疏水比焓
         let p_roxk = tx(t_roxk, 0.0, OP); // 出口疏水压力
         (
@@ -601,6 +862,8 @@ impl Calculator {
     ///
     /// * `dh_fw` - 每级给水焓升
     ///
+    /// * `stage` - 加热器级号
+    ///
     fn calc_fwxh(
         &self,
         p_fwxi: f64,
@@ -608,10 +871,11 @@ impl Calculator {
         t_fwxi: f64,
         p_fwxo: f64,
         dh_fw: f64,
+        stage: usize,
     ) -> (f64, f64, f64, f64, f64, f64, f64, f64, f64) {
         let h_fwxo = h_fwxi + dh_fw; // 出口给水比焓
         let t_fwxo = ph(p_fwxo, h_fwxo, OT); // 出口给水温度
-        let t_roxk = t_fwxo + self.params.theta_hu; // 出口疏水温度
+        let t_roxk = t_fwxo + self.params.heater_theta_u(stage); // 出口疏水温度
         let p_roxk = tx(t_roxk, 0.0, OP); // 出口疏水压力
         let h_roxk = px(p_roxk, 0.0, OH); // 出口疏水比焓
         (
@@ -630,6 +894,8 @@ impl Calculator {
     /// * `s_i` - 进口蒸汽比熵
     ///
     /// * `h_i` - 进口进气比焓
+    ///
+    /// * `stage` - 加热器级号，6、7级由高压缸抽汽
     // TODO: 移除`_p_roxk`
     fn calc_esx(
         &self,
@@ -637,14 +903,10 @@ impl Calculator {
         _p_roxk: f64,
         s_i: f64,
         h_i: f64,
-        is_h: bool,
+        stage: usize,
     ) -> (f64, f64, f64, f64, f64) {
-        let t_esx = t_fwxo
-            + if is_h {
-                self.params.theta_hu
-            } else {
-                self.params.theta_lu
-            }; // 抽汽温度
+        let is_h = stage > 5;
+        let t_esx = t_fwxo + self.params.heater_theta_u(stage); // 抽汽温度
         let p_esx = tx(t_esx, 1.0, OP) / (1.0 - self.params.dp_ej);
         let h_esxs = ps(p_esx, s_i, OH); // 抽气理想比焓
         let h_esx = h_i
@@ -683,8 +945,23 @@ impl Calculator {
         Ok(())
     }
 
+    /// 生成的计算代码按逐级自流的公式计算抽汽量(疏水冷却器仅改变疏水比焓)，
+    /// 有加热器疏水由疏水泵打入下游时返回不支持的原因
+    fn calc_code_unsupported(&self) -> Option<&'static str> {
+        let p = &self.params;
+        [
+            p.drain_1, p.drain_2, p.drain_3, p.drain_4, p.drain_6, p.drain_7,
+        ]
+        .into_iter()
+        .any(|code| drains::DrainMode::from_code(code) == drains::DrainMode::PumpedForward)
+        .then_some("加热器疏水由疏水泵打入下游时不支持生成计算代码")
+    }
+
     /// 将计算代码保存到文件
     pub fn save_code_to_file(&self, base_path: &str) -> std::io::Result<()> {
+        if let Some(reason) = self.calc_code_unsupported() {
+            return Err(std::io::Error::other(reason));
+        }
         if self.calc_code_rs.is_empty() {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::Other,
@@ -706,6 +983,86 @@ impl Calculator {
         Ok(())
     }
 
+    /// 将计算结果按表格分别保存为CSV/TSV文件
+    ///
+    /// # Arguments
+    ///
+    /// * `base_path` - 输出目录
+    ///
+    /// * `delimiter` - 分隔符，`,`为CSV，`\t`为TSV
+    pub fn save_result_tables_to_files(
+        &self,
+        base_path: &str,
+        delimiter: char,
+        units: &UnitSystem,
+    ) -> std::io::Result<()> {
+        if self.get_results().is_none() {
+            return Err(std::io::Error::other("计算结果为空"));
+        }
+        let extension = if delimiter == '\t' { "tsv" } else { "csv" };
+        for table in export::csv::result_tables(&self.results, delimiter, units) {
+            let file = File::create(format!("{}/{}.{}", base_path, table.name, extension))?;
+            let mut writer = BufWriter::new(file);
+            // 写入BOM，便于电子表格软件识别UTF-8编码
+            writer.write_all("\u{feff}".as_bytes())?;
+            writer.write_all(table.content.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// 将LaTeX计算书保存到文件
+    pub fn save_report_tex_to_file(&self, base_path: &str) -> std::io::Result<()> {
+        if self.get_results().is_none() {
+            return Err(std::io::Error::other("计算结果为空"));
+        }
+        let file = File::create(format!("{}/report.tex", base_path))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(self.generate_report_tex().as_bytes())?;
+        Ok(())
+    }
+
+    /// 生成LaTeX计算书
+    pub fn generate_report_tex(&self) -> String {
+        export::tex::generate_tex_report(&self.params, &self.results)
+    }
+
+    /// 将HTML计算报告保存到文件
+    pub fn save_report_html_to_file(
+        &self,
+        base_path: &str,
+        units: &UnitSystem,
+    ) -> std::io::Result<()> {
+        if self.get_results().is_none() {
+            return Err(std::io::Error::other("计算结果为空"));
+        }
+        let file = File::create(format!("{}/report.html", base_path))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(self.generate_report_html(units).as_bytes())?;
+        Ok(())
+    }
+
+    /// 生成HTML计算报告
+    pub fn generate_report_html(&self, units: &UnitSystem) -> String {
+        export::html::generate_html_report(&self.params, &self.results, units)
+    }
+
+    /// 将输入参数与计算结果保存为Excel工作簿
+    pub fn save_workbook_to_file(
+        &self,
+        base_path: &str,
+        units: &UnitSystem,
+    ) -> std::io::Result<()> {
+        if self.get_results().is_none() {
+            return Err(std::io::Error::other("计算结果为空"));
+        }
+        let mut workbook = export::xlsx::build_workbook(&self.params, &self.results, units)
+            .map_err(std::io::Error::other)?;
+        workbook
+            .save(format!("{}/results.xlsx", base_path))
+            .map_err(std::io::Error::other)?;
+        Ok(())
+    }
+
     /// 获取计算结果
     pub fn get_results(&self) -> Option<&CalcResultParamters> {
         if self.results.result1.is_empty() {
@@ -739,9 +1096,14 @@ impl Calculator {
         ));
         let t_ci = t_co - params.dt_c;
         code.push_str(&format!(
-            "\tlet t_ci = {:.4}; // 反应堆进口冷却剂温度(℃)\n\n",
+            "\tlet t_ci = {:.4}; // 反应堆进口冷却剂温度(℃)\n",
             t_ci
         ));
+        let k_pump = 1.0 + primary_loop::pump_heat_ratio(params, t_co, t_ci);
+        code.push_str(&format!(
+            "\tlet k_pump = {:.6}; // 计入主泵热量的热功率修正系数\n\n",
+            k_pump
+        ));
 
         code.push_str("\t// 蒸汽初参数\n");
         let t_s = px(params.p_s, 1.0, OT);
@@ -1009,14 +1371,30 @@ impl Calculator {
         ));
         let dh_fwh = (h_fw_calc - h_deao) / params.z_h;
         code.push_str(&format!(
-            "\tlet dh_fwh = {:.4}; // 高压给水加热器每一级给水焓升 ()\n",
+            "\tlet dh_fwh = {:.4}; // 高压给水加热器平均每一级给水焓升 ()\n",
             dh_fwh
         ));
         let dh_fwl = (h_deao - h_cd_val) / (params.z_l + 1.0);
         code.push_str(&format!(
-            "\tlet dh_fwl = {:.4}; // 除氧器及低压加热器每一级给水焓升 ()\n\n",
+            "\tlet dh_fwl = {:.4}; // 除氧器及低压加热器平均每一级给水焓升 ()\n",
             dh_fwl
         ));
+        let rises = allocation::Allocation::from_params(params).and_then(|allocation| {
+            allocation::enthalpy_rises(params, h_cd_val, h_deao, h_fw_calc)
+                .map(|rises| (allocation, rises))
+        });
+        let (allocation, (dh_fwhx, dh_fwlx)) = match rises {
+            Ok(rises) => rises,
+            Err(error) => return format!("// 无法生成计算代码: {error}\n"),
+        };
+        code.push_str(&format!(
+            "\tlet dh_fwhx = {:.4?}; // 高压给水加热器各级给水焓升 ({}) ()\n",
+            dh_fwhx, allocation
+        ));
+        code.push_str(&format!(
+            "\tlet dh_fwlx = {:.4?}; // 除氧器及低压加热器各级给水焓升 ()\n\n",
+            dh_fwlx
+        ));
 
         code.push_str("\t// 给水回路系统中的压力选择\n");
         let p_cwp = params.dp_cwp * p_dea;
@@ -1047,19 +1425,19 @@ impl Calculator {
 
         code.push_str("\t// 低压给水加热器 \n");
         let (p_fw1i, h_fw1i, t_fw1i, p_fw1o, h_fw1o, t_fw1o, t_ro1k, h_ro1k, p_ro1k) =
-            self.calc_fwxl(p_cwp, h_cwp, t_cwp, dp_fi, dh_fwl);
+            self.calc_fwxl(p_cwp, h_cwp, t_cwp, dp_fi, dh_fwlx[0], 1);
         code.push_str(&format!("\tlet (p_fw1i, h_fw1i, t_fw1i, p_fw1o, h_fw1o, t_fw1o, t_ro1k, h_ro1k, p_ro1k) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4});\n",
         p_fw1i, h_fw1i, t_fw1i, p_fw1o, h_fw1o, t_fw1o, t_ro1k, h_ro1k, p_ro1k));
         let (p_fw2i, h_fw2i, t_fw2i, p_fw2o, h_fw2o, t_fw2o, t_ro2k, h_ro2k, p_ro2k) =
-            self.calc_fwxl(p_fw1o, h_fw1o, t_fw1o, dp_fi, dh_fwl);
+            self.calc_fwxl(p_fw1o, h_fw1o, t_fw1o, dp_fi, dh_fwlx[1], 2);
         code.push_str(&format!("\tlet (p_fw2i, h_fw2i, t_fw2i, p_fw2o, h_fw2o, t_fw2o, t_ro2k, h_ro2k, p_ro2k) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4});\n",
         p_fw2i, h_fw2i, t_fw2i, p_fw2o, h_fw2o, t_fw2o, t_ro2k, h_ro2k, p_ro2k));
         let (p_fw3i, h_fw3i, t_fw3i, p_fw3o, h_fw3o, t_fw3o, t_ro3k, h_ro3k, p_ro3k) =
-            self.calc_fwxl(p_fw2o, h_fw2o, t_fw2o, dp_fi, dh_fwl);
+            self.calc_fwxl(p_fw2o, h_fw2o, t_fw2o, dp_fi, dh_fwlx[2], 3);
         code.push_str(&format!("\tlet (p_fw3i, h_fw3i, t_fw3i, p_fw3o, h_fw3o, t_fw3o, t_ro3k, h_ro3k, p_ro3k) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4});\n",
         p_fw3i, h_fw3i, t_fw3i, p_fw3o, h_fw3o, t_fw3o, t_ro3k, h_ro3k, p_ro3k));
         let (p_fw4i, h_fw4i, t_fw4i, p_fw4o, h_fw4o, t_fw4o, t_ro4k, h_ro4k, p_ro4k) =
-            self.calc_fwxl(p_fw3o, h_fw3o, t_fw3o, dp_fi, dh_fwl);
+            self.calc_fwxl(p_fw3o, h_fw3o, t_fw3o, dp_fi, dh_fwlx[3], 4);
         code.push_str(&format!("\tlet (p_fw4i, h_fw4i, t_fw4i, p_fw4o, h_fw4o, t_fw4o, t_ro4k, h_ro4k, p_ro4k) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4});\n",
         p_fw4i, h_fw4i, t_fw4i, p_fw4o, h_fw4o, t_fw4o, t_ro4k, h_ro4k, p_ro4k));
 
@@ -1097,24 +1475,25 @@ impl Calculator {
                 h_fwpo_calc,
                 t_fwpo,
                 p_fwpo - (p_fwpo - p_fwi) / 2.0,
-                dh_fwh,
+                dh_fwhx[0],
+                6,
             );
         code.push_str(&format!("\tlet (p_fw6i, h_fw6i, t_fw6i, p_fw6o, h_fw6o, t_fw6o, t_ro6k, h_ro6k, p_ro6k) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4});\n",
         p_fw6i, h_fw6i, t_fw6i, p_fw6o, h_fw6o, t_fw6o, t_ro6k, h_ro6k, p_ro6k));
         let (p_fw7i, h_fw7i, t_fw7i, p_fw7o, h_fw7o, t_fw7o, t_ro7k, h_ro7k, p_ro7k) =
-            self.calc_fwxh(p_fw6o, h_fw6o, t_fw6o, p_fwi, dh_fwh);
+            self.calc_fwxh(p_fw6o, h_fw6o, t_fw6o, p_fwi, dh_fwhx[1], 7);
         code.push_str(&format!("\tlet (p_fw7i, h_fw7i, t_fw7i, p_fw7o, h_fw7o, t_fw7o, t_ro7k, h_ro7k, p_ro7k) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4});\n",
         p_fw7i, h_fw7i, t_fw7i, p_fw7o, h_fw7o, t_fw7o, t_ro7k, h_ro7k, p_ro7k));
 
         code.push_str("\t// 高压缸抽汽\n");
         let (p_hes6, h_hes6s, h_hes6, x_hes6, t_hes6) =
-            self.calc_esx(t_fw6o, p_ro6k, s_hi, h_hi, true);
+            self.calc_esx(t_fw6o, p_ro6k, s_hi, h_hi, 6);
         code.push_str(&format!(
             "\tlet (p_hes6, h_hes6s, h_hes6, x_hes6, t_hes7) = ({:.4}, {:.4}, {:.4}, {:.4}, {:4});\n",
             p_hes6, h_hes6s, h_hes6, x_hes6, t_hes6
         ));
         let (p_hes7, h_hes7s, h_hes7, x_hes7, t_hes7) =
-            self.calc_esx(t_fw7o, p_ro7k, s_hi, h_hi, true);
+            self.calc_esx(t_fw7o, p_ro7k, s_hi, h_hi, 7);
         code.push_str(&format!(
             "\tlet (p_hes7, h_hes7s, h_hes7, x_hes7, t_hes7) = ({:.4}, {:.4}, {:.4}, {:.4}, {:4});\n",
             p_hes7, h_hes7s, h_hes7, x_hes7, t_hes7
@@ -1122,25 +1501,25 @@ impl Calculator {
 
         code.push_str("\t// 低压缸抽汽\n");
         let (p_les1, h_les1s, h_les1, x_les1, t_les1) =
-            self.calc_esx(t_fw1o, p_ro1k, s_li, h_li, false);
+            self.calc_esx(t_fw1o, p_ro1k, s_li, h_li, 1);
         code.push_str(&format!(
             "\tlet (p_les1, h_les1s, h_les1, x_les1, t_les1) = ({:.4}, {:.4}, {:.4}, {:.4}, {:4});\n",
             p_les1, h_les1s, h_les1, x_les1, t_les1
         ));
         let (p_les2, h_les2s, h_les2, x_les2, t_les2) =
-            self.calc_esx(t_fw2o, p_ro2k, s_li, h_li, false);
+            self.calc_esx(t_fw2o, p_ro2k, s_li, h_li, 2);
         code.push_str(&format!(
             "\tlet (p_les2, h_les2s, h_les2, x_les2, t_les2) = ({:.4}, {:.4}, {:.4}, {:.4}, {:4});\n",
             p_les2, h_les2s, h_les2, x_les2, t_les2
         ));
         let (p_les3, h_les3s, h_les3, x_les3, t_les3) =
-            self.calc_esx(t_fw3o, p_ro3k, s_li, h_li, false);
+            self.calc_esx(t_fw3o, p_ro3k, s_li, h_li, 3);
         code.push_str(&format!(
             "\tlet (p_les3, h_les3s, h_les3, x_les3, t_les4) = ({:.4}, {:.4}, {:.4}, {:.4}, {:4});\n",
             p_les3, h_les3s, h_les3, x_les3,t_les3
         ));
         let (p_les4, h_les4s, h_les4, x_les4, t_les4) =
-            self.calc_esx(t_fw4o, p_ro4k, s_li, h_li, false);
+            self.calc_esx(t_fw4o, p_ro4k, s_li, h_li, 4);
         code.push_str(&format!(
             "\tlet (p_les4, h_les4s, h_les4, x_les4, t_les4) = ({:.4}, {:.4}, {:.4}, {:.4}, {:4});\n",
             p_les4, h_les4s, h_les4, x_les4,t_les4
@@ -1156,10 +1535,23 @@ impl Calculator {
         code.push_str(&format!("\tlet (p_rh2_calc, x_rh2_calc, t_rh2_calc, h_rh2_calc, h_zs2_calc) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4});\n",
         p_rh2_calc, x_rh2_calc, t_rh2_calc, h_rh2_calc, h_zs2_calc));
         code.push_str("\t// 蒸汽发生器总蒸汽产量的计算 (迭代循环)\n");
-        let h_a = h_hi - h_hz;
+        let r2 = &self.results.result2;
+        let is_turbine = feed_pump::Drive::from_params(params) == feed_pump::Drive::Turbine;
+        let (from_fh, _, to_dea) = feed_pump::shares(params);
         code.push_str(&format!(
-            "\tlet h_a = {:.4}; // 给水泵汽轮机中蒸汽的绝热焓降\n",
-            h_a
+            "\tlet h_a = {:.4}; // 给水泵汽轮机实际焓降\n",
+            r2.h_fwpti - r2.h_fwptz
+        ));
+        code.push_str(&format!(
+            "\tlet h_fwptz = {:.4}; // 给水泵汽轮机排汽比焓\n",
+            r2.h_fwptz
+        ));
+        let h_d: Vec<String> = (r2.lfwx.iter().chain(&r2.hfwx))
+            .map(|fw| format!("{:.4}", fw.h_dx))
+            .collect();
+        code.push_str(&format!(
+            "\tlet (h_d1, h_d2, h_d3, h_d4, h_d6, h_d7) = ({}); // 各级加热器疏水比焓，经疏水冷却器时低于饱和水比焓\n",
+            h_d.join(", ")
         ));
         code.push_str(&format!(
             "\tlet mut mutable_params_g_cd = {:.4}; // 迭代变量，初始值为 g_cd\n",
@@ -1186,7 +1578,7 @@ impl Calculator {
 
         code.push_str("\tloop { // 外层循环: 迭代优化核电厂效率 (ne_npp)\n");
         code.push_str("\t\tq_r_loop = ne / mutable_params_ne_npp; // 反应堆热功率(MW)\n");
-        code.push_str(&format!("\t\td_s_loop = (1000.0 * q_r_loop * {:.4}) / ((h_fh - h_s_calc) + (1.0 + {:.4}) * (h_s_calc - h_fw_calc)); // GS蒸汽产量(kg/s)\n", params.n_1, params.zeta_d));
+        code.push_str(&format!("\t\td_s_loop = (1000.0 * q_r_loop * {:.4} * k_pump) / ((h_fh - h_s_calc) + (1.0 + {:.4}) * (h_s_calc - h_fw_calc)); // GS蒸汽产量(kg/s)\n", params.n_1, params.zeta_d));
         code.push_str(&format!(
             "\t\tg_fw_loop = (1.0 + {:.4}) * d_s_loop; // GS给水流量(kg/s)\n",
             params.zeta_d
@@ -1200,15 +1592,22 @@ impl Calculator {
 
         code.push_str("\t\tloop { // 内层循环: 迭代优化冷凝器凝结水量 (g_cd)\n");
         code.push_str("\t\t\tlet n_fwpp_loop = 1000.0 * g_fw_loop * h_fwp_loop / rho_fwp_loop; // 给水泵有效输出功率(kW)\n");
-        code.push_str(&format!("\t\t\tlet n_fwpt_loop = n_fwpp_loop / ({:.4} * {:.4} * {:.4} * {:.4}); // 给水泵理论功率(kW)\n", params.n_fwpp,
-            params.n_fwpti, params.n_fwptg, params.n_fwptm));
-        code.push_str("\t\t\tg_fwps_loop = n_fwpt_loop / h_a; // 给水泵汽轮机耗汽量(kg/s)\n\n");
+        if is_turbine {
+            code.push_str(&format!("\t\t\tlet n_fwpt_loop = n_fwpp_loop / ({:.4} * {:.4} * {:.4}); // 给水泵汽轮机功率(kW)\n", params.n_fwpp,
+                params.n_fwptg, params.n_fwptm));
+            code.push_str("\t\t\tlet n_fwpm_loop = 0.0; // 电动给水泵耗电功率(kW)\n");
+            code.push_str("\t\t\tg_fwps_loop = n_fwpt_loop / h_a; // 给水泵汽轮机耗汽量(kg/s)\n\n");
+        } else {
+            code.push_str(&format!("\t\t\tlet n_fwpm_loop = n_fwpp_loop / ({:.4} * {:.4}); // 电动给水泵耗电功率(kW)\n", params.n_fwpp,
+                params.n_fwpm));
+            code.push_str("\t\t\tg_fwps_loop = 0.0; // 电动给水泵不耗汽\n\n");
+        }
 
         code.push_str("\t\t\t// 低压给水加热器抽汽量\n");
-        code.push_str(&format!("\t\t\tg_les4_loop = mutable_params_g_cd * (h_fw4o - h_fw4i) / ({:.4} * (h_les4 - h_ro4k));\n", params.n_h));
-        code.push_str(&format!("\t\t\tg_les3_loop = (mutable_params_g_cd * (h_fw3o - h_fw3i) - {:.4} * g_les4_loop * (h_ro4k - h_ro3k)) / ({:.4} * (h_les3 - h_ro3k));\n", params.n_h, params.n_h));
-        code.push_str(&format!("\t\t\tg_les2_loop = (mutable_params_g_cd * (h_fw2o - h_fw2i) - {:.4} * (g_les3_loop + g_les4_loop) * (h_ro3k - h_ro2k)) / ({:.4} * (h_les2 - h_ro2k));\n", params.n_h, params.n_h));
-        code.push_str(&format!("\t\t\tg_les1_loop = (mutable_params_g_cd * (h_fw1o - h_fw1i) - {:.4} * (g_les2_loop + g_les3_loop + g_les4_loop) * (h_ro2k - h_ro1k)) / ({:.4} * (h_les1 - h_ro1k));\n\n", params.n_h, params.n_h));
+        code.push_str(&format!("\t\t\tg_les4_loop = mutable_params_g_cd * (h_fw4o - h_fw4i) / ({:.4} * (h_les4 - h_d4));\n", params.n_h));
+        code.push_str(&format!("\t\t\tg_les3_loop = (mutable_params_g_cd * (h_fw3o - h_fw3i) - {:.4} * g_les4_loop * (h_d4 - h_d3)) / ({:.4} * (h_les3 - h_d3));\n", params.n_h, params.n_h));
+        code.push_str(&format!("\t\t\tg_les2_loop = (mutable_params_g_cd * (h_fw2o - h_fw2i) - {:.4} * (g_les3_loop + g_les4_loop) * (h_d3 - h_d2)) / ({:.4} * (h_les2 - h_d2));\n", params.n_h, params.n_h));
+        code.push_str(&format!("\t\t\tg_les1_loop = (mutable_params_g_cd * (h_fw1o - h_fw1i) - {:.4} * (g_les2_loop + g_les3_loop + g_les4_loop) * (h_d2 - h_d1)) / ({:.4} * (h_les1 - h_d1));\n\n", params.n_h, params.n_h));
 
         code.push_str("\t\t\t// 低压缸耗气量(kg/s)\n");
         code.push_str(&format!("\t\t\tg_sl_loop = (0.6 * 1000.0 * {:.4} / ({:.4} * {:.4}) + g_les4_loop * (h_les4 - h_lz) + g_les3_loop * (h_les3 - h_lz) + g_les2_loop * (h_les2 - h_lz) + g_les1_loop * (h_les1 - h_lz)) / (h_li - h_lz);\n\n",
@@ -1216,37 +1615,42 @@ impl Calculator {
 
         code.push_str("\t\t\t// 再热器加热蒸汽量\n");
         code.push_str(&format!(
-            "\t\t\tg_zc1_loop = g_sl_loop * dh_rh / ({:.4} * (h_rh1_calc - h_zs1_calc));\n",
+            "\t\t\tlet g_rh_loop = g_sl_loop + {:.1} * g_fwps_loop; // 汽水分离再热器出口蒸汽流量\n",
+            1.0 - from_fh
+        ));
+        code.push_str(&format!(
+            "\t\t\tg_zc1_loop = g_rh_loop * dh_rh / ({:.4} * (h_rh1_calc - h_zs1_calc));\n",
             params.n_h
         ));
         code.push_str(&format!(
-            "\t\t\tg_zc2_loop = g_sl_loop * dh_rh / ({:.4} * (h_rh2_calc - h_zs2_calc));\n\n",
+            "\t\t\tg_zc2_loop = g_rh_loop * dh_rh / ({:.4} * (h_rh2_calc - h_zs2_calc));\n\n",
             params.n_h
         ));
 
         code.push_str("\t\t\t// 高压给水加热器抽汽量\n");
-        code.push_str(&format!("\t\t\tg_hes7_loop = (g_fw_loop * (h_fw7o - h_fw7i) - {:.4} * g_zc2_loop * (h_zs2_calc - h_ro7k)) / ({:.4} * (h_hes7 - h_ro7k));\n", params.n_h, params.n_h));
-        code.push_str(&format!("\t\t\tg_hes6_loop = (g_fw_loop * (h_fw6o - h_fw6i) - {:.4} * g_zc1_loop * (h_zs1_calc - h_ro6k) - {:.4} * (g_zc2_loop + g_hes7_loop) * (h_ro7k - h_ro6k)) / ({:.4} * (h_hes6 - h_ro6k));\n\n", params.n_h, params.n_h, params.n_h));
+        code.push_str(&format!("\t\t\tg_hes7_loop = (g_fw_loop * (h_fw7o - h_fw7i) - {:.4} * g_zc2_loop * (h_zs2_calc - h_d7)) / ({:.4} * (h_hes7 - h_d7));\n", params.n_h, params.n_h));
+        code.push_str(&format!("\t\t\tg_hes6_loop = (g_fw_loop * (h_fw6o - h_fw6i) - {:.4} * g_zc1_loop * (h_zs1_calc - h_d6) - {:.4} * (g_zc2_loop + g_hes7_loop) * (h_d7 - h_d6)) / ({:.4} * (h_hes6 - h_d6));\n\n", params.n_h, params.n_h, params.n_h));
 
         code.push_str(
-            "\t\t\tg_uw_loop = g_sl_loop * (x_rh1i - x_spi) / x_spi; // 汽水分离器疏水流量(kg/s)\n\n"
+            "\t\t\tg_uw_loop = g_rh_loop * (x_rh1i - x_spi) / x_spi; // 汽水分离器疏水流量(kg/s)\n\n"
         );
 
         code.push_str("\t\t\t// 除氧器耗汽量\n");
-        code.push_str("\t\t\tg_sdea_loop = (g_fw_loop * h_deao - g_uw_loop * h_uw - mutable_params_g_cd * h_fw4o - (g_zc1_loop + g_zc2_loop + g_hes6_loop + g_hes7_loop) * h_ro6k) / h_hz;\n\n");
+        code.push_str(&format!("\t\t\tg_sdea_loop = (g_fw_loop * h_deao - g_uw_loop * h_uw - mutable_params_g_cd * h_fw4o - (g_zc1_loop + g_zc2_loop + g_hes6_loop + g_hes7_loop) * h_d6 - {:.1} * g_fwps_loop * h_fwptz) / h_hz;\n\n", to_dea));
 
         code.push_str("\t\t\t// 高压缸耗汽量\n");
-        code.push_str(&format!("\t\t\tg_sh_loop = (0.4 * 1000.0 * ne / ({:.4} * {:.4}) + g_hes7_loop * (h_hes7 - h_hz) + g_hes6_loop * (h_hes6 - h_hz) + g_zc1_loop * (h_rh1_calc - h_hz)) / (h_hi - h_hz);\n\n", params.n_m, params.n_ge));
+        code.push_str(&format!("\t\t\tg_sh_loop = (0.4 * (1000.0 * ne + n_fwpm_loop) / ({:.4} * {:.4}) + g_hes7_loop * (h_hes7 - h_hz) + g_hes6_loop * (h_hes6 - h_hz) + g_zc1_loop * (h_rh1_calc - h_hz)) / (h_hi - h_hz);\n\n", params.n_m, params.n_ge));
 
         code.push_str("\t\t\t// 对假设冷凝水流量验证\n");
-        code.push_str("\t\t\td_s_loop = g_fwps_loop + g_zc2_loop + g_sh_loop; // 新蒸汽耗量 (根据新的流量重新评估 d_s_loop)\n");
+        code.push_str(&format!("\t\t\td_s_loop = {:.1} * g_fwps_loop + g_zc2_loop + g_sh_loop; // 新蒸汽耗量 (根据新的流量重新评估 d_s_loop)\n", from_fh));
         code.push_str(&format!(
             "\t\t\tlet g_fw1_loop = (1.0 + {:.4}) * d_s_loop; // 给水流量\n",
             params.zeta_d
         ));
-        code.push_str(
-            "\t\t\tlet g_cd1_loop = g_fw1_loop - g_sdea_loop - g_uw_loop - (g_hes6_loop + g_hes7_loop + g_zc1_loop + g_zc2_loop);\n",
-        );
+        code.push_str(&format!(
+            "\t\t\tlet g_cd1_loop = g_fw1_loop - g_sdea_loop - g_uw_loop - (g_hes6_loop + g_hes7_loop + g_zc1_loop + g_zc2_loop) - {:.1} * g_fwps_loop;\n",
+            to_dea
+        ));
 
         code.push_str(
             "\t\t\tif ((g_cd1_loop - mutable_params_g_cd) as f64).abs() / mutable_params_g_cd < 1e-2 {\n",
@@ -1258,7 +1662,7 @@ impl Calculator {
         code.push_str("\t\t\t}\n");
         code.push_str("\t\t} // 内层循环结束\n\n");
 
-        code.push_str(&format!("\t\tq_r_loop = (d_s_loop * (h_fh - h_fw_calc) + {:.4} * d_s_loop * (h_s_calc - h_fw_calc)) / (1000.0 * {:.4}); // 新反应堆热功率(MW)\n", params.zeta_d, params.n_1));
+        code.push_str(&format!("\t\tq_r_loop = (d_s_loop * (h_fh - h_fw_calc) + {:.4} * d_s_loop * (h_s_calc - h_fw_calc)) / (1000.0 * {:.4} * k_pump); // 新反应堆热功率(MW)\n", params.zeta_d, params.n_1));
         code.push_str("\t\tlet n_ennp1_loop = ne / q_r_loop;\n\n");
 
         // 打印迭代结果 (CalcResult1)
@@ -1643,9 +2047,11 @@ impl Calculator {
         let t_co = t_cs - params.dt_sub;
         code.push_str(&format!("\tt_co = {:.4} # 反应堆出口冷却剂温度(℃)\n", t_co));
         let t_ci = t_co - params.dt_c;
+        code.push_str(&format!("\tt_ci = {:.4} # 反应堆进口冷却剂温度(℃)\n", t_ci));
+        let k_pump = 1.0 + primary_loop::pump_heat_ratio(params, t_co, t_ci);
         code.push_str(&format!(
-            "\tt_ci = {:.4} # 反应堆进口冷却剂温度(℃)\n\n",
-            t_ci
+            "\tk_pump = {:.6} # 计入主泵热量的热功率修正系数\n\n",
+            k_pump
         ));
 
         code.push_str("\t# 蒸汽初参数\n");
@@ -1875,14 +2281,30 @@ impl Calculator {
         ));
         let dh_fwh = (h_fw_calc - h_deao) / params.z_h;
         code.push_str(&format!(
-            "\tdh_fwh = {:.4} # 高压给水加热器每一级给水焓升 ()\n",
+            "\tdh_fwh = {:.4} # 高压给水加热器平均每一级给水焓升 ()\n",
             dh_fwh
         ));
         let dh_fwl = (h_deao - h_cd_val) / (params.z_l + 1.0);
         code.push_str(&format!(
-            "\tdh_fwl = {:.4} # 除氧器及低压加热器每一级给水焓升 ()\n\n",
+            "\tdh_fwl = {:.4} # 除氧器及低压加热器平均每一级给水焓升 ()\n",
             dh_fwl
         ));
+        let rises = allocation::Allocation::from_params(params).and_then(|allocation| {
+            allocation::enthalpy_rises(params, h_cd_val, h_deao, h_fw_calc)
+                .map(|rises| (allocation, rises))
+        });
+        let (allocation, (dh_fwhx, dh_fwlx)) = match rises {
+            Ok(rises) => rises,
+            Err(error) => return format!("# 无法生成计算代码: {error}\n"),
+        };
+        code.push_str(&format!(
+            "\tdh_fwhx = {:.4?} # 高压给水加热器各级给水焓升 ({}) ()\n",
+            dh_fwhx, allocation
+        ));
+        code.push_str(&format!(
+            "\tdh_fwlx = {:.4?} # 除氧器及低压加热器各级给水焓升 ()\n\n",
+            dh_fwlx
+        ));
 
         code.push_str("\t# 给水回路系统中的压力选择\n");
         let p_cwp = params.dp_cwp * p_dea;
@@ -1904,19 +2326,19 @@ impl Calculator {
 
         code.push_str("\t# 低压给水加热器 \n");
         let (p_fw1i, h_fw1i, t_fw1i, p_fw1o, h_fw1o, t_fw1o, t_ro1k, h_ro1k, p_ro1k) =
-            self.calc_fwxl(p_cwp, h_cwp, t_cwp, dp_fi, dh_fwl);
+            self.calc_fwxl(p_cwp, h_cwp, t_cwp, dp_fi, dh_fwlx[0], 1);
         code.push_str(&format!("\t(p_fw1i, h_fw1i, t_fw1i, p_fw1o, h_fw1o, t_fw1o, t_ro1k, h_ro1k, p_ro1k) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4})\n",
         p_fw1i, h_fw1i, t_fw1i, p_fw1o, h_fw1o, t_fw1o, t_ro1k, h_ro1k, p_ro1k));
         let (p_fw2i, h_fw2i, t_fw2i, p_fw2o, h_fw2o, t_fw2o, t_ro2k, h_ro2k, p_ro2k) =
-            self.calc_fwxl(p_fw1o, h_fw1o, t_fw1o, dp_fi, dh_fwl);
+            self.calc_fwxl(p_fw1o, h_fw1o, t_fw1o, dp_fi, dh_fwlx[1], 2);
         code.push_str(&format!("\t(p_fw2i, h_fw2i, t_fw2i, p_fw2o, h_fw2o, t_fw2o, t_ro2k, h_ro2k, p_ro2k) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4})\n",
         p_fw2i, h_fw2i, t_fw2i, p_fw2o, h_fw2o, t_fw2o, t_ro2k, h_ro2k, p_ro2k));
         let (p_fw3i, h_fw3i, t_fw3i, p_fw3o, h_fw3o, t_fw3o, t_ro3k, h_ro3k, p_ro3k) =
-            self.calc_fwxl(p_fw2o, h_fw2o, t_fw2o, dp_fi, dh_fwl);
+            self.calc_fwxl(p_fw2o, h_fw2o, t_fw2o, dp_fi, dh_fwlx[2], 3);
         code.push_str(&format!("\t(p_fw3i, h_fw3i, t_fw3i, p_fw3o, h_fw3o, t_fw3o, t_ro3k, h_ro3k, p_ro3k) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4})\n",
         p_fw3i, h_fw3i, t_fw3i, p_fw3o, h_fw3o, t_fw3o, t_ro3k, h_ro3k, p_ro3k));
         let (p_fw4i, h_fw4i, t_fw4i, p_fw4o, h_fw4o, t_fw4o, t_ro4k, h_ro4k, p_ro4k) =
-            self.calc_fwxl(p_fw3o, h_fw3o, t_fw3o, dp_fi, dh_fwl);
+            self.calc_fwxl(p_fw3o, h_fw3o, t_fw3o, dp_fi, dh_fwlx[3], 4);
         code.push_str(&format!("\t(p_fw4i, h_fw4i, t_fw4i, p_fw4o, h_fw4o, t_fw4o, t_ro4k, h_ro4k, p_ro4k) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4})\n",
         p_fw4i, h_fw4i, t_fw4i, p_fw4o, h_fw4o, t_fw4o, t_ro4k, h_ro4k, p_ro4k));
 
@@ -1945,24 +2367,25 @@ impl Calculator {
                 h_fwpo_calc,
                 t_fwpo,
                 p_fwpo - (p_fwpo - p_fwi) / 2.0,
-                dh_fwh,
+                dh_fwhx[0],
+                6,
             );
         code.push_str(&format!("\t(p_fw6i, h_fw6i, t_fw6i, p_fw6o, h_fw6o, t_fw6o, t_ro6k, h_ro6k, p_ro6k) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4})\n",
         p_fw6i, h_fw6i, t_fw6i, p_fw6o, h_fw6o, t_fw6o, t_ro6k, h_ro6k, p_ro6k));
         let (p_fw7i, h_fw7i, t_fw7i, p_fw7o, h_fw7o, t_fw7o, t_ro7k, h_ro7k, p_ro7k) =
-            self.calc_fwxh(p_fw6o, h_fw6o, t_fw6o, p_fwi, dh_fwh);
+            self.calc_fwxh(p_fw6o, h_fw6o, t_fw6o, p_fwi, dh_fwhx[1], 7);
         code.push_str(&format!("\t(p_fw7i, h_fw7i, t_fw7i, p_fw7o, h_fw7o, t_fw7o, t_ro7k, h_ro7k, p_ro7k) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4})\n",
         p_fw7i, h_fw7i, t_fw7i, p_fw7o, h_fw7o, t_fw7o, t_ro7k, h_ro7k, p_ro7k));
 
         code.push_str("\t# 高压缸抽汽\n");
         let (p_hes6, h_hes6s, h_hes6, x_hes6, t_hes6) =
-            self.calc_esx(t_fw6o, p_ro6k, s_hi, h_hi, true);
+            self.calc_esx(t_fw6o, p_ro6k, s_hi, h_hi, 6);
         code.push_str(&format!(
             "\t(p_hes6, h_hes6s, h_hes6, x_hes6, t_hes6) = ({:.4}, {:.4}, {:.4}, {:.4}, {:4})\n",
             p_hes6, h_hes6s, h_hes6, x_hes6, t_hes6
         ));
         let (p_hes7, h_hes7s, h_hes7, x_hes7, t_hes7) =
-            self.calc_esx(t_fw7o, p_ro7k, s_hi, h_hi, true);
+            self.calc_esx(t_fw7o, p_ro7k, s_hi, h_hi, 7);
         code.push_str(&format!(
             "\t(p_hes7, h_hes7s, h_hes7, x_hes7, t_hes7) = ({:.4}, {:.4}, {:.4}, {:.4}, {:4})\n",
             p_hes7, h_hes7s, h_hes7, x_hes7, t_hes7
@@ -1970,25 +2393,25 @@ impl Calculator {
 
         code.push_str("\t# 低压缸抽汽\n");
         let (p_les1, h_les1s, h_les1, x_les1, t_les1) =
-            self.calc_esx(t_fw1o, p_ro1k, s_li, h_li, false);
+            self.calc_esx(t_fw1o, p_ro1k, s_li, h_li, 1);
         code.push_str(&format!(
             "\t(p_les1, h_les1s, h_les1, x_les1, t_les1) = ({:.4}, {:.4}, {:.4}, {:.4}, {:4})\n",
             p_les1, h_les1s, h_les1, x_les1, t_les1
         ));
         let (p_les2, h_les2s, h_les2, x_les2, t_les2) =
-            self.calc_esx(t_fw2o, p_ro2k, s_li, h_li, false);
+            self.calc_esx(t_fw2o, p_ro2k, s_li, h_li, 2);
         code.push_str(&format!(
             "\t(p_les2, h_les2s, h_les2, x_les2, t_les2) = ({:.4}, {:.4}, {:.4}, {:.4}, {:4})\n",
             p_les2, h_les2s, h_les2, x_les2, t_les2
         ));
         let (p_les3, h_les3s, h_les3, x_les3, t_les3) =
-            self.calc_esx(t_fw3o, p_ro3k, s_li, h_li, false);
+            self.calc_esx(t_fw3o, p_ro3k, s_li, h_li, 3);
         code.push_str(&format!(
             "\t(p_les3, h_les3s, h_les3, x_les3, t_les3) = ({:.4}, {:.4}, {:.4}, {:.4}, {:4})\n",
             p_les3, h_les3s, h_les3, x_les3, t_les3
         ));
         let (p_les4, h_les4s, h_les4, x_les4, t_les4) =
-            self.calc_esx(t_fw4o, p_ro4k, s_li, h_li, false);
+            self.calc_esx(t_fw4o, p_ro4k, s_li, h_li, 4);
         code.push_str(&format!(
             "\t(p_les4, h_les4s, h_les4, x_les4, t_les1) = ({:.4}, {:.4}, {:.4}, {:.4}, {:4})\n",
             p_les4, h_les4s, h_les4, x_les4, t_les4
@@ -2004,10 +2427,23 @@ impl Calculator {
         code.push_str(&format!("\t(p_rh2_calc, x_rh2_calc, t_rh2_calc, h_rh2_calc, h_zs2_calc) = ({:.4}, {:.4}, {:.4}, {:.4}, {:.4})\n",
         p_rh2_calc, x_rh2_calc, t_rh2_calc, h_rh2_calc, h_zs2_calc));
         code.push_str("\t# 蒸汽发生器总蒸汽产量的计算 (迭代循环)\n");
-        let h_a = h_hi - h_hz;
+        let r2 = &self.results.result2;
+        let is_turbine = feed_pump::Drive::from_params(params) == feed_pump::Drive::Turbine;
+        let (from_fh, _, to_dea) = feed_pump::shares(params);
+        code.push_str(&format!(
+            "\th_a = {:.4} # 给水泵汽轮机实际焓降\n",
+            r2.h_fwpti - r2.h_fwptz
+        ));
+        code.push_str(&format!(
+            "\th_fwptz = {:.4} # 给水泵汽轮机排汽比焓\n",
+            r2.h_fwptz
+        ));
+        let h_d: Vec<String> = (r2.lfwx.iter().chain(&r2.hfwx))
+            .map(|fw| format!("{:.4}", fw.h_dx))
+            .collect();
         code.push_str(&format!(
-            "\th_a = {:.4} # 给水泵汽轮机中蒸汽的绝热焓降\n",
-            h_a
+            "\th_d1, h_d2, h_d3, h_d4, h_d6, h_d7 = ({}) # 各级加热器疏水比焓，经疏水冷却器时低于饱和水比焓\n",
+            h_d.join(", ")
         ));
         code.push_str(&format!(
             "\tmutable_params_g_cd = {:.4} # 迭代变量，初始值为 g_cd\n",
@@ -2036,7 +2472,7 @@ impl Calculator {
 
         code.push_str("\twhile True: # 外层循环: 迭代优化核电厂效率 (ne_npp)\n");
         code.push_str("\t\tq_r_loop = ne / mutable_params_ne_npp # 反应堆热功率(MW)\n");
-        code.push_str(&format!("\t\td_s_loop = (1000.0 * q_r_loop * {:.4}) / ((h_fh - h_s_calc) + (1.0 + {:.4}) * (h_s_calc - h_fw_calc)) # GS蒸汽产量(kg/s)\n", params.n_1, params.zeta_d));
+        code.push_str(&format!("\t\td_s_loop = (1000.0 * q_r_loop * {:.4} * k_pump) / ((h_fh - h_s_calc) + (1.0 + {:.4}) * (h_s_calc - h_fw_calc)) # GS蒸汽产量(kg/s)\n", params.n_1, params.zeta_d));
         code.push_str(&format!(
             "\t\tg_fw_loop = (1.0 + {:.4}) * d_s_loop # GS给水流量(kg/s)\n",
             params.zeta_d
@@ -2050,17 +2486,26 @@ impl Calculator {
 
         code.push_str("\t\twhile True:# 内层循环: 迭代优化冷凝器凝结水量 (g_cd)\n");
         code.push_str("\t\t\tn_fwpp_loop = 1000.0 * g_fw_loop * h_fwp_loop / rho_fwp_loop # 给水泵有效输出功率(kW)\n");
-        code.push_str(&format!(
-        "\t\t\tn_fwpt_loop = n_fwpp_loop / ({:.4} * {:.4} * {:.4} * {:.4}) # 给水泵理论功率(kW)\n",
-        params.n_fwpp, params.n_fwpti, params.n_fwptg, params.n_fwptm
-    ));
-        code.push_str("\t\t\tg_fwps_loop = n_fwpt_loop / h_a# 给水泵汽轮机耗汽量(kg/s)\n\n");
+        if is_turbine {
+            code.push_str(&format!(
+                "\t\t\tn_fwpt_loop = n_fwpp_loop / ({:.4} * {:.4} * {:.4}) # 给水泵汽轮机功率(kW)\n",
+                params.n_fwpp, params.n_fwptg, params.n_fwptm
+            ));
+            code.push_str("\t\t\tn_fwpm_loop = 0.0 # 电动给水泵耗电功率(kW)\n");
+            code.push_str("\t\t\tg_fwps_loop = n_fwpt_loop / h_a# 给水泵汽轮机耗汽量(kg/s)\n\n");
+        } else {
+            code.push_str(&format!(
+                "\t\t\tn_fwpm_loop = n_fwpp_loop / ({:.4} * {:.4}) # 电动给水泵耗电功率(kW)\n",
+                params.n_fwpp, params.n_fwpm
+            ));
+            code.push_str("\t\t\tg_fwps_loop = 0.0 # 电动给水泵不耗汽\n\n");
+        }
 
         code.push_str("\t\t# 低压给水加热器抽汽量\n");
-        code.push_str(&format!("\t\t\tg_les4_loop = mutable_params_g_cd * (h_fw4o - h_fw4i) / ({:.4} * (h_les4 - h_ro4k));\n", params.n_h));
-        code.push_str(&format!("\t\t\tg_les3_loop = (mutable_params_g_cd * (h_fw3o - h_fw3i) - {:.4} * g_les4_loop * (h_ro4k - h_ro3k)) / ({:.4} * (h_les3 - h_ro3k))\n", params.n_h, params.n_h));
-        code.push_str(&format!("\t\t\tg_les2_loop = (mutable_params_g_cd * (h_fw2o - h_fw2i) - {:.4} * (g_les3_loop + g_les4_loop) * (h_ro3k - h_ro2k)) / ({:.4} * (h_les2 - h_ro2k))\n", params.n_h, params.n_h));
-        code.push_str(&format!("\t\t\tg_les1_loop = (mutable_params_g_cd * (h_fw1o - h_fw1i) - {:.4} * (g_les2_loop + g_les3_loop + g_les4_loop) * (h_ro2k - h_ro1k)) / ({:.4} * (h_les1 - h_ro1k))\n\n", params.n_h, params.n_h));
+        code.push_str(&format!("\t\t\tg_les4_loop = mutable_params_g_cd * (h_fw4o - h_fw4i) / ({:.4} * (h_les4 - h_d4));\n", params.n_h));
+        code.push_str(&format!("\t\t\tg_les3_loop = (mutable_params_g_cd * (h_fw3o - h_fw3i) - {:.4} * g_les4_loop * (h_d4 - h_d3)) / ({:.4} * (h_les3 - h_d3))\n", params.n_h, params.n_h));
+        code.push_str(&format!("\t\t\tg_les2_loop = (mutable_params_g_cd * (h_fw2o - h_fw2i) - {:.4} * (g_les3_loop + g_les4_loop) * (h_d3 - h_d2)) / ({:.4} * (h_les2 - h_d2))\n", params.n_h, params.n_h));
+        code.push_str(&format!("\t\t\tg_les1_loop = (mutable_params_g_cd * (h_fw1o - h_fw1i) - {:.4} * (g_les2_loop + g_les3_loop + g_les4_loop) * (h_d2 - h_d1)) / ({:.4} * (h_les1 - h_d1))\n\n", params.n_h, params.n_h));
 
         code.push_str("\t\t# 低压缸耗气量(kg/s)\n");
         code.push_str(&format!("\t\t\tg_sl_loop = (0.6 * 1000.0 * {:.4} / ({:.4} * {:.4}) + g_les4_loop * (h_les4 - h_lz) + g_les3_loop * (h_les3 - h_lz) + g_les2_loop * (h_les2 - h_lz) + g_les1_loop * (h_les1 - h_lz)) / (h_li - h_lz)\n\n",
@@ -2068,37 +2513,42 @@ impl Calculator {
 
         code.push_str("\t\t# 再热器加热蒸汽量\n");
         code.push_str(&format!(
-            "\t\t\tg_zc1_loop = g_sl_loop * dh_rh / ({:.4} * (h_rh1_calc - h_zs1_calc))\n",
+            "\t\t\tg_rh_loop = g_sl_loop + {:.1} * g_fwps_loop # 汽水分离再热器出口蒸汽流量\n",
+            1.0 - from_fh
+        ));
+        code.push_str(&format!(
+            "\t\t\tg_zc1_loop = g_rh_loop * dh_rh / ({:.4} * (h_rh1_calc - h_zs1_calc))\n",
             params.n_h
         ));
         code.push_str(&format!(
-            "\t\t\tg_zc2_loop = g_sl_loop * dh_rh / ({:.4} * (h_rh2_calc - h_zs2_calc))\n\n",
+            "\t\t\tg_zc2_loop = g_rh_loop * dh_rh / ({:.4} * (h_rh2_calc - h_zs2_calc))\n\n",
             params.n_h
         ));
 
         code.push_str("\t\t# 高压给水加热器抽汽量\n");
-        code.push_str(&format!("\t\t\tg_hes7_loop = (g_fw_loop * (h_fw7o - h_fw7i) - {:.4} * g_zc2_loop * (h_zs2_calc - h_ro7k)) / ({:.4} * (h_hes7 - h_ro7k))\n", params.n_h, params.n_h));
-        code.push_str(&format!("\t\t\tg_hes6_loop = (g_fw_loop * (h_fw6o - h_fw6i) - {:.4} * g_zc1_loop * (h_zs1_calc - h_ro6k) - {:.4} * (g_zc2_loop + g_hes7_loop) * (h_ro7k - h_ro6k)) / ({:.4} * (h_hes6 - h_ro6k))\n\n", params.n_h, params.n_h, params.n_h));
+        code.push_str(&format!("\t\t\tg_hes7_loop = (g_fw_loop * (h_fw7o - h_fw7i) - {:.4} * g_zc2_loop * (h_zs2_calc - h_d7)) / ({:.4} * (h_hes7 - h_d7))\n", params.n_h, params.n_h));
+        code.push_str(&format!("\t\t\tg_hes6_loop = (g_fw_loop * (h_fw6o - h_fw6i) - {:.4} * g_zc1_loop * (h_zs1_calc - h_d6) - {:.4} * (g_zc2_loop + g_hes7_loop) * (h_d7 - h_d6)) / ({:.4} * (h_hes6 - h_d6))\n\n", params.n_h, params.n_h, params.n_h));
 
         code.push_str(
-            "\t\t\tg_uw_loop = g_sl_loop * (x_rh1i - x_spi) / x_spi# 汽水分离器疏水流量(kg/s)\n\n",
+            "\t\t\tg_uw_loop = g_rh_loop * (x_rh1i - x_spi) / x_spi# 汽水分离器疏水流量(kg/s)\n\n",
         );
 
         code.push_str("\t\t# 除氧器耗汽量\n");
-        code.push_str("\t\t\tg_sdea_loop = (g_fw_loop * h_deao - g_uw_loop * h_uw - mutable_params_g_cd * h_fw4o - (g_zc1_loop + g_zc2_loop + g_hes6_loop + g_hes7_loop) * h_ro6k) / h_hz\n\n");
+        code.push_str(&format!("\t\t\tg_sdea_loop = (g_fw_loop * h_deao - g_uw_loop * h_uw - mutable_params_g_cd * h_fw4o - (g_zc1_loop + g_zc2_loop + g_hes6_loop + g_hes7_loop) * h_d6 - {:.1} * g_fwps_loop * h_fwptz) / h_hz\n\n", to_dea));
 
         code.push_str("\t\t# 高压缸耗汽量\n");
-        code.push_str(&format!("\t\t\tg_sh_loop = (0.4 * 1000.0 * ne / ({:.4} * {:.4}) + g_hes7_loop * (h_hes7 - h_hz) + g_hes6_loop * (h_hes6 - h_hz) + g_zc1_loop * (h_rh1_calc - h_hz)) / (h_hi - h_hz)\n\n", params.n_m, params.n_ge));
+        code.push_str(&format!("\t\t\tg_sh_loop = (0.4 * (1000.0 * ne + n_fwpm_loop) / ({:.4} * {:.4}) + g_hes7_loop * (h_hes7 - h_hz) + g_hes6_loop * (h_hes6 - h_hz) + g_zc1_loop * (h_rh1_calc - h_hz)) / (h_hi - h_hz)\n\n", params.n_m, params.n_ge));
 
         code.push_str("\t\t# 对假设冷凝水流量验证\n");
-        code.push_str("\t\t\td_s_loop = g_fwps_loop + g_zc2_loop + g_sh_loop# 新蒸汽耗量 (根据新的流量重新评估 d_s_loop)\n");
+        code.push_str(&format!("\t\t\td_s_loop = {:.1} * g_fwps_loop + g_zc2_loop + g_sh_loop# 新蒸汽耗量 (根据新的流量重新评估 d_s_loop)\n", from_fh));
         code.push_str(&format!(
             "\t\t\tg_fw1_loop = (1.0 + {:.4}) * d_s_loop# 给水流量\n",
             params.zeta_d
         ));
-        code.push_str(
-            "\t\t\tg_cd1_loop = g_fw1_loop - g_sdea_loop - g_uw_loop - (g_hes6_loop + g_hes7_loop + g_zc1_loop + g_zc2_loop)\n",
-        );
+        code.push_str(&format!(
+            "\t\t\tg_cd1_loop = g_fw1_loop - g_sdea_loop - g_uw_loop - (g_hes6_loop + g_hes7_loop + g_zc1_loop + g_zc2_loop) - {:.1} * g_fwps_loop\n",
+            to_dea
+        ));
 
         code.push_str(
             "\t\t\tif abs(g_cd1_loop - mutable_params_g_cd) / mutable_params_g_cd < 1e-2:\n",
@@ -2109,7 +2559,7 @@ impl Calculator {
         code.push_str("\t\t\t\tg_fw_loop = g_fw1_loop# 为下一次内层迭代更新 g_fw_loop\n");
         code.push_str("\t# 内层循环结束\n\n");
 
-        code.push_str(&format!("\t\tq_r_loop = (d_s_loop * (h_fh - h_fw_calc) + {:.4} * d_s_loop * (h_s_calc - h_fw_calc)) / (1000.0 * {:.4})# 新反应堆热功率(MW)\n", params.zeta_d, params.n_1));
+        code.push_str(&format!("\t\tq_r_loop = (d_s_loop * (h_fh - h_fw_calc) + {:.4} * d_s_loop * (h_s_calc - h_fw_calc)) / (1000.0 * {:.4} * k_pump)# 新反应堆热功率(MW)\n", params.zeta_d, params.n_1));
         code.push_str("\t\tn_ennp1_loop = ne / q_r_loop\n\n");
 
         // 打印迭代结果 (CalcResult1)
@@ -2453,9 +2903,110 @@ mod tests {
 
     #[test]
     fn test_calculator() {
-        let mut calculator = Calculator::default();
+        let mut calculator = Calculator::new(CalcInputParameters::from_default());
         calculator.calculate().unwrap();
         let result = calculator.get_results();
         assert!(result.is_some(), "Expect result is Some(...)");
+
+        // 参数全为0时迭代不收敛，应返回错误而非无限循环
+        let mut calculator = Calculator::default();
+        assert!(calculator.calculate().is_err());
+    }
+
+    #[test]
+    fn test_extraction_flows() {
+        // 默认参数与早期版本的计算结果一致
+        let mut calculator = Calculator::new(CalcInputParameters::from_default());
+        calculator.calculate().unwrap();
+        let r1 = calculator.results.result1.last().unwrap();
+        assert!((r1.eta_enpp - 0.319703).abs() < 1e-6, "{}", r1.eta_enpp);
+
+        for preset in crate::presets::builtin_presets() {
+            let mut calculator = Calculator::new(preset.params);
+            calculator.calculate().unwrap();
+            let r1 = calculator.results.result1.last().unwrap();
+            assert!(r1.g_sdea >= 0.0, "{}: G_sdea = {}", preset.name, r1.g_sdea);
+        }
+
+        // 给水泵汽轮机排汽全部进入除氧器使除氧器抽汽为负
+        let mut calculator = Calculator::new(CalcInputParameters {
+            fwpt_exhaust: 1.0,
+            fwpt_expansion: 1.0,
+            ..CalcInputParameters::from_default()
+        });
+        let error = calculator.calculate().unwrap_err().to_string();
+        assert!(error.contains("为负值"), "{error}");
+    }
+
+    #[test]
+    fn test_calc_code_drain_modes() {
+        let default = CalcInputParameters::from_default();
+        let mut calculator = Calculator::new(CalcInputParameters {
+            drain_4: 2.0,
+            ..default.clone()
+        });
+        calculator.calculate().unwrap();
+        // 经疏水冷却器时生成的代码使用冷却后的疏水比焓
+        let heater = &calculator.results.result2.lfwx[3];
+        assert!(heater.h_dx < heater.h_roxk);
+        let h_d4 = format!("{:.4}", heater.h_dx);
+        assert!(calculator.calc_code_rs.contains(&h_d4));
+        assert!(calculator.calc_code_py.contains(&h_d4));
+
+        let mut calculator = Calculator::new(CalcInputParameters {
+            drain_4: 1.0,
+            ..default
+        });
+        calculator.calculate().unwrap();
+        assert!(!calculator.calc_code_rs.contains("fn main"));
+        let dir = std::env::temp_dir();
+        assert!(calculator.save_code_to_file(dir.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_heater_terminal_differences() {
+        let solve = |params: CalcInputParameters| {
+            let mut calculator = Calculator::new(params);
+            calculator.calculate().unwrap();
+            calculator.results.result2
+        };
+        let ttd = |heater: &CalcFWParameters| heater.t_roxk - heater.t_fwxo;
+        let default = CalcInputParameters::from_default();
+        let base = solve(default.clone());
+        assert!((ttd(&base.lfwx[1]) - default.theta_lu).abs() < 1e-9);
+        assert!((ttd(&base.hfwx[1]) - default.theta_hu).abs() < 1e-9);
+
+        // 单独给定的端差只作用于该级，其余各级取全局值
+        let r2 = solve(CalcInputParameters {
+            theta_u2: Some(4.0),
+            theta_u7: Some(-1.5),
+            ..default
+        });
+        assert!((ttd(&r2.lfwx[1]) - 4.0).abs() < 1e-9);
+        assert!((ttd(&r2.hfwx[1]) + 1.5).abs() < 1e-9);
+        for i in [0, 2, 3] {
+            assert!((ttd(&r2.lfwx[i]) - ttd(&base.lfwx[i])).abs() < 1e-9);
+        }
+        assert!((ttd(&r2.hfwx[0]) - ttd(&base.hfwx[0])).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_generate_report_tex() {
+        let mut calculator = Calculator::new(CalcInputParameters::from_default());
+        calculator.calculate().unwrap();
+        let report = calculator.generate_report_tex();
+        assert!(report.starts_with("\\documentclass"));
+        assert!(report.contains("附表一"));
+        assert!(report.ends_with("\\end{document}\n"));
+    }
+
+    #[test]
+    fn test_generate_report_html() {
+        let mut calculator = Calculator::new(CalcInputParameters::from_default());
+        calculator.calculate().unwrap();
+        let report = calculator.generate_report_html(&UnitSystem::default());
+        assert!(report.starts_with("<!DOCTYPE html>"));
+        assert_eq!(report.matches("<svg").count(), 2);
+        assert!(report.contains("附表二"));
     }
 }
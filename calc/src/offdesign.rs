@@ -0,0 +1,496 @@
+//! 变工况(部分负荷)计算
+//!
+//! 以输入参数的计算结果为设计工况，汽轮机通流部分几何不变：
+//! - 高、低压缸进排汽压力与第七级抽汽压力按斯托多拉锥体定律随流量变化，
+//!   `p² - p_z² = (G/G₀)²(p₀² - p_z₀²)`，忽略湿蒸汽区温度变化的影响；
+//! - 蒸汽发生器压力不变，调节阀节流至锥体定律给出的高压缸进口压力(节流调节)；
+//! - 高、低压缸内效率按 `η = η₀[1 - C(1 - G/G₀)²]` 修正；
+//! - 循环冷却水流量不变，冷却水温升与冷凝器端差正比于低压缸流量；
+//! - 最高一级高压加热器的抽汽压力决定给水温度的变化，其余各级抽汽压力(包括低压缸
+//!   各级)不按锥体定律计算，仍由热平衡计算按给水焓升分配确定，随凝结水温度与给水温度
+//!   的变化而降低；低负荷下与按各级组流量由锥体定律给出的压力有偏差，低压缸前几级
+//!   尤为明显。结果中列出热平衡实际采用的抽汽压力。
+//!
+//! 各负荷下迭代至高、低压缸流量比收敛。
+use seuif97::*;
+
+use crate::Calculator;
+use crate::export::csv::line;
+use crate::parameters::{CalcHESParameters, CalcInputParameters};
+use crate::units::{Unit, UnitSystem};
+
+/// 默认计算的负荷(占设计功率的比例)
+pub const DEFAULT_LOADS: [f64; 4] = [1.0, 0.75, 0.5, 0.3];
+/// 默认的内效率部分负荷修正系数C
+pub const DEFAULT_EFFICIENCY_COEFFICIENT: f64 = 0.2;
+/// 每个负荷的最大迭代次数
+pub const MAX_ITERATIONS: usize = 50;
+/// 流量比的收敛容差
+const TOLERANCE: f64 = 1e-5;
+
+/// 设计工况下汽轮机的流量与压力
+#[derive(Debug, Clone)]
+struct DesignPoint {
+    g_sh: f64,
+    g_sl: f64,
+    p_hi: f64,
+    p_hz: f64,
+    p_li: f64,
+    p_lz: f64,
+    /// 第七级抽汽压力
+    p_hes7: f64,
+    t_fw: f64,
+    t_fwop: f64,
+    eta_enpp: f64,
+    g_cd: f64,
+}
+
+impl DesignPoint {
+    fn new(calculator: &Calculator) -> Result<Self, String> {
+        let r1 = calculator
+            .results
+            .result1
+            .last()
+            .ok_or_else(|| String::from("计算结果为空"))?;
+        let r2 = &calculator.results.result2;
+        Ok(Self {
+            g_sh: r1.g_shp,
+            g_sl: r1.g_slp,
+            p_hi: r2.p_hi,
+            p_hz: r2.p_hz,
+            p_li: r2.p_li,
+            p_lz: r2.p_lz,
+            p_hes7: extraction(&r2.hhes, 1),
+            t_fw: r2.t_fw,
+            t_fwop: r2.t_fwop,
+            eta_enpp: r1.eta_enpp,
+            g_cd: r1.g_cd,
+        })
+    }
+}
+
+/// 按锥体定律由背压与流量比求级组前压力
+fn cone(p_z: f64, ratio: f64, p0: f64, p_z0: f64) -> f64 {
+    (p_z * p_z + ratio * ratio * (p0 * p0 - p_z0 * p_z0)).sqrt()
+}
+
+/// 热平衡结果中第`i`级抽汽压力，缺少该级时为NaN
+fn extraction(stages: &[CalcHESParameters], i: usize) -> f64 {
+    stages.get(i).map_or(f64::NAN, |stage| stage.p_hesx)
+}
+
+/// 锥体定律给出的汽轮机压力，低压缸各级抽汽压力由热平衡确定，不在其中
+#[derive(Debug, Clone, Copy)]
+struct Pressures {
+    p_hi: f64,
+    p_hz: f64,
+    p_hes7: f64,
+}
+
+impl Pressures {
+    /// `ratio`为高、低压缸流量比，`p_lz`为低压缸排汽压力
+    fn new(design: &DesignPoint, ratio: [f64; 2], p_lz: f64) -> Self {
+        let [ratio_h, ratio_l] = ratio;
+        let p_li = cone(p_lz, ratio_l, design.p_li, design.p_lz);
+        // 高压缸排汽至低压缸进口的压损比例不变
+        let p_hz = p_li * design.p_hz / design.p_li;
+        Self {
+            p_hi: cone(p_hz, ratio_h, design.p_hi, design.p_hz),
+            p_hz,
+            p_hes7: cone(p_hz, ratio_h, design.p_hes7, design.p_hz),
+        }
+    }
+}
+
+/// 一个负荷下的计算结果(内部单位)
+#[derive(Debug, Clone)]
+pub struct LoadPoint {
+    /// 负荷，占设计功率的比例
+    pub load: f64,
+    /// 电功率(MW)
+    pub ne: f64,
+    /// 高压缸流量比G_sh/G_sh0
+    pub ratio_h: f64,
+    /// 低压缸流量比G_sl/G_sl0
+    pub ratio_l: f64,
+    pub p_hi: f64,
+    pub p_hz: f64,
+    pub p_lz: f64,
+    /// 热平衡采用的第六、七级抽汽压力
+    pub p_hes: [f64; 2],
+    /// 热平衡采用的第一至四级抽汽压力
+    pub p_les: [f64; 4],
+    pub t_fw: f64,
+    pub eta_hi: f64,
+    pub eta_li: f64,
+    pub eta_enpp: f64,
+    /// 反应堆热功率(GW)
+    pub q_r: f64,
+    pub d_s: f64,
+    pub g_sh: f64,
+    pub g_sl: f64,
+    pub g_cd: f64,
+    /// 热耗率(kJ/kWh)
+    pub heat_rate: f64,
+    pub iterations: usize,
+    pub converged: bool,
+    /// 该负荷下使用的输入参数
+    pub params: CalcInputParameters,
+}
+
+/// 变工况结果表的一列
+pub struct Column {
+    pub label: &'static str,
+    pub symbol: &'static str,
+    pub unit: Unit,
+    pub value: fn(&LoadPoint) -> f64,
+}
+
+impl Column {
+    /// 表头，如`η_eNPP(%)`
+    pub fn header(&self, units: &UnitSystem) -> String {
+        match units.label(self.unit) {
+            "" => self.symbol.to_string(),
+            unit => format!("{}({unit})", self.symbol),
+        }
+    }
+
+    /// 按单位制格式化某一负荷下的数值，无量纲数保留4位小数
+    pub fn format(&self, point: &LoadPoint, units: &UnitSystem) -> String {
+        let value = (self.value)(point);
+        if self.unit == Unit::Dimensionless {
+            format!("{value:.4}")
+        } else {
+            units.format(self.unit, value)
+        }
+    }
+}
+
+const fn column(
+    label: &'static str,
+    symbol: &'static str,
+    unit: Unit,
+    value: fn(&LoadPoint) -> f64,
+) -> Column {
+    Column {
+        label,
+        symbol,
+        unit,
+        value,
+    }
+}
+
+/// 变工况结果表的各列，第一列为负荷
+pub const COLUMNS: &[Column] = &[
+    column("负荷", "N_e/N_e0", Unit::Fraction, |p| p.load),
+    column("电功率", "N_e", Unit::MW, |p| p.ne),
+    column(
+        "高压缸流量比",
+        "G_sh/G_sh0",
+        Unit::Dimensionless,
+        |p| p.ratio_h,
+    ),
+    column(
+        "低压缸流量比",
+        "G_sl/G_sl0",
+        Unit::Dimensionless,
+        |p| p.ratio_l,
+    ),
+    column("高压缸进口压力", "p_hi", Unit::MPa, |p| p.p_hi),
+    column("第七级抽汽压力", "p_hes7", Unit::MPa, |p| p.p_hes[1]),
+    column("第六级抽汽压力", "p_hes6", Unit::MPa, |p| p.p_hes[0]),
+    column("高压缸排汽压力", "p_hz", Unit::MPa, |p| p.p_hz),
+    column("第四级抽汽压力", "p_les4", Unit::MPa, |p| p.p_les[3]),
+    column("第三级抽汽压力", "p_les3", Unit::MPa, |p| p.p_les[2]),
+    column("第二级抽汽压力", "p_les2", Unit::MPa, |p| p.p_les[1]),
+    column("第一级抽汽压力", "p_les1", Unit::MPa, |p| p.p_les[0]),
+    column("低压缸排汽压力", "p_lz", Unit::MPa, |p| p.p_lz),
+    column("给水温度", "T_fw", Unit::Celsius, |p| p.t_fw),
+    column("高压缸内效率", "η_hi", Unit::Fraction, |p| p.eta_hi),
+    column("低压缸内效率", "η_li", Unit::Fraction, |p| p.eta_li),
+    column("核电厂效率", "η_eNPP", Unit::Fraction, |p| p.eta_enpp),
+    column(
+        "热耗率(kJ/kWh)",
+        "HR(kJ/kWh)",
+        Unit::Dimensionless,
+        |p| p.heat_rate,
+    ),
+    column("反应堆热功率", "Q_R", Unit::GW, |p| p.q_r),
+    column("蒸汽产量", "D_s", Unit::KgPerS, |p| p.d_s),
+    column("高压缸耗汽量", "G_sh", Unit::KgPerS, |p| p.g_sh),
+    column("低压缸耗汽量", "G_sl", Unit::KgPerS, |p| p.g_sl),
+    column("凝结水流量", "G_cd", Unit::KgPerS, |p| p.g_cd),
+];
+
+/// 变工况计算结果
+#[derive(Debug, Clone, Default)]
+pub struct OffDesignReport {
+    /// 内效率部分负荷修正系数
+    pub coefficient: f64,
+    /// 按负荷从高到低排列
+    pub points: Vec<LoadPoint>,
+    /// 计算失败的负荷及原因
+    pub failures: Vec<(f64, String)>,
+}
+
+/// 按部分负荷修正内效率
+fn corrected_efficiency(eta: f64, ratio: f64, coefficient: f64) -> f64 {
+    eta * (1.0 - coefficient * (1.0 - ratio).powi(2))
+}
+
+/// 由锥体定律给出的压力与流量比构造部分负荷的输入参数
+fn part_load_params(
+    params: &CalcInputParameters,
+    design: &DesignPoint,
+    load: f64,
+    ratio: [f64; 2],
+    pressures: &Pressures,
+    t_fwop: f64,
+    coefficient: f64,
+) -> CalcInputParameters {
+    let [ratio_h, ratio_l] = ratio;
+    // 最高一级加热器抽汽饱和温度的变化即给水温度的变化
+    let t_sat = |p: f64| px(p * (1.0 - params.dp_ej), 1.0, OT);
+    let t_fw = design.t_fw + t_sat(pressures.p_hes7) - t_sat(design.p_hes7);
+    CalcInputParameters {
+        ne: load * params.ne,
+        n_hi: corrected_efficiency(params.n_hi, ratio_h, coefficient),
+        n_li: corrected_efficiency(params.n_li, ratio_l, coefficient),
+        dp_fh: 1.0 - pressures.p_hi / params.p_s,
+        dp_hz: pressures.p_hz / pressures.p_hi,
+        dt_fw: t_fw / t_fwop,
+        dt_sw: params.dt_sw * ratio_l,
+        dt: params.dt * ratio_l,
+        ne_npp: design.eta_enpp,
+        g_cd: design.g_cd * ratio_l,
+        ..params.clone()
+    }
+}
+
+/// 计算一个负荷，迭代至高、低压缸流量比收敛
+fn solve_load(
+    params: &CalcInputParameters,
+    design: &DesignPoint,
+    load: f64,
+    coefficient: f64,
+) -> Result<LoadPoint, String> {
+    let mut ratio = [load, load];
+    let (mut p_lz, mut t_fwop) = (design.p_lz, design.t_fwop);
+    for iteration in 1..=MAX_ITERATIONS {
+        let pressures = Pressures::new(design, ratio, p_lz);
+        if pressures.p_hi >= params.p_s {
+            return Err(String::from("高压缸进口压力超过蒸汽发生器压力"));
+        }
+        let part = part_load_params(params, design, load, ratio, &pressures, t_fwop, coefficient);
+        let calculator = Calculator::solve(part.clone())?;
+        let r1 = calculator
+            .results
+            .result1
+            .last()
+            .ok_or_else(|| String::from("计算结果为空"))?;
+        let r2 = &calculator.results.result2;
+        let next = [r1.g_shp / design.g_sh, r1.g_slp / design.g_sl];
+        if !next.iter().all(|ratio| ratio.is_finite() && *ratio > 0.0) {
+            return Err(String::from("流量比不是正的有限值"));
+        }
+        let converged = next
+            .iter()
+            .zip(ratio)
+            .all(|(next, ratio)| (next - ratio).abs() < TOLERANCE);
+        if converged || iteration == MAX_ITERATIONS {
+            return Ok(LoadPoint {
+                load,
+                ne: part.ne,
+                ratio_h: ratio[0],
+                ratio_l: ratio[1],
+                p_hi: r2.p_hi,
+                p_hz: r2.p_hz,
+                p_lz: r2.p_lz,
+                p_hes: [0, 1].map(|i| extraction(&r2.hhes, i)),
+                p_les: [0, 1, 2, 3].map(|i| extraction(&r2.lhes, i)),
+                t_fw: r2.t_fw,
+                eta_hi: part.n_hi,
+                eta_li: part.n_li,
+                eta_enpp: r1.eta_enpp,
+                q_r: r1.q_r,
+                d_s: r1.d_s,
+                g_sh: r1.g_shp,
+                g_sl: r1.g_slp,
+                g_cd: r1.g_cd,
+                heat_rate: 3600.0 * r1.q_r * 1000.0 / part.ne,
+                iterations: iteration,
+                converged,
+                params: part,
+            });
+        }
+        ratio = next;
+        (p_lz, t_fwop) = (r2.p_lz, r2.t_fwop);
+    }
+    unreachable!("最后一次迭代总会返回")
+}
+
+/// 以输入参数为设计工况，计算各负荷下的性能
+///
+/// # Arguments
+///
+/// * `loads` - 负荷，占设计功率的比例，应在(0, 1]内
+///
+/// * `coefficient` - 内效率部分负荷修正系数C
+pub fn off_design(
+    params: &CalcInputParameters,
+    loads: &[f64],
+    coefficient: f64,
+) -> Result<OffDesignReport, String> {
+    if loads.is_empty() {
+        return Err(String::from("未指定负荷"));
+    }
+    if let Some(load) = loads.iter().find(|load| !(**load > 0.0 && **load <= 1.0)) {
+        return Err(format!("负荷{load}不在(0, 1]内"));
+    }
+    if !(0.0..1.0).contains(&coefficient) {
+        return Err(String::from("内效率修正系数应在[0, 1)内"));
+    }
+    let calculator =
+        Calculator::solve(params.clone()).map_err(|error| format!("设计工况计算失败: {error}"))?;
+    let design = DesignPoint::new(&calculator)?;
+
+    let mut loads = loads.to_vec();
+    loads.sort_by(|a, b| b.total_cmp(a));
+    loads.dedup();
+    let mut report = OffDesignReport {
+        coefficient,
+        ..OffDesignReport::default()
+    };
+    for load in loads {
+        match solve_load(params, &design, load, coefficient) {
+            Ok(point) => report.points.push(point),
+            Err(error) => report.failures.push((load, error)),
+        }
+    }
+    Ok(report)
+}
+
+impl OffDesignReport {
+    fn cells(point: &LoadPoint, units: &UnitSystem) -> Vec<String> {
+        COLUMNS
+            .iter()
+            .map(|column| column.format(point, units))
+            .collect()
+    }
+
+    /// 生成Markdown格式的报告
+    pub fn to_markdown(&self, units: &UnitSystem) -> String {
+        let table_row = |cells: &[String]| format!("| {} |\n", cells.join(" | "));
+        let mut markdown = String::from("# 变工况计算\n\n");
+        markdown.push_str(&format!(
+            "斯托多拉锥体定律确定汽缸进排汽压力与给水温度，其余抽汽压力按给水焓升分配确定，\
+             节流调节，内效率修正系数 C = {}。\n\n",
+            self.coefficient
+        ));
+        for (load, error) in &self.failures {
+            markdown.push_str(&format!("- 负荷 {:.1}% 计算失败: {error}\n", load * 100.0));
+        }
+        for point in self.points.iter().filter(|point| !point.converged) {
+            markdown.push_str(&format!(
+                "- 负荷 {:.1}% 迭代{}次流量比仍未收敛\n",
+                point.load * 100.0,
+                point.iterations
+            ));
+        }
+        if !self.failures.is_empty() || self.points.iter().any(|point| !point.converged) {
+            markdown.push('\n');
+        }
+
+        let headers: Vec<String> = COLUMNS.iter().map(|column| column.header(units)).collect();
+        markdown.push_str(&table_row(&headers));
+        markdown.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+        for point in &self.points {
+            markdown.push_str(&table_row(&Self::cells(point, units)));
+        }
+        markdown
+    }
+
+    /// 生成CSV/TSV格式的结果表
+    pub fn to_csv(&self, delimiter: char, units: &UnitSystem) -> String {
+        let headers: Vec<String> = COLUMNS.iter().map(|column| column.header(units)).collect();
+        let mut content = line(&headers, delimiter);
+        for point in &self.points {
+            content.push_str(&line(&Self::cells(point, units), delimiter));
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cone() {
+        assert!((cone(0.01, 1.0, 1.0, 0.01) - 1.0).abs() < 1e-12);
+        // 背压很低时压力近似正比于流量
+        assert!((cone(0.0, 0.5, 1.0, 0.0) - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_off_design() {
+        let params = CalcInputParameters::from_default();
+        let report = off_design(&params, &DEFAULT_LOADS, DEFAULT_EFFICIENCY_COEFFICIENT).unwrap();
+        assert!(report.failures.is_empty(), "{:?}", report.failures);
+        assert_eq!(report.points.len(), DEFAULT_LOADS.len());
+        assert!(report.points.iter().all(|point| point.converged));
+
+        // 满负荷即设计工况
+        let design = crate::Calculator::evaluate(params.clone()).unwrap();
+        let full = &report.points[0];
+        assert_eq!(full.load, 1.0);
+        assert!((full.eta_enpp - design.eta_enpp).abs() < 1e-6);
+        assert!((full.ratio_h - 1.0).abs() < 1e-4);
+
+        // 结果中的压力即热平衡实际采用的压力
+        for point in &report.points {
+            let r2 = Calculator::solve(point.params.clone())
+                .unwrap()
+                .results
+                .result2;
+            assert_eq!(point.p_hi, r2.p_hi);
+            assert_eq!(point.p_hz, r2.p_hz);
+            assert_eq!(point.p_lz, r2.p_lz);
+            for (p, stage) in point.p_hes.iter().zip(&r2.hhes) {
+                assert_eq!(*p, stage.p_hesx);
+            }
+            for (p, stage) in point.p_les.iter().zip(&r2.lhes) {
+                assert_eq!(*p, stage.p_hesx);
+            }
+        }
+
+        // 负荷降低时压力、给水温度与效率均下降
+        for pair in report.points.windows(2) {
+            let (high, low) = (&pair[0], &pair[1]);
+            assert!(low.p_hz < high.p_hz);
+            assert!(low.p_hes[1] < high.p_hes[1]);
+            // 低压缸各级抽汽压力不按锥体定律计算，但仍随热平衡中的凝结水与给水温度降低
+            for (low, high) in low.p_les.iter().zip(&high.p_les) {
+                assert!(low < high);
+            }
+            assert!(low.t_fw < high.t_fw);
+            assert!(low.eta_enpp < high.eta_enpp);
+            assert!(low.d_s < high.d_s);
+        }
+
+        let units = UnitSystem::default();
+        assert_eq!(
+            report.to_csv(',', &units).lines().count(),
+            1 + DEFAULT_LOADS.len()
+        );
+        assert!(report.to_markdown(&units).contains("| --- |"));
+    }
+
+    #[test]
+    fn test_invalid_loads() {
+        let params = CalcInputParameters::from_default();
+        assert!(off_design(&params, &[], 0.2).is_err());
+        assert!(off_design(&params, &[1.2], 0.2).is_err());
+        assert!(off_design(&params, &[0.5], 1.5).is_err());
+    }
+}
@@ -0,0 +1,505 @@
+//! 设计参数优化
+//!
+//! 在用户给定上下限的若干输入参数上最大化核电厂效率`eta_enpp`或最小化热耗率。
+//! 优化变量归一化到[0, 1]后求解：单个变量用黄金分割法，多个变量用 Nelder–Mead 单纯形法，
+//! 均不需要导数。越界的点投影回边界，计算失败的点视为最差。
+use std::fmt::Display;
+
+use crate::Calculator;
+use crate::export::csv::line;
+use crate::fields::{
+    FieldMeta, INPUT_FIELDS, InputKind, field_map, field_value, input_field, with_input_values,
+};
+use crate::parameters::CalcInputParameters;
+use crate::units::UnitSystem;
+
+/// 最大迭代次数
+pub const MAX_ITERATIONS: usize = 200;
+/// 归一化变量的收敛容差
+const TOLERANCE: f64 = 1e-4;
+/// 最优点与边界的归一化距离小于该值时视为边界起作用
+const BOUND_TOLERANCE: f64 = 1e-3;
+/// 默认的优化变量
+pub const DEFAULT_VARIABLES: [&str; 3] = ["p_s", "dp_hz", "dt_fw"];
+
+/// 优化目标
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Objective {
+    /// 最大化核电厂效率
+    #[default]
+    Efficiency,
+    /// 最小化热耗率
+    HeatRate,
+}
+
+impl Objective {
+    pub const ALL: [Self; 2] = [Self::Efficiency, Self::HeatRate];
+}
+
+impl Display for Objective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Efficiency => "最大化核电厂效率",
+            Self::HeatRate => "最小化热耗率",
+        })
+    }
+}
+
+/// 优化方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    GoldenSection,
+    NelderMead,
+}
+
+impl Method {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::GoldenSection => "黄金分割法",
+            Self::NelderMead => "Nelder–Mead单纯形法",
+        }
+    }
+}
+
+/// 可作为优化变量的输入参数：有推荐范围的连续参数
+pub fn candidate_fields() -> impl Iterator<Item = &'static FieldMeta> {
+    INPUT_FIELDS
+        .iter()
+        .filter(|field| field.range.is_some() && field.kind == InputKind::Continuous)
+}
+
+/// 优化变量及其上下限(内部单位)
+#[derive(Debug, Clone, Copy)]
+pub struct DesignVariable {
+    pub field: &'static FieldMeta,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl DesignVariable {
+    /// 以推荐范围为上下限
+    pub fn new(key: &str) -> Option<Self> {
+        let field = input_field(key)?;
+        let (lower, upper) = field.range?;
+        Some(Self {
+            field,
+            lower,
+            upper,
+        })
+    }
+
+    fn to_value(self, u: f64) -> f64 {
+        self.lower + u.clamp(0.0, 1.0) * (self.upper - self.lower)
+    }
+
+    fn to_unit(self, value: f64) -> f64 {
+        ((value - self.lower) / (self.upper - self.lower)).clamp(0.0, 1.0)
+    }
+}
+
+/// 起作用的边界
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Lower,
+    Upper,
+}
+
+impl Bound {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Lower => "下限",
+            Self::Upper => "上限",
+        }
+    }
+}
+
+/// 迭代过程中的一步，记录当前最优点
+#[derive(Debug, Clone)]
+pub struct TracePoint {
+    pub iteration: usize,
+    /// 各优化变量的数值(内部单位)
+    pub values: Vec<f64>,
+    pub eta_enpp: f64,
+    /// 热耗率(kJ/kWh)
+    pub heat_rate: f64,
+}
+
+/// 优化结果
+#[derive(Debug, Clone)]
+pub struct Optimum {
+    pub objective: Objective,
+    pub method: Method,
+    pub variables: Vec<DesignVariable>,
+    /// 最优点各变量的数值(内部单位)
+    pub values: Vec<f64>,
+    /// 代入最优点后的输入参数
+    pub params: CalcInputParameters,
+    pub eta_enpp: f64,
+    /// 热耗率(kJ/kWh)
+    pub heat_rate: f64,
+    /// 优化前的效率
+    pub initial_eta_enpp: f64,
+    pub trace: Vec<TracePoint>,
+    /// 调用计算的次数
+    pub evaluations: usize,
+    /// 是否在最大迭代次数内收敛
+    pub converged: bool,
+}
+
+/// 一次计算的效率与热耗率
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    eta_enpp: f64,
+    heat_rate: f64,
+}
+
+/// 目标函数：按归一化变量计算，越小越好，计算失败时为正无穷
+struct Problem<'a> {
+    params: &'a CalcInputParameters,
+    variables: &'a [DesignVariable],
+    objective: Objective,
+    evaluations: usize,
+}
+
+impl Problem<'_> {
+    fn values(&self, u: &[f64]) -> Vec<f64> {
+        self.variables
+            .iter()
+            .zip(u)
+            .map(|(variable, &u)| variable.to_value(u))
+            .collect()
+    }
+
+    fn point(&mut self, u: &[f64]) -> Option<Point> {
+        self.evaluations += 1;
+        let values: Vec<_> = self
+            .variables
+            .iter()
+            .zip(self.values(u))
+            .map(|(variable, value)| (variable.field.key, value))
+            .collect();
+        let outputs = with_input_values(self.params, &values)
+            .and_then(Calculator::evaluate)
+            .ok()?;
+        // Q_R为GW，N_e为MW
+        let heat_rate = 3600.0 * outputs.q_r * 1000.0 / self.params.ne;
+        Some(Point {
+            eta_enpp: outputs.eta_enpp,
+            heat_rate,
+        })
+        .filter(|point| point.eta_enpp > 0.0 && point.heat_rate.is_finite())
+    }
+
+    fn cost(&mut self, u: &[f64]) -> f64 {
+        match (self.point(u), self.objective) {
+            (Some(point), Objective::Efficiency) => -point.eta_enpp,
+            (Some(point), Objective::HeatRate) => point.heat_rate,
+            (None, _) => f64::INFINITY,
+        }
+    }
+
+    fn trace(&mut self, iteration: usize, u: &[f64]) -> Option<TracePoint> {
+        let point = self.point(u)?;
+        Some(TracePoint {
+            iteration,
+            values: self.values(u),
+            eta_enpp: point.eta_enpp,
+            heat_rate: point.heat_rate,
+        })
+    }
+}
+
+/// 黄金分割法，返回最优点及是否收敛
+fn golden_section(problem: &mut Problem, trace: &mut Vec<TracePoint>) -> (Vec<f64>, bool) {
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut a, mut b) = (0.0, 1.0);
+    let mut c = b - ratio * (b - a);
+    let mut d = a + ratio * (b - a);
+    let mut fc = problem.cost(&[c]);
+    let mut fd = problem.cost(&[d]);
+    for iteration in 1..=MAX_ITERATIONS {
+        if fc <= fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - ratio * (b - a);
+            fc = problem.cost(&[c]);
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + ratio * (b - a);
+            fd = problem.cost(&[d]);
+        }
+        let best = if fc <= fd { c } else { d };
+        trace.extend(problem.trace(iteration, &[best]));
+        if b - a < TOLERANCE {
+            return (vec![best], true);
+        }
+    }
+    (vec![if fc <= fd { c } else { d }], false)
+}
+
+/// Nelder–Mead单纯形法，返回最优点及是否收敛
+fn nelder_mead(
+    problem: &mut Problem,
+    start: Vec<f64>,
+    trace: &mut Vec<TracePoint>,
+) -> (Vec<f64>, bool) {
+    let n = start.len();
+    let clamp = |u: Vec<f64>| u.into_iter().map(|u| u.clamp(0.0, 1.0)).collect::<Vec<_>>();
+    // 初始单纯形：沿各坐标方向偏移，靠近上限时向下偏移
+    let mut simplex = vec![start.clone()];
+    for i in 0..n {
+        let mut vertex = start.clone();
+        vertex[i] += if vertex[i] + 0.25 <= 1.0 { 0.25 } else { -0.25 };
+        simplex.push(vertex);
+    }
+    let mut costs: Vec<f64> = simplex.iter().map(|u| problem.cost(u)).collect();
+
+    for iteration in 1..=MAX_ITERATIONS {
+        let mut order: Vec<usize> = (0..=n).collect();
+        order.sort_by(|&a, &b| costs[a].total_cmp(&costs[b]));
+        simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+        costs = order.iter().map(|&i| costs[i]).collect();
+        trace.extend(problem.trace(iteration, &simplex[0]));
+
+        let size = simplex[1..]
+            .iter()
+            .flat_map(|vertex| vertex.iter().zip(&simplex[0]).map(|(a, b)| (a - b).abs()))
+            .fold(0.0, f64::max);
+        if size < TOLERANCE {
+            return (simplex.swap_remove(0), true);
+        }
+
+        // 除最差点外各点的形心
+        let centroid: Vec<f64> = (0..n)
+            .map(|j| simplex[..n].iter().map(|vertex| vertex[j]).sum::<f64>() / n as f64)
+            .collect();
+        let towards = |scale: f64, vertex: &[f64]| {
+            clamp(
+                centroid
+                    .iter()
+                    .zip(vertex)
+                    .map(|(c, v)| c + scale * (v - c))
+                    .collect(),
+            )
+        };
+        let worst = simplex[n].clone();
+        let reflected = towards(-1.0, &worst);
+        let f_reflected = problem.cost(&reflected);
+        if f_reflected < costs[0] {
+            let expanded = towards(-2.0, &worst);
+            let f_expanded = problem.cost(&expanded);
+            if f_expanded < f_reflected {
+                (simplex[n], costs[n]) = (expanded, f_expanded);
+            } else {
+                (simplex[n], costs[n]) = (reflected, f_reflected);
+            }
+        } else if f_reflected < costs[n - 1] {
+            (simplex[n], costs[n]) = (reflected, f_reflected);
+        } else {
+            let contracted = if f_reflected < costs[n] {
+                towards(-0.5, &worst)
+            } else {
+                towards(0.5, &worst)
+            };
+            let f_contracted = problem.cost(&contracted);
+            if f_contracted < costs[n].min(f_reflected) {
+                (simplex[n], costs[n]) = (contracted, f_contracted);
+            } else {
+                // 向最优点收缩
+                let best = simplex[0].clone();
+                for i in 1..=n {
+                    simplex[i] = best
+                        .iter()
+                        .zip(&simplex[i])
+                        .map(|(b, v)| b + 0.5 * (v - b))
+                        .collect();
+                    costs[i] = problem.cost(&simplex[i]);
+                }
+            }
+        }
+    }
+    let best = (0..=n)
+        .min_by(|&a, &b| costs[a].total_cmp(&costs[b]))
+        .unwrap_or(0);
+    (simplex.swap_remove(best), false)
+}
+
+/// 在给定上下限内优化输入参数，以当前参数为初始点
+pub fn optimize(
+    params: &CalcInputParameters,
+    variables: &[DesignVariable],
+    objective: Objective,
+) -> Result<Optimum, String> {
+    if variables.is_empty() {
+        return Err(String::from("未选择优化变量"));
+    }
+    for variable in variables {
+        if variable.lower.partial_cmp(&variable.upper) != Some(std::cmp::Ordering::Less) {
+            return Err(format!(
+                "{}({})的下限应小于上限",
+                variable.field.label, variable.field.key
+            ));
+        }
+    }
+    let initial = Calculator::evaluate(params.clone())
+        .map_err(|error| format!("初始参数计算失败: {error}"))?;
+
+    let values = field_map(params);
+    let start: Vec<f64> = variables
+        .iter()
+        .map(|variable| variable.to_unit(field_value(&values, variable.field.key)))
+        .map(|u| if u.is_nan() { 0.5 } else { u })
+        .collect();
+    let mut problem = Problem {
+        params,
+        variables,
+        objective,
+        evaluations: 0,
+    };
+    let mut trace = Vec::new();
+    let (method, (best, converged)) = if variables.len() == 1 {
+        (
+            Method::GoldenSection,
+            golden_section(&mut problem, &mut trace),
+        )
+    } else {
+        (
+            Method::NelderMead,
+            nelder_mead(&mut problem, start, &mut trace),
+        )
+    };
+    let point = problem
+        .point(&best)
+        .ok_or_else(|| String::from("在给定范围内没有可以完成计算的参数组合"))?;
+    let values = problem.values(&best);
+    let pairs: Vec<_> = variables
+        .iter()
+        .zip(&values)
+        .map(|(variable, &value)| (variable.field.key, value))
+        .collect();
+
+    Ok(Optimum {
+        objective,
+        method,
+        variables: variables.to_vec(),
+        params: with_input_values(params, &pairs)?,
+        values,
+        eta_enpp: point.eta_enpp,
+        heat_rate: point.heat_rate,
+        initial_eta_enpp: initial.eta_enpp,
+        trace,
+        evaluations: problem.evaluations,
+        converged,
+    })
+}
+
+impl Optimum {
+    /// 最优点处起作用的边界
+    pub fn active_bounds(&self) -> Vec<(&DesignVariable, Bound)> {
+        self.variables
+            .iter()
+            .zip(&self.values)
+            .filter_map(|(variable, &value)| {
+                let u = variable.to_unit(value);
+                if u < BOUND_TOLERANCE {
+                    Some((variable, Bound::Lower))
+                } else if u > 1.0 - BOUND_TOLERANCE {
+                    Some((variable, Bound::Upper))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// 生成CSV/TSV格式的迭代过程
+    pub fn trace_to_csv(&self, delimiter: char, units: &UnitSystem) -> String {
+        let mut headers = vec![String::from("迭代")];
+        headers.extend(
+            self.variables
+                .iter()
+                .map(|variable| variable.field.label_with_unit(units)),
+        );
+        headers.extend([String::from("η_eNPP"), String::from("热耗率(kJ/kWh)")]);
+        let mut content = line(&headers, delimiter);
+        for point in &self.trace {
+            let mut cells = vec![point.iteration.to_string()];
+            cells.extend(
+                self.variables
+                    .iter()
+                    .zip(&point.values)
+                    .map(|(variable, &value)| units.format(variable.field.unit, value)),
+            );
+            cells.extend([
+                format!("{:.6}", point.eta_enpp),
+                format!("{:.2}", point.heat_rate),
+            ]);
+            content.push_str(&line(&cells, delimiter));
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_golden_section() {
+        let params = CalcInputParameters::from_default();
+        let variables = [DesignVariable::new("dp_hz").unwrap()];
+        let optimum = optimize(&params, &variables, Objective::Efficiency).unwrap();
+        assert_eq!(optimum.method, Method::GoldenSection);
+        assert!(optimum.converged);
+        let (lower, upper) = (variables[0].lower, variables[0].upper);
+        assert!((lower..=upper).contains(&optimum.values[0]));
+        assert!(optimum.eta_enpp >= optimum.initial_eta_enpp - 1e-6);
+        assert_eq!(optimum.params.dp_hz, optimum.values[0]);
+    }
+
+    #[test]
+    fn test_nelder_mead() {
+        let params = CalcInputParameters::from_default();
+        let variables: Vec<_> = DEFAULT_VARIABLES
+            .iter()
+            .filter_map(|key| DesignVariable::new(key))
+            .collect();
+        let optimum = optimize(&params, &variables, Objective::HeatRate).unwrap();
+        assert_eq!(optimum.method, Method::NelderMead);
+        assert!(!optimum.trace.is_empty());
+        assert!(optimum.eta_enpp >= optimum.initial_eta_enpp - 1e-6);
+        // 迭代过程中的最优点不会变差
+        assert!(
+            optimum
+                .trace
+                .windows(2)
+                .all(|w| w[1].heat_rate <= w[0].heat_rate + 1e-9)
+        );
+        for (variable, bound) in optimum.active_bounds() {
+            let value = optimum.values[variables
+                .iter()
+                .position(|v| v.field.key == variable.field.key)
+                .unwrap()];
+            let limit = match bound {
+                Bound::Lower => variable.lower,
+                Bound::Upper => variable.upper,
+            };
+            assert!((value - limit).abs() <= BOUND_TOLERANCE * (variable.upper - variable.lower));
+        }
+        let csv = optimum.trace_to_csv(',', &UnitSystem::default());
+        assert_eq!(csv.lines().count(), optimum.trace.len() + 1);
+    }
+
+    #[test]
+    fn test_invalid_variables() {
+        let params = CalcInputParameters::from_default();
+        assert!(optimize(&params, &[], Objective::Efficiency).is_err());
+        let mut variable = DesignVariable::new("p_s").unwrap();
+        variable.upper = variable.lower;
+        assert!(optimize(&params, &[variable], Objective::Efficiency).is_err());
+        assert!(candidate_fields().all(|field| field.key != "ne_npp"));
+    }
+}
@@ -3,6 +3,7 @@ use std::fmt::Display;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default = "CalcInputParameters::from_default")]
 pub struct CalcInputParameters {
     // 已知条件和给定参数
     /// 核电厂输出电功率, 给定，1000(MW)
@@ -27,7 +28,7 @@ pub struct CalcInputParameters {
     pub dp_rh: f64,
     /// 回热抽汽压损，(3% ~ 5%)P_cj(MPa)
     pub dp_ej: f64,
-    /// 低压缸排汽压损，给定，5%P_cd(kPa)
+    /// 低压缸排汽压损，占P_cd的比例，给定，5%
     pub dp_cd: f64,
     /// 流动损失（%入口压力），默认1
     pub dp_f: f64,
@@ -80,6 +81,99 @@ pub struct CalcInputParameters {
     pub dp_fwpo: f64,
     /// 凝水泵出口压力(x倍除氧器运行压力，MPa)，3 ~ 3.2
     pub dp_cwp: f64,
+    // 一回路冷却剂系统与蒸汽发生器热力设计
+    /// 一回路环路数(蒸汽发生器、主泵台数)，3
+    pub n_sg: f64,
+    /// 主泵扬程，约100(m)
+    pub h_rcp: f64,
+    /// 主泵效率，0.75 ~ 0.85
+    pub n_rcp: f64,
+    /// 主泵轴功率计入一回路热平衡的份额，0表示不计入
+    pub k_rcp: f64,
+    // 给水泵驱动方式
+    /// 给水泵驱动方式，0为电动，1为汽动
+    pub fwp_drive: f64,
+    /// 给水泵汽轮机汽源，0为新蒸汽，1为再热蒸汽
+    pub fwpt_source: f64,
+    /// 给水泵汽轮机排汽去向，0为冷凝器，1为除氧器
+    pub fwpt_exhaust: f64,
+    /// 给水泵汽轮机实际焓降，0为按高压缸焓降估算，1为按进排汽压力计算
+    pub fwpt_expansion: f64,
+    /// 给水泵电动机效率，0.95
+    pub n_fwpm: f64,
+    // 给水加热器疏水方式，0为逐级自流，1为疏水泵打入下游，2为疏水冷却器
+    /// 第一级低压加热器疏水方式
+    pub drain_1: f64,
+    /// 第二级低压加热器疏水方式
+    pub drain_2: f64,
+    /// 第三级低压加热器疏水方式
+    pub drain_3: f64,
+    /// 第四级低压加热器疏水方式
+    pub drain_4: f64,
+    /// 第六级高压加热器疏水方式
+    pub drain_6: f64,
+    /// 第七级高压加热器疏水方式
+    pub drain_7: f64,
+    /// 疏水冷却器端差，5 ~ 10(℃)
+    pub theta_dc: f64,
+    // 各级给水加热器端差(℃)，未给定时取全局值
+    /// 第一级低压加热器出口端差
+    pub theta_u1: Option<f64>,
+    /// 第二级低压加热器出口端差
+    pub theta_u2: Option<f64>,
+    /// 第三级低压加热器出口端差
+    pub theta_u3: Option<f64>,
+    /// 第四级低压加热器出口端差
+    pub theta_u4: Option<f64>,
+    /// 第六级高压加热器出口端差
+    pub theta_u6: Option<f64>,
+    /// 第七级高压加热器出口端差
+    pub theta_u7: Option<f64>,
+    /// 第一级低压加热器疏水冷却器端差
+    pub theta_dc1: Option<f64>,
+    /// 第二级低压加热器疏水冷却器端差
+    pub theta_dc2: Option<f64>,
+    /// 第三级低压加热器疏水冷却器端差
+    pub theta_dc3: Option<f64>,
+    /// 第四级低压加热器疏水冷却器端差
+    pub theta_dc4: Option<f64>,
+    /// 第六级高压加热器疏水冷却器端差
+    pub theta_dc6: Option<f64>,
+    /// 第七级高压加热器疏水冷却器端差
+    pub theta_dc7: Option<f64>,
+    /// 给水焓升分配方式，0为等焓升，1为等温升，2为几何分配，3为自定义
+    pub fw_alloc: f64,
+    /// 几何分配时相邻两级给水焓升之比
+    pub q_fw: f64,
+    // 自定义分配时各级给水焓升的权重，在低压侧与高压侧内分别归一化
+    /// 第一级低压加热器给水焓升权重
+    pub w_fw1: f64,
+    /// 第二级低压加热器给水焓升权重
+    pub w_fw2: f64,
+    /// 第三级低压加热器给水焓升权重
+    pub w_fw3: f64,
+    /// 第四级低压加热器给水焓升权重
+    pub w_fw4: f64,
+    /// 除氧器给水焓升权重
+    pub w_fwd: f64,
+    /// 第六级高压加热器给水焓升权重
+    pub w_fw6: f64,
+    /// 第七级高压加热器给水焓升权重
+    pub w_fw7: f64,
+    /// 蒸汽发生器总传热系数(kW/(m²·K))，0表示按管束几何计算
+    pub k_sg: f64,
+    /// 传热管外径，19.05(mm)
+    pub d_sgo: f64,
+    /// 传热管壁厚，1.09(mm)
+    pub delta_sg: f64,
+    /// 传热管平均长度，20.3(m)
+    pub l_sg: f64,
+    /// 传热管导热系数，因科镍690合金约17(W/(m·K))
+    pub lambda_sg: f64,
+    /// 二次侧沸腾换热系数，25 ~ 50(kW/(m²·K))
+    pub alpha_2: f64,
+    /// 污垢热阻(m²·K/kW)
+    pub r_sg: f64,
 }
 
 impl Display for CalcInputParameters {
@@ -128,11 +222,90 @@ impl CalcInputParameters {
             theta_lu: 2.0,
             x_fh: 99.75 / 100.0,
             zeta_d: 1.05 / 100.0,
+            n_sg: 3.0,
+            h_rcp: 100.0,
+            n_rcp: 80.0 / 100.0,
+            k_rcp: 0.0,
+            fwp_drive: 1.0,
+            fwpt_source: 0.0,
+            fwpt_exhaust: 0.0,
+            fwpt_expansion: 0.0,
+            n_fwpm: 95.0 / 100.0,
+            drain_1: 0.0,
+            drain_2: 0.0,
+            drain_3: 0.0,
+            drain_4: 0.0,
+            drain_6: 0.0,
+            drain_7: 0.0,
+            theta_dc: 5.6,
+            theta_u1: None,
+            theta_u2: None,
+            theta_u3: None,
+            theta_u4: None,
+            theta_u6: None,
+            theta_u7: None,
+            theta_dc1: None,
+            theta_dc2: None,
+            theta_dc3: None,
+            theta_dc4: None,
+            theta_dc6: None,
+            theta_dc7: None,
+            fw_alloc: 0.0,
+            q_fw: 1.1,
+            w_fw1: 1.0,
+            w_fw2: 1.0,
+            w_fw3: 1.0,
+            w_fw4: 1.0,
+            w_fwd: 1.0,
+            w_fw6: 1.0,
+            w_fw7: 1.0,
+            k_sg: 0.0,
+            d_sgo: 19.05,
+            delta_sg: 1.09,
+            l_sg: 20.3,
+            lambda_sg: 17.0,
+            alpha_2: 35.0,
+            r_sg: 0.03,
         }
     }
+
+    /// 第`stage`级给水加热器出口端差(℃)，未单独给定时取高压或低压加热器的全局值
+    ///
+    /// 级号与抽汽编号一致：1 ~ 4为低压加热器，6、7为高压加热器
+    pub fn heater_theta_u(&self, stage: usize) -> f64 {
+        let global = if stage > 5 {
+            self.theta_hu
+        } else {
+            self.theta_lu
+        };
+        let theta = match stage {
+            1 => self.theta_u1,
+            2 => self.theta_u2,
+            3 => self.theta_u3,
+            4 => self.theta_u4,
+            6 => self.theta_u6,
+            7 => self.theta_u7,
+            _ => None,
+        };
+        theta.unwrap_or(global)
+    }
+
+    /// 第`stage`级疏水冷却器端差(℃)，未单独给定时取全局值
+    pub fn heater_theta_dc(&self, stage: usize) -> f64 {
+        let theta = match stage {
+            1 => self.theta_dc1,
+            2 => self.theta_dc2,
+            3 => self.theta_dc3,
+            4 => self.theta_dc4,
+            6 => self.theta_dc6,
+            7 => self.theta_dc7,
+            _ => None,
+        };
+        theta.unwrap_or(self.theta_dc)
+    }
 }
 
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CalcResultParamters {
     /// 热平衡计算结果
     pub result1: Vec<CalcResult1>,
@@ -140,11 +313,11 @@ pub struct CalcResultParamters {
     pub result2: CalcResult2,
 }
 
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CalcResult1 {
     /// 1.核电厂效率η_eNPP
     pub eta_enpp: f64,
-    /// 2.反应堆热功率Q_R
+    /// 2.反应堆热功率Q_R(GW)
     pub q_r: f64,
     /// 3.蒸汽发生器总蒸汽产量Ds
     pub d_s: f64,
@@ -186,7 +359,7 @@ pub struct CalcResult1 {
     pub g_zc2: f64,
 }
 
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CalcResult2 {
     // 附表一
     /// 1.核电厂输出功率N_e
@@ -405,9 +578,62 @@ pub struct CalcResult2 {
     pub lhes: Vec<CalcHESParameters>,
     /// 48.再热器抽汽(第一、二级再热器抽汽参数)
     pub rhx: Vec<CalcRHXParameters>,
+    // 早期的计算记录中没有以下字段
+    /// 44.4.给水泵驱动功率N_fwpd，汽动时为给水泵汽轮机轴功率，电动时为电动机耗电功率
+    #[serde(default)]
+    pub n_fwpd: f64,
+    /// 44.5.给水泵汽轮机进汽比焓h_fwpti，电动时为0
+    #[serde(default)]
+    pub h_fwpti: f64,
+    /// 44.6.给水泵汽轮机排汽比焓h_fwptz，电动时为0
+    #[serde(default)]
+    pub h_fwptz: f64,
+    /// 44.7.疏水泵耗电功率N_dp
+    #[serde(default)]
+    pub n_dp: f64,
+    /// 44.8.低压加热器自流入冷凝器的疏水流量G_dcd
+    #[serde(default)]
+    pub g_dcd: f64,
+    /// 44.9.低压加热器自流入冷凝器的疏水比焓h_dcd
+    #[serde(default)]
+    pub h_dcd: f64,
+    // 一回路冷却剂系统与蒸汽发生器热力设计
+    /// 49.一回路冷却剂总质量流量G_c
+    #[serde(default)]
+    pub g_c: f64,
+    /// 49.1.每环路冷却剂质量流量G_c1
+    #[serde(default)]
+    pub g_cloop: f64,
+    /// 49.2.每环路冷却剂体积流量V_c1
+    #[serde(default)]
+    pub v_cloop: f64,
+    /// 49.3.每台主泵轴功率N_RCP1
+    #[serde(default)]
+    pub n_rcp1: f64,
+    /// 49.4.主泵总轴功率N_RCP
+    #[serde(default)]
+    pub n_rcp: f64,
+    /// 50.蒸汽发生器总传热系数K_SG
+    #[serde(default)]
+    pub k_sg: f64,
+    /// 50.1.一次侧对流换热系数α_1
+    #[serde(default)]
+    pub alpha_1: f64,
+    /// 50.2.传热管内冷却剂流速w_c
+    #[serde(default)]
+    pub w_c: f64,
+    /// 51.蒸汽发生器总传热面积F_SG
+    #[serde(default)]
+    pub f_sg: f64,
+    /// 51.1.每台蒸汽发生器传热面积F_SG1
+    #[serde(default)]
+    pub f_sg1: f64,
+    /// 52.每台蒸汽发生器传热管数N_t
+    #[serde(default)]
+    pub n_tsg: f64,
 }
 
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CalcFWParameters {
     /// 进口给水压力p_fwxi
     pub p_fwxi: f64,
@@ -425,9 +651,21 @@ pub struct CalcFWParameters {
     pub t_roxk: f64,
     /// 汽侧疏水比焓h_roxk
     pub h_roxk: f64,
+    /// 管侧给水流量G_fwx，早期的计算记录中没有此字段
+    #[serde(default)]
+    pub g_fwx: f64,
+    /// 离开汽侧的疏水流量G_dx
+    #[serde(default)]
+    pub g_dx: f64,
+    /// 离开汽侧的疏水比焓h_dx
+    #[serde(default)]
+    pub h_dx: f64,
+    /// 给水焓升Δh_fwx
+    #[serde(default)]
+    pub dh_fwx: f64,
 }
 
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CalcHESParameters {
     /// 抽汽温度T_hesx
     pub t_hesx: f64,
@@ -441,7 +679,7 @@ pub struct CalcHESParameters {
     pub h_hesx: f64,
 }
 
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CalcRHXParameters {
     /// 加热蒸汽进口压力p_rhx
     pub p_rhx: f64,
@@ -0,0 +1,279 @@
+//! 输入参数预设
+//!
+//! 内置课程设计默认参数及若干参照实际机组的参数组，用户预设以JSON文件保存在预设目录中，
+//! 文件名即预设名称。
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fields::{FieldMeta, INPUT_FIELDS, field_map, field_value};
+use crate::parameters::CalcInputParameters;
+
+/// 命名的输入参数组
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    /// 预设名称
+    pub name: String,
+    /// 说明
+    #[serde(default)]
+    pub description: String,
+    /// 输入参数
+    pub params: CalcInputParameters,
+    /// 是否为内置预设，内置预设不可重命名或删除
+    #[serde(skip)]
+    pub builtin: bool,
+}
+
+impl Preset {
+    pub fn new(name: &str, description: &str, params: CalcInputParameters) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            params,
+            builtin: false,
+        }
+    }
+}
+
+/// 内置预设
+pub fn builtin_presets() -> Vec<Preset> {
+    let default = CalcInputParameters::from_default();
+    let presets = [
+        Preset::new(
+            "课程设计默认参数",
+            "课程设计给定的1000 MW压水堆核电厂参数",
+            default.clone(),
+        ),
+        Preset::new(
+            "大亚湾类(M310)",
+            "参照大亚湾核电站M310三环路机组：电功率984 MW，蒸汽发生器出口压力6.71 MPa，\
+             反应堆出口过冷度约17 ℃，海水冷却，其余取默认值，仅供教学参考",
+            CalcInputParameters {
+                ne: 984.0,
+                p_s: 6.71,
+                dt_sub: 17.0,
+                t_sw1: 22.0,
+                g_cd: 1100.0,
+                ..default.clone()
+            },
+        ),
+        Preset::new(
+            "AP1000类",
+            "参照AP1000两环路机组：电功率1250 MW，蒸汽发生器出口压力5.76 MPa，\
+             反应堆进出口温升约40 ℃，过冷度取推荐范围上限，\
+             两台蒸汽发生器、传热管外径17.48 mm，电动给水泵，其余取默认值，仅供教学参考",
+            CalcInputParameters {
+                ne: 1250.0,
+                p_s: 5.76,
+                dt_sub: 20.0,
+                dt_c: 40.0,
+                t_sw1: 26.0,
+                g_cd: 1500.0,
+                n_sg: 2.0,
+                d_sgo: 17.48,
+                delta_sg: 1.01,
+                l_sg: 20.85,
+                fwp_drive: 0.0,
+                ..default
+            },
+        ),
+    ];
+    presets
+        .into_iter()
+        .map(|preset| Preset {
+            builtin: true,
+            ..preset
+        })
+        .collect()
+}
+
+/// 检查预设名称能否用作文件名
+fn preset_path(dir: &Path, name: &str) -> std::io::Result<PathBuf> {
+    let invalid = |c: char| c.is_control() || r#"/\:*?"<>|"#.contains(c);
+    if name.trim().is_empty() || name.starts_with('.') || name.contains(invalid) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("预设名称无效: {name}"),
+        ));
+    }
+    if builtin_presets().iter().any(|preset| preset.name == name) {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("与内置预设重名: {name}"),
+        ));
+    }
+    Ok(dir.join(format!("{name}.json")))
+}
+
+/// 读取预设目录中的用户预设，按名称排序，目录不存在时返回空表
+pub fn load_user_presets(dir: impl AsRef<Path>) -> std::io::Result<Vec<Preset>> {
+    let dir = dir.as_ref();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut presets = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        let reader = BufReader::new(File::open(&path)?);
+        let mut preset: Preset = serde_json::from_reader(reader).map_err(|error| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("{}: {error}", path.display()),
+            )
+        })?;
+        // 以文件名为准，手动改名的文件也能正确显示
+        if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+            preset.name = stem.to_string();
+        }
+        presets.push(preset);
+    }
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(presets)
+}
+
+/// 内置预设与用户预设
+pub fn load_presets(dir: impl AsRef<Path>) -> std::io::Result<Vec<Preset>> {
+    let mut presets = builtin_presets();
+    presets.extend(load_user_presets(dir)?);
+    Ok(presets)
+}
+
+/// 保存用户预设，同名预设将被覆盖
+pub fn save_user_preset(dir: impl AsRef<Path>, preset: &Preset) -> std::io::Result<()> {
+    let path = preset_path(dir.as_ref(), &preset.name)?;
+    std::fs::create_dir_all(dir)?;
+    let writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(writer, preset).map_err(Error::other)
+}
+
+/// 重命名用户预设，新名称已存在时返回错误
+pub fn rename_user_preset(
+    dir: impl AsRef<Path>,
+    name: &str,
+    new_name: &str,
+) -> std::io::Result<()> {
+    let dir = dir.as_ref();
+    let path = preset_path(dir, name)?;
+    let new_path = preset_path(dir, new_name)?;
+    if new_path.exists() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("预设已存在: {new_name}"),
+        ));
+    }
+    let reader = BufReader::new(File::open(&path)?);
+    let mut preset: Preset = serde_json::from_reader(reader).map_err(Error::other)?;
+    preset.name = new_name.to_string();
+    let writer = BufWriter::new(File::create(new_path)?);
+    serde_json::to_writer_pretty(writer, &preset).map_err(Error::other)?;
+    std::fs::remove_file(path)
+}
+
+/// 删除用户预设
+pub fn delete_user_preset(dir: impl AsRef<Path>, name: &str) -> std::io::Result<()> {
+    std::fs::remove_file(preset_path(dir.as_ref(), name)?)
+}
+
+/// 两组输入参数中取值不同的字段
+#[derive(Debug, Clone, Copy)]
+pub struct ParamDiff {
+    pub field: &'static FieldMeta,
+    pub left: f64,
+    pub right: f64,
+}
+
+/// 按字段顺序比较两组输入参数
+pub fn diff_params(left: &CalcInputParameters, right: &CalcInputParameters) -> Vec<ParamDiff> {
+    let left = field_map(left);
+    let right = field_map(right);
+    INPUT_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let diff = ParamDiff {
+                field,
+                left: field_value(&left, field.key),
+                right: field_value(&right, field.key),
+            };
+            // 可选参数未给定时为NaN，给定与未给定之间也算作差异
+            let changed = if diff.left.is_nan() || diff.right.is_nan() {
+                diff.left.is_nan() != diff.right.is_nan()
+            } else {
+                (diff.left - diff.right).abs() > 1e-12 * diff.left.abs().max(1.0)
+            };
+            changed.then_some(diff)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fields::check_input_ranges;
+
+    #[test]
+    fn test_builtin_presets() {
+        let presets = builtin_presets();
+        assert!(presets.iter().all(|preset| preset.builtin));
+        for preset in &presets {
+            assert!(
+                check_input_ranges(&preset.params).is_empty(),
+                "{}",
+                preset.name
+            );
+        }
+        let diff = diff_params(&presets[0].params, &presets[1].params);
+        let keys: Vec<_> = diff.iter().map(|diff| diff.field.key).collect();
+        assert_eq!(keys, vec!["ne", "t_sw1", "g_cd", "dt_sub", "p_s"]);
+        assert_eq!(diff[0].right, 984.0);
+
+        // 逐级端差由未给定改为给定也算作差异
+        let params = CalcInputParameters {
+            theta_u2: Some(4.0),
+            ..presets[0].params.clone()
+        };
+        let diff = diff_params(&presets[0].params, &params);
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].field.key, "theta_u2");
+        assert!(diff[0].left.is_nan());
+        assert_eq!(diff[0].right, 4.0);
+    }
+
+    #[test]
+    fn test_user_presets() {
+        let dir = std::env::temp_dir().join(format!("npp-thc-presets-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(load_user_presets(&dir).unwrap().is_empty());
+
+        let params = CalcInputParameters {
+            ne: 1100.0,
+            ..CalcInputParameters::from_default()
+        };
+        save_user_preset(&dir, &Preset::new("我的参数", "", params)).unwrap();
+        assert!(
+            save_user_preset(
+                &dir,
+                &Preset::new("a/b", "", CalcInputParameters::default())
+            )
+            .is_err()
+        );
+        assert!(save_user_preset(&dir, &builtin_presets()[0]).is_err());
+
+        rename_user_preset(&dir, "我的参数", "方案一").unwrap();
+        let presets = load_presets(&dir).unwrap();
+        let preset = presets
+            .iter()
+            .find(|preset| preset.name == "方案一")
+            .unwrap();
+        assert!(!preset.builtin);
+        assert_eq!(preset.params.ne, 1100.0);
+
+        delete_user_preset(&dir, "方案一").unwrap();
+        assert!(load_user_presets(&dir).unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+//! 一回路冷却剂流量与主泵功率
+//!
+//! 由反应堆热功率与堆芯进出口冷却剂比焓差确定冷却剂质量流量
+//! `G_c = Q_R/(h_co - h_ci)`，按环路数均分后以主泵处(堆芯进口温度)的密度
+//! 求每环路体积流量；主泵轴功率 `N_RCP = G_c1·g·H/η_RCP`。
+//!
+//! 主泵轴功率最终耗散为冷却剂的热量，可按给定份额计入一回路热平衡：
+//! 传给蒸汽发生器的热量为 `η_1·(Q_R + k·N_RCP)`。
+use seuif97::*;
+
+use crate::parameters::CalcInputParameters;
+
+/// 重力加速度(m/s²)
+const GRAVITY: f64 = 9.80665;
+
+/// 一回路冷却剂流量与主泵功率
+#[derive(Debug, Clone, Copy)]
+pub struct PrimaryLoop {
+    /// 一回路冷却剂总质量流量(kg/s)
+    pub g_c: f64,
+    /// 每环路冷却剂质量流量(kg/s)
+    pub g_cloop: f64,
+    /// 每环路冷却剂体积流量(m³/s)
+    pub v_cloop: f64,
+    /// 每台主泵轴功率(MW)
+    pub n_rcp1: f64,
+    /// 主泵总轴功率(MW)
+    pub n_rcp: f64,
+}
+
+/// 每MW反应堆热功率对应的主泵轴功率(MW)
+fn pump_power_ratio(params: &CalcInputParameters, t_co: f64, t_ci: f64) -> f64 {
+    let p_c = params.p_c;
+    let g_c = 1000.0 / (pt(p_c, t_co, OH) - pt(p_c, t_ci, OH));
+    g_c * GRAVITY * params.h_rcp / params.n_rcp / 1e6
+}
+
+/// 计入热平衡的主泵热量与反应堆热功率之比，主泵热量不计入时为0
+///
+/// 热平衡中传给蒸汽发生器的热量为 `η_1·Q_R·(1 + 返回值)`。
+pub fn pump_heat_ratio(params: &CalcInputParameters, t_co: f64, t_ci: f64) -> f64 {
+    if params.k_rcp > 0.0 {
+        params.k_rcp * pump_power_ratio(params, t_co, t_ci)
+    } else {
+        0.0
+    }
+}
+
+/// 一回路冷却剂流量与主泵功率
+///
+/// # Arguments
+///
+/// * `q_r` - 反应堆热功率(MW)
+///
+/// * `t_co`、`t_ci` - 反应堆出口、进口冷却剂温度(℃)
+pub fn primary_loop(params: &CalcInputParameters, q_r: f64, t_co: f64, t_ci: f64) -> PrimaryLoop {
+    let p_c = params.p_c;
+    let g_c = q_r * 1000.0 / (pt(p_c, t_co, OH) - pt(p_c, t_ci, OH));
+    let g_cloop = g_c / params.n_sg;
+    let n_rcp = q_r * pump_power_ratio(params, t_co, t_ci);
+    PrimaryLoop {
+        g_c,
+        g_cloop,
+        v_cloop: g_cloop / pt(p_c, t_ci, OD),
+        n_rcp1: n_rcp / params.n_sg,
+        n_rcp,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temperatures(params: &CalcInputParameters) -> (f64, f64) {
+        let t_co = px(params.p_c, 0.0, OT) - params.dt_sub;
+        (t_co, t_co - params.dt_c)
+    }
+
+    #[test]
+    fn test_flow_and_pump_power() {
+        let params = CalcInputParameters::from_default();
+        let (t_co, t_ci) = temperatures(&params);
+        let result = primary_loop(&params, 3000.0, t_co, t_ci);
+        // 百万千瓦级三环路机组：每环路约5 m³/s，每台主泵轴功率约6 MW
+        assert!((14000.0..17000.0).contains(&result.g_c), "{result:?}");
+        assert!((4.0..7.0).contains(&result.v_cloop), "{result:?}");
+        assert!((4.0..9.0).contains(&result.n_rcp1), "{result:?}");
+        assert!((result.g_cloop * params.n_sg - result.g_c).abs() < 1e-9);
+        assert!((result.n_rcp1 * params.n_sg - result.n_rcp).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pump_heat_ratio() {
+        let params = CalcInputParameters::from_default();
+        let (t_co, t_ci) = temperatures(&params);
+        assert_eq!(pump_heat_ratio(&params, t_co, t_ci), 0.0);
+
+        let params = CalcInputParameters {
+            k_rcp: 1.0,
+            ..params
+        };
+        let ratio = pump_heat_ratio(&params, t_co, t_ci);
+        let result = primary_loop(&params, 3000.0, t_co, t_ci);
+        assert!((ratio * 3000.0 - result.n_rcp).abs() < 1e-9);
+
+        // 计入主泵热量后，相同电功率所需的反应堆热功率降低
+        let q_r = |params: CalcInputParameters| {
+            let calculator = crate::Calculator::solve(params).unwrap();
+            calculator.results.result1.last().unwrap().q_r
+        };
+        let with_pump = q_r(params.clone());
+        let without_pump = q_r(CalcInputParameters {
+            k_rcp: 0.0,
+            ..params
+        });
+        assert!(with_pump < without_pump, "{with_pump} {without_pump}");
+    }
+}
@@ -0,0 +1,439 @@
+//! 冷却水温度季节性分析
+//!
+//! 读取逐月或逐时的循环冷却水进口温度`t_sw1`曲线，在每个时段求解全厂热平衡，
+//! 给出电功率或效率的时间序列，以及按发电量加权的全年平均值。
+//! 可选择电功率不变(效率随水温变化)或反应堆热功率不变(电功率随水温变化)。
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::Calculator;
+use crate::export::csv::line;
+use crate::parameters::CalcInputParameters;
+use crate::units::{Unit, UnitSystem};
+
+/// 各月天数(平年)，逐月曲线未给出小时数时使用
+const DAYS_PER_MONTH: [f64; 12] = [
+    31.0, 28.0, 31.0, 30.0, 31.0, 30.0, 31.0, 31.0, 30.0, 31.0, 30.0, 31.0,
+];
+/// 热功率不变时调整电功率的最大迭代次数
+const MAX_ITERATIONS: usize = 20;
+/// 热功率的相对收敛容差
+const TOLERANCE: f64 = 1e-6;
+
+/// 冷却水温度曲线中的一个时段
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfilePoint {
+    /// 时段名称，如月份或时刻
+    pub label: String,
+    /// 循环冷却水进口温度(℃)
+    pub t_sw1: f64,
+    /// 时段小时数
+    pub hours: f64,
+}
+
+/// 解析冷却水温度曲线CSV/TSV
+///
+/// 每行为`时段,温度(℃)[,小时数]`，跳过空行、`#`注释行和表头。
+/// 未给出小时数时，恰为12行的曲线视为逐月(按平年天数)，否则每行为1小时。
+pub fn parse_profile(contents: &str) -> Result<Vec<ProfilePoint>, String> {
+    let contents = contents.trim_start_matches('\u{feff}');
+    let mut rows = Vec::new();
+    let mut first = true;
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let is_first = std::mem::replace(&mut first, false);
+        let cells: Vec<&str> = line
+            .split([',', '\t'])
+            .map(|cell| cell.trim().trim_matches('"').trim())
+            .collect();
+        let number = |index: usize| cells.get(index).map(|cell| cell.parse::<f64>());
+        match (number(1), number(2)) {
+            (Some(Ok(t_sw1)), hours) => {
+                let hours = match hours {
+                    Some(Ok(hours)) if hours > 0.0 => Some(hours),
+                    None => None,
+                    Some(_) if cells[2].is_empty() => None,
+                    Some(_) => return Err(format!("第{}行小时数无效: {line}", i + 1)),
+                };
+                rows.push((cells[0].to_string(), t_sw1, hours));
+            }
+            // 第一行为表头
+            _ if is_first => {}
+            _ => return Err(format!("第{}行温度无效: {line}", i + 1)),
+        }
+    }
+    if rows.is_empty() {
+        return Err(String::from("温度曲线为空"));
+    }
+    let monthly = rows.len() == DAYS_PER_MONTH.len();
+    Ok(rows
+        .into_iter()
+        .enumerate()
+        .map(|(i, (label, t_sw1, hours))| ProfilePoint {
+            label,
+            t_sw1,
+            hours: hours.unwrap_or(if monthly {
+                24.0 * DAYS_PER_MONTH[i]
+            } else {
+                1.0
+            }),
+        })
+        .collect())
+}
+
+/// 随冷却水温度变化时保持不变的量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Basis {
+    /// 电功率不变，效率与反应堆热功率随水温变化
+    #[default]
+    ConstantElectric,
+    /// 反应堆热功率不变，电功率随水温变化
+    ConstantThermal,
+}
+
+impl Basis {
+    pub const ALL: [Self; 2] = [Self::ConstantElectric, Self::ConstantThermal];
+}
+
+impl Display for Basis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::ConstantElectric => "电功率不变",
+            Self::ConstantThermal => "反应堆热功率不变",
+        })
+    }
+}
+
+impl FromStr for Basis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "electric" => Ok(Self::ConstantElectric),
+            "thermal" => Ok(Self::ConstantThermal),
+            _ => Err(format!("未知的计算基准: {s}，应为electric或thermal")),
+        }
+    }
+}
+
+/// 一个时段的计算结果(内部单位)
+#[derive(Debug, Clone)]
+pub struct SeasonalPoint {
+    pub label: String,
+    pub t_sw1: f64,
+    pub hours: f64,
+    /// 电功率(MW)
+    pub ne: f64,
+    pub eta_enpp: f64,
+    /// 反应堆热功率(GW)
+    pub q_r: f64,
+    /// 冷凝器凝结水饱和温度
+    pub t_cd: f64,
+    /// 冷凝器运行压力
+    pub p_cd: f64,
+}
+
+/// 全年汇总
+#[derive(Debug, Clone, Copy)]
+pub struct Annual {
+    /// 计算成功的时段总小时数
+    pub hours: f64,
+    /// 发电量(MWh)
+    pub energy: f64,
+    /// 反应堆产热量(MWh)
+    pub heat: f64,
+    /// 按发电量加权的平均效率
+    pub eta_enpp: f64,
+    /// 平均电功率(MW)
+    pub ne: f64,
+    /// 按时间加权的平均冷却水温度
+    pub t_sw1: f64,
+    pub eta_min: f64,
+    pub eta_max: f64,
+}
+
+/// 季节性分析结果
+#[derive(Debug, Clone, Default)]
+pub struct SeasonalReport {
+    pub basis: Basis,
+    /// 输入参数下的反应堆热功率(GW)，热功率不变时为各时段的目标值
+    pub design_q_r: f64,
+    pub points: Vec<SeasonalPoint>,
+    /// 计算失败的时段及原因
+    pub failures: Vec<(String, String)>,
+}
+
+/// 按给定电功率与冷却水温度求解一个时段
+fn solve_point(
+    params: &CalcInputParameters,
+    profile: &ProfilePoint,
+    ne: f64,
+) -> Result<SeasonalPoint, String> {
+    let calculator = Calculator::solve(CalcInputParameters {
+        ne,
+        t_sw1: profile.t_sw1,
+        ..params.clone()
+    })?;
+    let r1 = calculator
+        .results
+        .result1
+        .last()
+        .ok_or_else(|| String::from("计算结果为空"))?;
+    if !(r1.eta_enpp.is_finite() && r1.q_r.is_finite()) {
+        return Err(String::from("计算结果不是有限值"));
+    }
+    Ok(SeasonalPoint {
+        label: profile.label.clone(),
+        t_sw1: profile.t_sw1,
+        hours: profile.hours,
+        ne,
+        eta_enpp: r1.eta_enpp,
+        q_r: r1.q_r,
+        t_cd: calculator.results.result2.t_cd,
+        p_cd: calculator.results.result2.p_cd,
+    })
+}
+
+/// 反应堆热功率不变：按热功率之比调整电功率直至收敛
+fn solve_constant_thermal(
+    params: &CalcInputParameters,
+    profile: &ProfilePoint,
+    q_r: f64,
+) -> Result<SeasonalPoint, String> {
+    let mut ne = params.ne;
+    for _ in 0..MAX_ITERATIONS {
+        let point = solve_point(params, profile, ne)?;
+        if ((point.q_r - q_r) / q_r).abs() < TOLERANCE {
+            return Ok(point);
+        }
+        ne *= q_r / point.q_r;
+    }
+    Err(format!("调整电功率{MAX_ITERATIONS}次后热功率仍未收敛"))
+}
+
+/// 在冷却水温度曲线的每个时段求解全厂热平衡
+pub fn seasonal_study(
+    params: &CalcInputParameters,
+    profile: &[ProfilePoint],
+    basis: Basis,
+) -> Result<SeasonalReport, String> {
+    if profile.is_empty() {
+        return Err(String::from("温度曲线为空"));
+    }
+    let design = solve_point(
+        params,
+        &ProfilePoint {
+            label: String::new(),
+            t_sw1: params.t_sw1,
+            hours: 0.0,
+        },
+        params.ne,
+    )
+    .map_err(|error| format!("输入参数计算失败: {error}"))?;
+
+    let mut report = SeasonalReport {
+        basis,
+        design_q_r: design.q_r,
+        ..SeasonalReport::default()
+    };
+    for point in profile {
+        let result = match basis {
+            Basis::ConstantElectric => solve_point(params, point, params.ne),
+            Basis::ConstantThermal => solve_constant_thermal(params, point, design.q_r),
+        };
+        match result {
+            Ok(point) => report.points.push(point),
+            Err(error) => report.failures.push((point.label.clone(), error)),
+        }
+    }
+    Ok(report)
+}
+
+impl SeasonalReport {
+    /// 全年汇总，没有计算成功的时段时返回`None`
+    pub fn annual(&self) -> Option<Annual> {
+        let hours: f64 = self.points.iter().map(|point| point.hours).sum();
+        if self.points.is_empty() || hours <= 0.0 {
+            return None;
+        }
+        let energy: f64 = self.points.iter().map(|p| p.ne * p.hours).sum();
+        let heat: f64 = self.points.iter().map(|p| p.q_r * 1000.0 * p.hours).sum();
+        let etas = self.points.iter().map(|point| point.eta_enpp);
+        Some(Annual {
+            hours,
+            energy,
+            heat,
+            eta_enpp: self
+                .points
+                .iter()
+                .map(|p| p.eta_enpp * p.ne * p.hours)
+                .sum::<f64>()
+                / energy,
+            ne: energy / hours,
+            t_sw1: self.points.iter().map(|p| p.t_sw1 * p.hours).sum::<f64>() / hours,
+            eta_min: etas.clone().fold(f64::INFINITY, f64::min),
+            eta_max: etas.fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+
+    /// 逐时段结果表的表头
+    pub fn headers(units: &UnitSystem) -> Vec<String> {
+        let header = |name: &str, unit: Unit| match units.label(unit) {
+            "" => name.to_string(),
+            label => format!("{name}({label})"),
+        };
+        vec![
+            String::from("时段"),
+            String::from("小时数"),
+            header("T_sw1", Unit::Celsius),
+            header("T_cd", Unit::Celsius),
+            header("p_cd", Unit::MPa),
+            header("N_e", Unit::MW),
+            header("η_eNPP", Unit::Fraction),
+            header("Q_R", Unit::GW),
+        ]
+    }
+
+    /// 逐时段结果表中的一行
+    pub fn cells(point: &SeasonalPoint, units: &UnitSystem) -> Vec<String> {
+        vec![
+            point.label.clone(),
+            format!("{}", point.hours),
+            units.format(Unit::Celsius, point.t_sw1),
+            units.format(Unit::Celsius, point.t_cd),
+            units.format(Unit::MPa, point.p_cd),
+            units.format(Unit::MW, point.ne),
+            units.format(Unit::Fraction, point.eta_enpp),
+            units.format(Unit::GW, point.q_r),
+        ]
+    }
+
+    /// 全年汇总的各项，(名称, 数值)
+    pub fn annual_rows(&self, units: &UnitSystem) -> Vec<(String, String)> {
+        let Some(annual) = self.annual() else {
+            return Vec::new();
+        };
+        let with_unit = |name: &str, unit: Unit, value: f64| match units.label(unit) {
+            "" => (name.to_string(), units.format(unit, value)),
+            label => (format!("{name}({label})"), units.format(unit, value)),
+        };
+        vec![
+            (String::from("总小时数"), format!("{}", annual.hours)),
+            (String::from("发电量(MWh)"), format!("{:.1}", annual.energy)),
+            (
+                String::from("反应堆产热量(MWh)"),
+                format!("{:.1}", annual.heat),
+            ),
+            with_unit("发电量加权平均效率", Unit::Fraction, annual.eta_enpp),
+            with_unit("平均电功率", Unit::MW, annual.ne),
+            with_unit("时间加权平均冷却水温度", Unit::Celsius, annual.t_sw1),
+            with_unit("最低效率", Unit::Fraction, annual.eta_min),
+            with_unit("最高效率", Unit::Fraction, annual.eta_max),
+        ]
+    }
+
+    /// 生成Markdown格式的报告
+    pub fn to_markdown(&self, units: &UnitSystem) -> String {
+        let table_row = |cells: &[String]| format!("| {} |\n", cells.join(" | "));
+        let mut markdown = String::from("# 冷却水温度季节性分析\n\n");
+        markdown.push_str(&format!(
+            "计算基准: {}，输入参数下的反应堆热功率 {} {}。\n\n",
+            self.basis,
+            units.format(Unit::GW, self.design_q_r),
+            units.label(Unit::GW)
+        ));
+        for (label, error) in &self.failures {
+            markdown.push_str(&format!("- 时段“{label}”计算失败: {error}\n"));
+        }
+        if !self.failures.is_empty() {
+            markdown.push('\n');
+        }
+        let annual = self.annual_rows(units);
+        if !annual.is_empty() {
+            markdown.push_str("## 全年汇总\n\n| 项目 | 数值 |\n| --- | --- |\n");
+            for (name, value) in annual {
+                markdown.push_str(&table_row(&[name, value]));
+            }
+            markdown.push('\n');
+        }
+        markdown.push_str("## 逐时段结果\n\n");
+        let headers = Self::headers(units);
+        markdown.push_str(&table_row(&headers));
+        markdown.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+        for point in &self.points {
+            markdown.push_str(&table_row(&Self::cells(point, units)));
+        }
+        markdown
+    }
+
+    /// 生成CSV/TSV格式的时间序列，其后为全年汇总
+    pub fn to_csv(&self, delimiter: char, units: &UnitSystem) -> String {
+        let mut content = line(&Self::headers(units), delimiter);
+        for point in &self.points {
+            content.push_str(&line(&Self::cells(point, units), delimiter));
+        }
+        let annual = self.annual_rows(units);
+        if !annual.is_empty() {
+            content.push('\n');
+            for (name, value) in annual {
+                content.push_str(&line(&[name, value], delimiter));
+            }
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profile() {
+        let monthly: String = (1..=12)
+            .map(|month| format!("{month}月,{}\n", 10 + month))
+            .collect();
+        let profile = parse_profile(&format!("时段,温度\n{monthly}")).unwrap();
+        assert_eq!(profile.len(), 12);
+        assert_eq!(profile[1].hours, 28.0 * 24.0);
+        assert_eq!(profile.iter().map(|p| p.hours).sum::<f64>(), 8760.0);
+
+        let hourly = parse_profile("# 逐时\n时刻\t温度\n0:00\t20.5\n1:00\t20.1\t2\n").unwrap();
+        assert_eq!(hourly[0].hours, 1.0);
+        assert_eq!(hourly[1].hours, 2.0);
+        assert_eq!(hourly[1].t_sw1, 20.1);
+
+        assert!(parse_profile("").is_err());
+        assert!(parse_profile("1月,20\n2月,abc\n").is_err());
+        assert!(parse_profile("1月,20,-1\n").is_err());
+    }
+
+    #[test]
+    fn test_seasonal_study() {
+        let params = CalcInputParameters::from_default();
+        let profile = parse_profile("冬,12,2000\n春,20,2000\n夏,30,2760\n秋,22,2000\n").unwrap();
+
+        let electric = seasonal_study(&params, &profile, Basis::ConstantElectric).unwrap();
+        assert!(electric.failures.is_empty());
+        assert!(electric.points.iter().all(|p| p.ne == params.ne));
+        // 水温越高冷凝器压力越高、效率越低
+        assert!(electric.points[2].p_cd > electric.points[0].p_cd);
+        assert!(electric.points[2].eta_enpp < electric.points[0].eta_enpp);
+        let annual = electric.annual().unwrap();
+        assert_eq!(annual.hours, 8760.0);
+        assert!(annual.eta_min <= annual.eta_enpp && annual.eta_enpp <= annual.eta_max);
+
+        let thermal = seasonal_study(&params, &profile, Basis::ConstantThermal).unwrap();
+        assert!(thermal.failures.is_empty());
+        for point in &thermal.points {
+            assert!((point.q_r - thermal.design_q_r).abs() / thermal.design_q_r < 1e-5);
+        }
+        assert!(thermal.points[2].ne < thermal.points[0].ne);
+
+        let units = UnitSystem::default();
+        assert!(thermal.to_markdown(&units).contains("## 全年汇总"));
+        assert!(thermal.to_csv(',', &units).starts_with("时段,小时数"));
+    }
+}
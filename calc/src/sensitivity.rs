@@ -0,0 +1,366 @@
+//! 灵敏度分析
+//!
+//! 以中心差分逐个扰动输入参数，求核电厂效率`eta_enpp`与反应堆热功率`q_r`对各参数的偏导数，
+//! 以及归一化的弹性系数 (∂y/∂x · x/y)，按效率弹性系数的绝对值排序。
+//! 某一侧扰动计算失败或迭代次数与基准不同时改用单侧差分；两侧均失败时记录失败原因。
+use crate::export::csv::line;
+use crate::fields::{FieldMeta, INPUT_FIELDS, field_map, field_value, with_input_values};
+use crate::parameters::CalcInputParameters;
+use crate::units::{Unit, UnitSystem};
+use crate::{Calculator, Outputs};
+
+/// 默认相对扰动步长
+pub const DEFAULT_STEP: f64 = 1e-3;
+
+/// 差分格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difference {
+    /// 中心差分
+    Central,
+    /// 向前差分，仅正向扰动可用
+    Forward,
+    /// 向后差分，仅负向扰动可用
+    Backward,
+}
+
+impl Difference {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Central => "中心",
+            Self::Forward => "向前",
+            Self::Backward => "向后",
+        }
+    }
+}
+
+/// 某个输入参数的偏导数(内部单位)
+#[derive(Debug, Clone, Copy)]
+pub struct Derivative {
+    pub method: Difference,
+    /// ∂η_eNPP/∂x
+    pub d_eta: f64,
+    /// ∂Q_R/∂x，Q_R单位为GW
+    pub d_q_r: f64,
+    /// 效率的弹性系数
+    pub elasticity_eta: f64,
+    /// 热功率的弹性系数
+    pub elasticity_q_r: f64,
+}
+
+/// 某个输入参数的分析结果
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Derivative(Derivative),
+    /// 未参与分析
+    Skipped(&'static str),
+    /// 两侧扰动均计算失败
+    Failed(String),
+}
+
+/// 灵敏度表中的一行
+#[derive(Debug, Clone)]
+pub struct SensitivityRow {
+    pub field: &'static FieldMeta,
+    /// 基准值(内部单位)
+    pub value: f64,
+    /// 扰动步长(内部单位)
+    pub step: f64,
+    pub outcome: Outcome,
+    /// 改用单侧差分等情况的说明
+    pub note: Option<String>,
+}
+
+impl SensitivityRow {
+    pub fn derivative(&self) -> Option<&Derivative> {
+        match &self.outcome {
+            Outcome::Derivative(derivative) => Some(derivative),
+            _ => None,
+        }
+    }
+}
+
+/// 灵敏度分析结果，各行按效率弹性系数的绝对值从大到小排列，未得到导数的行在最后
+#[derive(Debug, Clone, Default)]
+pub struct SensitivityReport {
+    /// 基准效率
+    pub eta_enpp: f64,
+    /// 基准热功率(GW)
+    pub q_r: f64,
+    /// 相对扰动步长
+    pub step: f64,
+    pub rows: Vec<SensitivityRow>,
+}
+
+/// 对全部输入参数做灵敏度分析，`step`为相对扰动步长，参数为0时作为绝对步长
+///
+/// 基准参数计算失败时返回错误。
+pub fn analyze_sensitivity(
+    params: &CalcInputParameters,
+    step: f64,
+) -> Result<SensitivityReport, String> {
+    let base = Calculator::evaluate(params.clone())
+        .map_err(|error| format!("基准参数计算失败: {error}"))?;
+    let values = field_map(params);
+    let mut rows: Vec<_> = INPUT_FIELDS
+        .iter()
+        .map(|field| {
+            let value = field_value(&values, field.key);
+            let h = if value.abs() > 1e-12 {
+                step * value.abs()
+            } else {
+                step
+            };
+            let mut row = SensitivityRow {
+                field,
+                value,
+                step: h,
+                outcome: Outcome::Skipped(""),
+                note: None,
+            };
+            if let Some(reason) = field.kind.skip_reason() {
+                row.outcome = Outcome::Skipped(reason);
+                return row;
+            }
+
+            let evaluate = |value| {
+                with_input_values(params, &[(field.key, value)]).and_then(Calculator::evaluate)
+            };
+            let (plus, minus) = (evaluate(value + h), evaluate(value - h));
+            // 迭代次数变化时输出不连续，优先使用与基准迭代次数相同的一侧
+            let same = |outputs: &Outputs| outputs.iterations == base.iterations;
+            let (method, upper, lower, note) = match (&plus, &minus) {
+                (Ok(p), Ok(m)) if same(p) == same(m) => {
+                    let note = (!same(p)).then(|| String::from("迭代次数与基准不同"));
+                    (Difference::Central, *p, *m, note)
+                }
+                (Ok(p), Ok(_)) if same(p) => (
+                    Difference::Forward,
+                    *p,
+                    base,
+                    Some(String::from("负向扰动迭代次数与基准不同")),
+                ),
+                (Ok(_), Ok(m)) => (
+                    Difference::Backward,
+                    base,
+                    *m,
+                    Some(String::from("正向扰动迭代次数与基准不同")),
+                ),
+                (Ok(p), Err(error)) => (
+                    Difference::Forward,
+                    *p,
+                    base,
+                    Some(format!("负向扰动失败: {error}")),
+                ),
+                (Err(error), Ok(m)) => (
+                    Difference::Backward,
+                    base,
+                    *m,
+                    Some(format!("正向扰动失败: {error}")),
+                ),
+                (Err(error), Err(_)) => {
+                    row.outcome = Outcome::Failed(error.clone());
+                    return row;
+                }
+            };
+            let dx = if method == Difference::Central {
+                2.0 * h
+            } else {
+                h
+            };
+            let d_eta = (upper.eta_enpp - lower.eta_enpp) / dx;
+            let d_q_r = (upper.q_r - lower.q_r) / dx;
+            row.outcome = Outcome::Derivative(Derivative {
+                method,
+                d_eta,
+                d_q_r,
+                elasticity_eta: d_eta * value / base.eta_enpp,
+                elasticity_q_r: d_q_r * value / base.q_r,
+            });
+            row.note = note;
+            row
+        })
+        .collect();
+
+    let key = |row: &SensitivityRow| row.derivative().map(|d| d.elasticity_eta.abs());
+    rows.sort_by(|a, b| match (key(a), key(b)) {
+        (Some(a), Some(b)) => b.total_cmp(&a),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    });
+
+    Ok(SensitivityReport {
+        eta_enpp: base.eta_enpp,
+        q_r: base.q_r,
+        step,
+        rows,
+    })
+}
+
+impl SensitivityReport {
+    /// 效率弹性系数绝对值的最大值，用于确定条形图比例
+    pub fn max_elasticity(&self) -> f64 {
+        self.rows
+            .iter()
+            .filter_map(SensitivityRow::derivative)
+            .fold(0.0, |max, d| max.max(d.elasticity_eta.abs()))
+    }
+
+    const HEADERS: [&'static str; 11] = [
+        "排名",
+        "字段",
+        "参数",
+        "单位",
+        "基准值",
+        "∂η_eNPP/∂x",
+        "∂Q_R/∂x",
+        "η_eNPP弹性",
+        "Q_R弹性",
+        "差分",
+        "说明",
+    ];
+
+    /// 一行的各列，偏导数按内部单位给出
+    fn cells(rank: usize, row: &SensitivityRow, units: &UnitSystem) -> Vec<String> {
+        let mut cells = vec![
+            rank.to_string(),
+            row.field.key.to_string(),
+            format!("{} ({})", row.field.label, row.field.symbol),
+            units.label(row.field.unit).to_string(),
+            units.format(row.field.unit, row.value),
+        ];
+        match &row.outcome {
+            Outcome::Derivative(d) => {
+                cells.extend([
+                    format!("{:.4e}", d.d_eta),
+                    format!("{:.4e}", d.d_q_r),
+                    format!("{:+.4}", d.elasticity_eta),
+                    format!("{:+.4}", d.elasticity_q_r),
+                    d.method.label().to_string(),
+                    row.note.clone().unwrap_or_default(),
+                ]);
+            }
+            Outcome::Skipped(reason) => {
+                cells.extend(["-", "-", "-", "-", "-"].map(String::from));
+                cells.push(format!("未分析: {reason}"));
+            }
+            Outcome::Failed(error) => {
+                cells.extend(["-", "-", "-", "-", "-"].map(String::from));
+                cells.push(format!("计算失败: {error}"));
+            }
+        }
+        cells
+    }
+
+    /// 生成Markdown格式的灵敏度报告，附文本形式的龙卷风图
+    pub fn to_markdown(&self, units: &UnitSystem) -> String {
+        let table_row = |cells: &[String]| format!("| {} |\n", cells.join(" | "));
+        let mut markdown = String::from("# 灵敏度分析\n\n");
+        markdown.push_str(&format!(
+            "基准：η_eNPP = {} {}，Q_R = {} GW。相对扰动步长 {:e}，偏导数按内部单位计算，\
+             弹性系数为 ∂y/∂x · x/y。\n\n",
+            units.format(Unit::Fraction, self.eta_enpp),
+            units.label(Unit::Fraction),
+            self.q_r,
+            self.step
+        ));
+
+        markdown.push_str("## η_eNPP 弹性系数\n\n```\n");
+        let max = self.max_elasticity();
+        for row in &self.rows {
+            let Some(d) = row.derivative() else { continue };
+            let width = if max > 0.0 {
+                (d.elasticity_eta.abs() / max * 20.0).round() as usize
+            } else {
+                0
+            };
+            let bar = "█".repeat(width);
+            let (left, right) = if d.elasticity_eta < 0.0 {
+                (format!("{bar:>20}"), String::new())
+            } else {
+                (" ".repeat(20), bar)
+            };
+            markdown.push_str(&format!(
+                "{:<10} {left}|{right:<20} {:+.4}\n",
+                row.field.key, d.elasticity_eta
+            ));
+        }
+        markdown.push_str("```\n\n## 全部参数\n\n");
+        let headers = Self::HEADERS.map(String::from);
+        markdown.push_str(&table_row(&headers));
+        markdown.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+        for (i, row) in self.rows.iter().enumerate() {
+            markdown.push_str(&table_row(&Self::cells(i + 1, row, units)));
+        }
+        markdown
+    }
+
+    /// 生成CSV/TSV格式的灵敏度表
+    pub fn to_csv(&self, delimiter: char, units: &UnitSystem) -> String {
+        let mut content = line(&Self::HEADERS.map(String::from), delimiter);
+        for (i, row) in self.rows.iter().enumerate() {
+            content.push_str(&line(&Self::cells(i + 1, row, units), delimiter));
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_sensitivity() {
+        let report =
+            analyze_sensitivity(&CalcInputParameters::from_default(), DEFAULT_STEP).unwrap();
+        assert_eq!(report.rows.len(), INPUT_FIELDS.len());
+        let row = |key: &str| report.rows.iter().find(|row| row.field.key == key).unwrap();
+
+        // 高压缸内效率提高，核电厂效率提高、热功率降低
+        let n_hi = row("n_hi").derivative().unwrap();
+        assert!(n_hi.d_eta > 0.0 && n_hi.d_q_r < 0.0);
+        // 电功率与热功率近似成正比
+        let ne = row("ne").derivative().unwrap();
+        assert!((ne.elasticity_q_r - 1.0).abs() < 1e-2);
+        assert!(matches!(row("z").outcome, Outcome::Skipped(_)));
+
+        let elasticities: Vec<_> = report
+            .rows
+            .iter()
+            .filter_map(|row| row.derivative().map(|d| d.elasticity_eta.abs()))
+            .collect();
+        assert!(elasticities.windows(2).all(|w| w[0] >= w[1]));
+        assert!(matches!(
+            report.rows.last().unwrap().outcome,
+            Outcome::Skipped(_)
+        ));
+
+        let units = UnitSystem::default();
+        assert!(report.to_markdown(&units).contains("## η_eNPP 弹性系数"));
+        let csv = report.to_csv(',', &units);
+        assert!(csv.starts_with("排名,字段,参数,单位,基准值,"));
+        assert_eq!(csv.lines().count(), INPUT_FIELDS.len() + 1);
+    }
+
+    #[test]
+    fn test_one_sided_difference() {
+        // 干度为1时正向扰动不可用，改用向后差分
+        let params = CalcInputParameters {
+            x_fh: 1.0,
+            ..CalcInputParameters::from_default()
+        };
+        let report = analyze_sensitivity(&params, DEFAULT_STEP).unwrap();
+        let row = report
+            .rows
+            .iter()
+            .find(|row| row.field.key == "x_fh")
+            .unwrap();
+        assert_eq!(row.derivative().unwrap().method, Difference::Backward);
+        assert!(row.note.is_some());
+
+        // 基准计算失败时返回错误
+        let params = CalcInputParameters {
+            p_s: f64::NAN,
+            ..CalcInputParameters::from_default()
+        };
+        assert!(analyze_sensitivity(&params, DEFAULT_STEP).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! 蒸汽发生器热力设计
+//!
+//! 由反应堆热功率、一回路冷却剂流量、一二次侧对数平均温差`ΔT_m`与冷却剂进出口温度确定：
+//! - 传热面积 `F = η_1·Q_R/(K·ΔT_m)`；
+//! - 每台传热管数 `N_t = F/(n_SG·π·d_o·L)`。
+//!
+//! 总传热系数`K`可直接给定；给定为0时按管束几何计算，以传热管外表面为基准
+//! `1/K = d_o/(d_i·α_1) + d_o·ln(d_o/d_i)/(2λ) + R_f + 1/α_2`，
+//! 一次侧`α_1`按Dittus-Boelter关联式 `Nu = 0.023Re^0.8·Pr^0.4`，物性取冷却剂平均温度。
+//! 管内流速取决于传热管数，因此与传热面积交替迭代至收敛。
+use std::f64::consts::PI;
+
+use seuif97::*;
+
+use crate::parameters::CalcInputParameters;
+
+/// 按管束几何计算总传热系数的最大迭代次数
+const MAX_ITERATIONS: usize = 50;
+/// 总传热系数的相对收敛容差
+const TOLERANCE: f64 = 1e-9;
+/// 按管束几何计算时总传热系数的迭代初值，kW/(m²·K)
+const INITIAL_COEFFICIENT: f64 = 6.0;
+
+/// 蒸汽发生器热力设计结果
+#[derive(Debug, Clone, Copy)]
+pub struct Sizing {
+    /// 总传热系数(kW/(m²·K))
+    pub k_sg: f64,
+    /// 一次侧对流换热系数(kW/(m²·K))
+    pub alpha_1: f64,
+    /// 传热管内冷却剂流速(m/s)
+    pub w_c: f64,
+    /// 总传热面积(m²)
+    pub f_sg: f64,
+    /// 每台传热面积(m²)
+    pub f_sg1: f64,
+    /// 每台传热管数，向上取整
+    pub n_tsg: f64,
+}
+
+/// 蒸汽发生器热力设计
+///
+/// # Arguments
+///
+/// * `q_r` - 反应堆热功率(MW)
+///
+/// * `g_c` - 一回路冷却剂总质量流量(kg/s)
+///
+/// * `t_co`、`t_ci` - 反应堆出口、进口冷却剂温度(℃)
+///
+/// * `dt_m` - 一、二次侧对数平均温差(℃)
+pub fn size(
+    params: &CalcInputParameters,
+    q_r: f64,
+    g_c: f64,
+    t_co: f64,
+    t_ci: f64,
+    dt_m: f64,
+) -> Sizing {
+    let p_c = params.p_c;
+    let g_csg = g_c / params.n_sg;
+    let q_sg = params.n_1 * q_r * 1000.0; // 传给二次侧的热量(kW)
+
+    // 冷却剂平均温度下的物性
+    let t_c = (t_co + t_ci) / 2.0;
+    let rho = pt(p_c, t_c, OD);
+    let mu = pt(p_c, t_c, ODV);
+    let lambda = pt(p_c, t_c, OTC);
+    let pr = pt(p_c, t_c, OCP) * 1000.0 * mu / lambda;
+
+    let d_o = params.d_sgo / 1000.0;
+    let d_i = d_o - 2.0 * params.delta_sg / 1000.0;
+    let tube_area = PI * d_o * params.l_sg; // 每根传热管外表面积
+    let flow_area = PI * d_i * d_i / 4.0; // 每根传热管流通面积
+    // 管壁导热热阻(m²·K/kW)，以外表面为基准
+    let r_wall = d_o * (d_o / d_i).ln() / (2.0 * params.lambda_sg) * 1000.0;
+
+    let given = params.k_sg > 0.0;
+    let mut k_sg = if given {
+        params.k_sg
+    } else {
+        INITIAL_COEFFICIENT
+    };
+    let mut iteration = 0;
+    loop {
+        iteration += 1;
+        let f_sg = q_sg / (k_sg * dt_m);
+        let n_t = f_sg / (params.n_sg * tube_area);
+        let w_c = g_csg / (rho * n_t * flow_area);
+        let re = rho * w_c * d_i / mu;
+        let alpha_1 = 0.023 * re.powf(0.8) * pr.powf(0.4) * lambda / d_i / 1000.0;
+        let k_new = 1.0 / (d_o / (d_i * alpha_1) + r_wall + params.r_sg + 1.0 / params.alpha_2);
+        if given || (k_new - k_sg).abs() < TOLERANCE * k_sg || iteration >= MAX_ITERATIONS {
+            return Sizing {
+                k_sg,
+                alpha_1,
+                w_c,
+                f_sg,
+                f_sg1: f_sg / params.n_sg,
+                n_tsg: n_t.ceil(),
+            };
+        }
+        k_sg = k_new;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primary_loop::primary_loop;
+
+    fn reference() -> (CalcInputParameters, f64, f64, f64) {
+        let params = CalcInputParameters::from_default();
+        let t_co = px(params.p_c, 0.0, OT) - params.dt_sub;
+        let t_ci = t_co - params.dt_c;
+        let t_s = px(params.p_s, 1.0, OT);
+        let dt_m = (t_co - t_ci) / ((t_co - t_s) / (t_ci - t_s)).ln();
+        (params, t_co, t_ci, dt_m)
+    }
+
+    #[test]
+    fn test_geometry() {
+        let (params, t_co, t_ci, dt_m) = reference();
+        let g_c = primary_loop(&params, 3125.0, t_co, t_ci).g_c;
+        let sizing = size(&params, 3125.0, g_c, t_co, t_ci, dt_m);
+        // 百万千瓦级三环路机组：每台数千根传热管
+        assert!((3000.0..8000.0).contains(&sizing.n_tsg), "{sizing:?}");
+        assert!((4.0..9.0).contains(&sizing.k_sg), "{sizing:?}");
+        assert!((3.0..10.0).contains(&sizing.w_c), "{sizing:?}");
+        assert!((sizing.f_sg - params.n_1 * 3125.0 * 1000.0 / (sizing.k_sg * dt_m)).abs() < 1e-6);
+        assert!((sizing.f_sg1 * params.n_sg - sizing.f_sg).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_given_coefficient() {
+        let (params, t_co, t_ci, dt_m) = reference();
+        let params = CalcInputParameters {
+            k_sg: 5.0,
+            ..params
+        };
+        let g_c = primary_loop(&params, 3125.0, t_co, t_ci).g_c;
+        let sizing = size(&params, 3125.0, g_c, t_co, t_ci, dt_m);
+        assert_eq!(sizing.k_sg, 5.0);
+        assert!((sizing.f_sg - params.n_1 * 3125.0 * 1000.0 / (5.0 * dt_m)).abs() < 1e-6);
+    }
+}
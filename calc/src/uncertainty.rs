@@ -0,0 +1,505 @@
+//! 不确定性分析
+//!
+//! 为若干输入参数指定概率分布(均匀、正态或三角分布)，按给定种子抽取N组样本逐一计算，
+//! 统计核电厂效率`eta_enpp`、反应堆热功率`q_r`与蒸汽产量`d_s`的均值、标准差、百分位数及直方图。
+//! 全部样本在计算前按固定顺序抽取，同一种子与输入得到相同的结果。
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::Calculator;
+use crate::export::csv::line;
+use crate::fields::{FieldMeta, INPUT_FIELDS, RESULT1_COLUMNS, input_field, with_input_values};
+use crate::parameters::CalcInputParameters;
+use crate::units::UnitSystem;
+
+/// 默认样本数
+pub const DEFAULT_SAMPLES: usize = 1000;
+/// 默认随机数种子
+pub const DEFAULT_SEED: u64 = 42;
+/// 直方图的分组数
+pub const HISTOGRAM_BINS: usize = 20;
+/// 报告的百分位数
+pub const PERCENTILES: [f64; 5] = [5.0, 25.0, 50.0, 75.0, 95.0];
+/// 统计的输出字段
+pub const OUTPUT_KEYS: [&str; 3] = ["eta_enpp", "q_r", "d_s"];
+
+/// 输入参数的概率分布，数值为内部单位
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Distribution {
+    Uniform { lower: f64, upper: f64 },
+    Normal { mean: f64, std_dev: f64 },
+    Triangular { lower: f64, mode: f64, upper: f64 },
+}
+
+impl Distribution {
+    /// 检查分布参数是否有效
+    pub fn validate(&self) -> Result<(), String> {
+        let valid = match *self {
+            Self::Uniform { lower, upper } => lower < upper,
+            Self::Normal { mean, std_dev } => mean.is_finite() && std_dev >= 0.0,
+            Self::Triangular { lower, mode, upper } => {
+                lower < upper && (lower..=upper).contains(&mode)
+            }
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("分布参数无效: {self}"))
+        }
+    }
+
+    /// 抽取一个样本
+    pub fn sample(&self, rng: &mut impl Rng) -> f64 {
+        let u: f64 = rng.random();
+        match *self {
+            Self::Uniform { lower, upper } => lower + u * (upper - lower),
+            Self::Normal { mean, std_dev } => {
+                // Box–Muller变换，1-u避免对0取对数
+                let v: f64 = rng.random();
+                let z = (-2.0 * (1.0 - u).ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos();
+                mean + std_dev * z
+            }
+            Self::Triangular { lower, mode, upper } => {
+                // 逆分布函数
+                let split = (mode - lower) / (upper - lower);
+                if u < split {
+                    lower + (u * (upper - lower) * (mode - lower)).sqrt()
+                } else {
+                    upper - ((1.0 - u) * (upper - lower) * (upper - mode)).sqrt()
+                }
+            }
+        }
+    }
+}
+
+impl Display for Distribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Uniform { lower, upper } => write!(f, "均匀分布 U({lower}, {upper})"),
+            Self::Normal { mean, std_dev } => write!(f, "正态分布 N({mean}, {std_dev}²)"),
+            Self::Triangular { lower, mode, upper } => {
+                write!(f, "三角分布 T({lower}, {mode}, {upper})")
+            }
+        }
+    }
+}
+
+/// 带概率分布的输入参数
+#[derive(Debug, Clone, Copy)]
+pub struct UncertainInput {
+    pub field: &'static FieldMeta,
+    pub distribution: Distribution,
+}
+
+/// 可指定概率分布的输入字段：迭代初值、整数级数等不参与抽样
+pub fn candidate_fields() -> impl Iterator<Item = &'static FieldMeta> {
+    INPUT_FIELDS
+        .iter()
+        .filter(|field| field.kind.skip_reason().is_none())
+}
+
+/// 默认的不确定输入：效率与压损在推荐范围内或名义值附近变化，冷却水温度按正态分布
+pub fn default_inputs(params: &CalcInputParameters) -> Vec<UncertainInput> {
+    let triangular = |value: f64, relative: f64| Distribution::Triangular {
+        lower: value * (1.0 - relative),
+        mode: value,
+        upper: value * (1.0 + relative),
+    };
+    [
+        ("n_hi", triangular(params.n_hi, 0.02)),
+        ("n_li", triangular(params.n_li, 0.02)),
+        (
+            "n_h",
+            Distribution::Uniform {
+                lower: 0.97,
+                upper: 0.99,
+            },
+        ),
+        (
+            "dp_ej",
+            Distribution::Uniform {
+                lower: 0.03,
+                upper: 0.05,
+            },
+        ),
+        (
+            "t_sw1",
+            Distribution::Normal {
+                mean: params.t_sw1,
+                std_dev: 2.0,
+            },
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(key, distribution)| {
+        Some(UncertainInput {
+            field: input_field(key)?,
+            distribution,
+        })
+    })
+    .collect()
+}
+
+/// 直方图：自`lower`起每组宽`width`
+#[derive(Debug, Clone, Default)]
+pub struct Histogram {
+    pub lower: f64,
+    pub width: f64,
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    fn new(sorted: &[f64]) -> Self {
+        let (Some(&lower), Some(&upper)) = (sorted.first(), sorted.last()) else {
+            return Self::default();
+        };
+        let width = (upper - lower) / HISTOGRAM_BINS as f64;
+        let mut counts = vec![0; HISTOGRAM_BINS];
+        for value in sorted {
+            let bin = if width > 0.0 {
+                (((value - lower) / width) as usize).min(HISTOGRAM_BINS - 1)
+            } else {
+                0
+            };
+            counts[bin] += 1;
+        }
+        Self {
+            lower,
+            width,
+            counts,
+        }
+    }
+
+    /// 第`i`组的下界与上界
+    pub fn bin(&self, i: usize) -> (f64, f64) {
+        let lower = self.lower + i as f64 * self.width;
+        (lower, lower + self.width)
+    }
+}
+
+/// 一个输出字段的统计结果(内部单位)
+#[derive(Debug, Clone)]
+pub struct Statistics {
+    pub field: &'static FieldMeta,
+    pub mean: f64,
+    /// 样本标准差
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    /// 与`PERCENTILES`对应的百分位数
+    pub percentiles: Vec<f64>,
+    pub histogram: Histogram,
+}
+
+impl Statistics {
+    fn new(field: &'static FieldMeta, mut values: Vec<f64>) -> Self {
+        values.sort_by(f64::total_cmp);
+        let n = values.len();
+        let mean = values.iter().sum::<f64>() / n as f64;
+        let std_dev = if n > 1 {
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        // 线性插值
+        let percentile = |p: f64| {
+            let rank = p / 100.0 * (n - 1) as f64;
+            let (i, fraction) = (rank.floor() as usize, rank.fract());
+            let next = values[(i + 1).min(n - 1)];
+            values[i] + fraction * (next - values[i])
+        };
+        Self {
+            field,
+            mean,
+            std_dev,
+            min: values[0],
+            max: values[n - 1],
+            percentiles: PERCENTILES.iter().map(|&p| percentile(p)).collect(),
+            histogram: Histogram::new(&values),
+        }
+    }
+}
+
+/// 不确定性分析结果
+#[derive(Debug, Clone, Default)]
+pub struct UncertaintyReport {
+    pub seed: u64,
+    /// 抽取的样本数
+    pub samples: usize,
+    /// 计算失败的样本数
+    pub failures: usize,
+    /// 各失败原因及次数
+    pub failure_reasons: Vec<(String, usize)>,
+    /// 按`OUTPUT_KEYS`顺序的统计结果，全部样本失败时为空
+    pub outputs: Vec<Statistics>,
+}
+
+/// 抽取样本并逐一计算
+pub fn propagate(
+    params: &CalcInputParameters,
+    inputs: &[UncertainInput],
+    samples: usize,
+    seed: u64,
+) -> Result<UncertaintyReport, String> {
+    if inputs.is_empty() {
+        return Err(String::from("未指定不确定输入参数"));
+    }
+    if samples == 0 {
+        return Err(String::from("样本数应大于0"));
+    }
+    for input in inputs {
+        input
+            .distribution
+            .validate()
+            .map_err(|error| format!("{}({}): {error}", input.field.label, input.field.key))?;
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let draws: Vec<Vec<(&str, f64)>> = (0..samples)
+        .map(|_| {
+            inputs
+                .iter()
+                .map(|input| (input.field.key, input.distribution.sample(&mut rng)))
+                .collect()
+        })
+        .collect();
+
+    let mut values = vec![Vec::with_capacity(samples); OUTPUT_KEYS.len()];
+    let mut reasons = BTreeMap::new();
+    for draw in draws {
+        match with_input_values(params, &draw).and_then(Calculator::evaluate) {
+            Ok(outputs) => {
+                for (values, value) in
+                    values
+                        .iter_mut()
+                        .zip([outputs.eta_enpp, outputs.q_r, outputs.d_s])
+                {
+                    values.push(value);
+                }
+            }
+            Err(error) => *reasons.entry(error).or_insert(0) += 1,
+        }
+    }
+
+    let failures = reasons.values().sum();
+    let outputs = if failures == samples {
+        Vec::new()
+    } else {
+        OUTPUT_KEYS
+            .iter()
+            .zip(values)
+            .filter_map(|(key, values)| {
+                let field = RESULT1_COLUMNS.iter().find(|field| field.key == *key)?;
+                Some(Statistics::new(field, values))
+            })
+            .collect()
+    };
+    Ok(UncertaintyReport {
+        seed,
+        samples,
+        failures,
+        failure_reasons: reasons.into_iter().collect(),
+        outputs,
+    })
+}
+
+impl UncertaintyReport {
+    fn summary_headers() -> Vec<String> {
+        let mut headers: Vec<String> =
+            ["字段", "参数", "单位", "均值", "标准差", "最小值", "最大值"]
+                .map(String::from)
+                .to_vec();
+        headers.extend(PERCENTILES.iter().map(|p| format!("P{p}")));
+        headers
+    }
+
+    fn summary_cells(stats: &Statistics, units: &UnitSystem) -> Vec<String> {
+        let unit = stats.field.unit;
+        // 标准差为差值，按显示单位换算时扣除零点偏移
+        let std_dev =
+            units.to_display(unit, stats.mean + stats.std_dev) - units.to_display(unit, stats.mean);
+        let mut cells = vec![
+            stats.field.key.to_string(),
+            format!("{} ({})", stats.field.label, stats.field.symbol),
+            units.label(unit).to_string(),
+            units.format(unit, stats.mean),
+            format!("{std_dev:.6}"),
+            units.format(unit, stats.min),
+            units.format(unit, stats.max),
+        ];
+        cells.extend(stats.percentiles.iter().map(|&p| units.format(unit, p)));
+        cells
+    }
+
+    /// 生成Markdown格式的报告，附文本形式的直方图
+    pub fn to_markdown(&self, units: &UnitSystem) -> String {
+        let table_row = |cells: &[String]| format!("| {} |\n", cells.join(" | "));
+        let mut markdown = String::from("# 不确定性分析\n\n");
+        markdown.push_str(&format!(
+            "种子 {}，样本数 {}，计算失败 {} 个。\n\n",
+            self.seed, self.samples, self.failures
+        ));
+        for (reason, count) in &self.failure_reasons {
+            markdown.push_str(&format!("- {reason}: {count} 个\n"));
+        }
+        if !self.failure_reasons.is_empty() {
+            markdown.push('\n');
+        }
+        if self.outputs.is_empty() {
+            markdown.push_str("全部样本计算失败，没有统计结果。\n");
+            return markdown;
+        }
+
+        markdown.push_str("## 统计结果\n\n");
+        let headers = Self::summary_headers();
+        markdown.push_str(&table_row(&headers));
+        markdown.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+        for stats in &self.outputs {
+            markdown.push_str(&table_row(&Self::summary_cells(stats, units)));
+        }
+
+        for stats in &self.outputs {
+            let unit = stats.field.unit;
+            markdown.push_str(&format!(
+                "\n## {} ({}) 直方图\n\n```\n",
+                stats.field.label, stats.field.symbol
+            ));
+            let max = stats.histogram.counts.iter().copied().max().unwrap_or(0);
+            for (i, &count) in stats.histogram.counts.iter().enumerate() {
+                let (lower, upper) = stats.histogram.bin(i);
+                let width = (count * 40).checked_div(max).unwrap_or(0);
+                markdown.push_str(&format!(
+                    "{:>12} ~ {:<12} {:<40} {count}\n",
+                    units.format(unit, lower),
+                    units.format(unit, upper),
+                    "█".repeat(width)
+                ));
+            }
+            markdown.push_str("```\n");
+        }
+        markdown
+    }
+
+    /// 生成CSV/TSV格式的统计表，其后为各字段的直方图
+    pub fn to_csv(&self, delimiter: char, units: &UnitSystem) -> String {
+        let mut content = line(&Self::summary_headers(), delimiter);
+        for stats in &self.outputs {
+            content.push_str(&line(&Self::summary_cells(stats, units), delimiter));
+        }
+        content.push('\n');
+        content.push_str(&line(
+            &["字段", "下界", "上界", "样本数"].map(String::from),
+            delimiter,
+        ));
+        for stats in &self.outputs {
+            let unit = stats.field.unit;
+            for (i, count) in stats.histogram.counts.iter().enumerate() {
+                let (lower, upper) = stats.histogram.bin(i);
+                content.push_str(&line(
+                    &[
+                        stats.field.key.to_string(),
+                        units.format(unit, lower),
+                        units.format(unit, upper),
+                        count.to_string(),
+                    ],
+                    delimiter,
+                ));
+            }
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribution_sample() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let triangular = Distribution::Triangular {
+            lower: 1.0,
+            mode: 2.0,
+            upper: 4.0,
+        };
+        let normal = Distribution::Normal {
+            mean: 10.0,
+            std_dev: 2.0,
+        };
+        let n = 20000;
+        let samples: Vec<_> = (0..n).map(|_| triangular.sample(&mut rng)).collect();
+        assert!(samples.iter().all(|v| (1.0..=4.0).contains(v)));
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        assert!((mean - 7.0 / 3.0).abs() < 0.05);
+        let stats = Statistics::new(
+            &RESULT1_COLUMNS[0],
+            (0..n).map(|_| normal.sample(&mut rng)).collect(),
+        );
+        assert!((stats.mean - 10.0).abs() < 0.1);
+        assert!((stats.std_dev - 2.0).abs() < 0.1);
+        assert_eq!(stats.histogram.counts.iter().sum::<usize>(), n);
+
+        assert!(
+            Distribution::Uniform {
+                lower: 1.0,
+                upper: 1.0
+            }
+            .validate()
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_propagate() {
+        let params = CalcInputParameters::from_default();
+        let inputs = default_inputs(&params);
+        assert_eq!(inputs.len(), 5);
+        let report = propagate(&params, &inputs, 50, DEFAULT_SEED).unwrap();
+        assert_eq!(report.outputs.len(), OUTPUT_KEYS.len());
+        let eta = &report.outputs[0];
+        assert_eq!(eta.field.key, "eta_enpp");
+        assert!(eta.min <= eta.percentiles[0] && eta.percentiles[4] <= eta.max);
+        assert!(eta.std_dev > 0.0);
+        assert_eq!(
+            eta.histogram.counts.iter().sum::<usize>() + report.failures,
+            50
+        );
+
+        // 同一种子结果相同
+        let again = propagate(&params, &inputs, 50, DEFAULT_SEED).unwrap();
+        assert_eq!(again.outputs[1].mean, report.outputs[1].mean);
+        let other = propagate(&params, &inputs, 50, DEFAULT_SEED + 1).unwrap();
+        assert_ne!(other.outputs[1].mean, report.outputs[1].mean);
+
+        let units = UnitSystem::default();
+        assert!(report.to_markdown(&units).contains("## 统计结果"));
+        assert!(
+            report
+                .to_csv(',', &units)
+                .starts_with("字段,参数,单位,均值,标准差")
+        );
+    }
+
+    #[test]
+    fn test_failed_samples() {
+        // 干度超过1的样本计算失败或结果无效，计入失败数
+        let params = CalcInputParameters::from_default();
+        let inputs = [UncertainInput {
+            field: input_field("p_s").unwrap(),
+            distribution: Distribution::Uniform {
+                lower: -10.0,
+                upper: -5.0,
+            },
+        }];
+        let report = propagate(&params, &inputs, 5, DEFAULT_SEED).unwrap();
+        assert_eq!(report.failures, 5);
+        assert!(report.outputs.is_empty());
+        assert!(!report.failure_reasons.is_empty());
+        assert!(propagate(&params, &[], 5, DEFAULT_SEED).is_err());
+    }
+}
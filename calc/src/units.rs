@@ -0,0 +1,296 @@
+//! 物理量单位与单位制
+//!
+//! 计算过程中的数值始终以内部单位存储(压力MPa、温度℃、比焓kJ/kg、比例为小数)，
+//! 仅在输入、显示和导出时按所选单位制换算。
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// 字段的内部存储单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// 无量纲
+    Dimensionless,
+    /// 比例，以小数存储
+    Fraction,
+    /// 压力，MPa
+    MPa,
+    /// 温度，℃
+    Celsius,
+    /// 温差，℃
+    DeltaCelsius,
+    /// 比焓，kJ/kg
+    KjPerKg,
+    /// 比熵，kJ/(kg·K)
+    KjPerKgK,
+    /// 质量流量，kg/s
+    KgPerS,
+    /// 功率，MW
+    MW,
+    /// 功率，GW
+    GW,
+    /// 长度，mm
+    Millimeter,
+    /// 长度，m
+    Meter,
+    /// 面积，m²
+    SquareMeter,
+    /// 流速，m/s
+    MeterPerSecond,
+    /// 体积流量，m³/s
+    CubicMeterPerSecond,
+    /// 传热系数，kW/(m²·K)
+    KwPerM2K,
+    /// 导热系数，W/(m·K)
+    WPerMK,
+    /// 热阻，m²·K/kW
+    M2KPerKw,
+}
+
+/// 1 BTU/lb 对应的 kJ/kg
+const KJ_PER_KG_PER_BTU_PER_LB: f64 = 2.326;
+/// 1 BTU/(lb·°R) 对应的 kJ/(kg·K)
+const KJ_PER_KG_K_PER_BTU_PER_LB_R: f64 = 4.1868;
+
+/// 压力单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PressureUnit {
+    #[default]
+    MPa,
+    Bar,
+    KPa,
+}
+
+/// 温度单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Kelvin,
+}
+
+/// 比焓(比熵)单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EnthalpyUnit {
+    #[default]
+    KjPerKg,
+    BtuPerLb,
+}
+
+/// 比例的显示方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FractionUnit {
+    #[default]
+    Fraction,
+    Percent,
+}
+
+impl PressureUnit {
+    pub const ALL: [Self; 3] = [Self::MPa, Self::Bar, Self::KPa];
+}
+
+impl TemperatureUnit {
+    pub const ALL: [Self; 2] = [Self::Celsius, Self::Kelvin];
+}
+
+impl EnthalpyUnit {
+    pub const ALL: [Self; 2] = [Self::KjPerKg, Self::BtuPerLb];
+}
+
+impl FractionUnit {
+    pub const ALL: [Self; 2] = [Self::Fraction, Self::Percent];
+}
+
+impl Display for PressureUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::MPa => "MPa",
+            Self::Bar => "bar",
+            Self::KPa => "kPa",
+        })
+    }
+}
+
+impl Display for TemperatureUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Celsius => "℃",
+            Self::Kelvin => "K",
+        })
+    }
+}
+
+impl Display for EnthalpyUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::KjPerKg => "kJ/kg",
+            Self::BtuPerLb => "BTU/lb",
+        })
+    }
+}
+
+impl Display for FractionUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Fraction => "小数",
+            Self::Percent => "%",
+        })
+    }
+}
+
+/// 显示单位制
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UnitSystem {
+    pub pressure: PressureUnit,
+    pub temperature: TemperatureUnit,
+    pub enthalpy: EnthalpyUnit,
+    pub fraction: FractionUnit,
+}
+
+impl UnitSystem {
+    /// 比例以百分数显示，其余为内部单位，与界面原有的输入习惯一致
+    pub fn percent() -> Self {
+        Self {
+            fraction: FractionUnit::Percent,
+            ..Self::default()
+        }
+    }
+
+    /// 单位符号，无量纲时为空
+    pub fn label(&self, unit: Unit) -> &'static str {
+        match unit {
+            Unit::Dimensionless => "",
+            Unit::Fraction => match self.fraction {
+                FractionUnit::Fraction => "",
+                FractionUnit::Percent => "%",
+            },
+            Unit::MPa => match self.pressure {
+                PressureUnit::MPa => "MPa",
+                PressureUnit::Bar => "bar",
+                PressureUnit::KPa => "kPa",
+            },
+            Unit::Celsius | Unit::DeltaCelsius => match self.temperature {
+                TemperatureUnit::Celsius => "℃",
+                TemperatureUnit::Kelvin => "K",
+            },
+            Unit::KjPerKg => match self.enthalpy {
+                EnthalpyUnit::KjPerKg => "kJ/kg",
+                EnthalpyUnit::BtuPerLb => "BTU/lb",
+            },
+            Unit::KjPerKgK => match self.enthalpy {
+                EnthalpyUnit::KjPerKg => "kJ/(kg·K)",
+                EnthalpyUnit::BtuPerLb => "BTU/(lb·°R)",
+            },
+            Unit::KgPerS => "kg/s",
+            Unit::MW => "MW",
+            Unit::GW => "GW",
+            Unit::Millimeter => "mm",
+            Unit::Meter => "m",
+            Unit::SquareMeter => "m²",
+            Unit::MeterPerSecond => "m/s",
+            Unit::CubicMeterPerSecond => "m³/s",
+            Unit::KwPerM2K => "kW/(m²·K)",
+            Unit::WPerMK => "W/(m·K)",
+            Unit::M2KPerKw => "m²·K/kW",
+        }
+    }
+
+    /// 由内部单位换算为显示单位
+    pub fn to_display(&self, unit: Unit, value: f64) -> f64 {
+        match unit {
+            Unit::Fraction if self.fraction == FractionUnit::Percent => value * 100.0,
+            Unit::MPa => match self.pressure {
+                PressureUnit::MPa => value,
+                PressureUnit::Bar => value * 10.0,
+                PressureUnit::KPa => value * 1000.0,
+            },
+            Unit::Celsius if self.temperature == TemperatureUnit::Kelvin => value + 273.15,
+            Unit::KjPerKg if self.enthalpy == EnthalpyUnit::BtuPerLb => {
+                value / KJ_PER_KG_PER_BTU_PER_LB
+            }
+            Unit::KjPerKgK if self.enthalpy == EnthalpyUnit::BtuPerLb => {
+                value / KJ_PER_KG_K_PER_BTU_PER_LB_R
+            }
+            _ => value,
+        }
+    }
+
+    /// 换算为显示单位并格式化，去掉多余的0
+    pub fn format(&self, unit: Unit, value: f64) -> String {
+        let text = format!("{:.6}", self.to_display(unit, value));
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+
+    /// 由显示单位换算为内部单位
+    pub fn from_display(&self, unit: Unit, value: f64) -> f64 {
+        match unit {
+            Unit::Fraction if self.fraction == FractionUnit::Percent => value / 100.0,
+            Unit::MPa => match self.pressure {
+                PressureUnit::MPa => value,
+                PressureUnit::Bar => value / 10.0,
+                PressureUnit::KPa => value / 1000.0,
+            },
+            Unit::Celsius if self.temperature == TemperatureUnit::Kelvin => value - 273.15,
+            Unit::KjPerKg if self.enthalpy == EnthalpyUnit::BtuPerLb => {
+                value * KJ_PER_KG_PER_BTU_PER_LB
+            }
+            Unit::KjPerKgK if self.enthalpy == EnthalpyUnit::BtuPerLb => {
+                value * KJ_PER_KG_K_PER_BTU_PER_LB_R
+            }
+            _ => value,
+        }
+    }
+}
+
+impl FromStr for UnitSystem {
+    type Err = String;
+
+    /// 由逗号分隔的单位列表解析，如`bar,K,BTU/lb,%`，未列出的单位取默认值
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut units = Self::default();
+        for token in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            match token.to_ascii_lowercase().as_str() {
+                "mpa" => units.pressure = PressureUnit::MPa,
+                "bar" => units.pressure = PressureUnit::Bar,
+                "kpa" => units.pressure = PressureUnit::KPa,
+                "c" | "℃" | "°c" => units.temperature = TemperatureUnit::Celsius,
+                "k" => units.temperature = TemperatureUnit::Kelvin,
+                "kj/kg" => units.enthalpy = EnthalpyUnit::KjPerKg,
+                "btu/lb" => units.enthalpy = EnthalpyUnit::BtuPerLb,
+                "%" | "percent" => units.fraction = FractionUnit::Percent,
+                "fraction" => units.fraction = FractionUnit::Fraction,
+                _ => return Err(format!("未知单位: {token}")),
+            }
+        }
+        Ok(units)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let units: UnitSystem = "bar,K,BTU/lb,%".parse().unwrap();
+        for unit in [
+            Unit::Fraction,
+            Unit::MPa,
+            Unit::Celsius,
+            Unit::DeltaCelsius,
+            Unit::KjPerKg,
+            Unit::KjPerKgK,
+        ] {
+            let value = units.from_display(unit, units.to_display(unit, 0.75));
+            assert!((value - 0.75).abs() < 1e-12);
+        }
+        assert_eq!(units.to_display(Unit::MPa, 6.0), 60.0);
+        assert_eq!(units.to_display(Unit::DeltaCelsius, 5.0), 5.0);
+        assert_eq!(units.label(Unit::DeltaCelsius), "K");
+        assert_eq!(units.format(Unit::Fraction, 0.8207), "82.07");
+        assert_eq!(units.format(Unit::Celsius, 24.0), "297.15");
+        assert!("psi".parse::<UnitSystem>().is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! 命令行接口
+//!
+//! 不带参数启动时打开图形界面，带参数时在命令行中完成计算与导出。
+use calc::{
+    import::import_params_from_file,
+    parameters::CalcInputParameters,
+    sensitivity::{analyze_sensitivity, DEFAULT_STEP},
+    units::UnitSystem,
+    Calculator,
+};
+
+const USAGE: &str = "用法: NPP-THC <命令> [选项]
+
+命令:
+    export-csv              计算并将结果表格分别导出为CSV文件
+    sensitivity             对输入参数做灵敏度分析，导出sensitivity.md及CSV文件
+    help                    显示帮助信息
+
+选项:
+    -p, --params <文件>     输入参数文件(JSON/TOML/YAML/CSV)，缺省时使用默认参数
+    -o, --output <目录>     输出目录，缺省为当前目录
+        --tsv               以制表符分隔，导出为TSV文件
+    -u, --units <单位>      显示单位，逗号分隔，如 bar,K,BTU/lb,%";
+
+/// 命令行选项
+struct Options {
+    params: Option<String>,
+    output: String,
+    delimiter: char,
+    units: UnitSystem,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Self {
+            params: None,
+            output: String::from("."),
+            delimiter: ',',
+            units: UnitSystem::default(),
+        };
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-p" | "--params" => {
+                    options.params = Some(iter.next().ok_or("缺少参数文件路径")?.clone())
+                }
+                "-o" | "--output" => options.output = iter.next().ok_or("缺少输出目录")?.clone(),
+                "--tsv" => options.delimiter = '\t',
+                "-u" | "--units" => options.units = iter.next().ok_or("缺少单位")?.parse()?,
+                _ => return Err(format!("未知选项: {arg}")),
+            }
+        }
+        Ok(options)
+    }
+
+    /// 读取输入参数，未指定文件时使用默认参数
+    fn load_params(&self) -> Result<CalcInputParameters, String> {
+        match &self.params {
+            Some(path) => {
+                let imported = import_params_from_file(path)
+                    .map_err(|error| format!("读取参数文件失败: {error}"))?;
+                if let Some(summary) = imported.summary() {
+                    eprintln!("警告: {summary}");
+                }
+                Ok(imported.params)
+            }
+            None => Ok(CalcInputParameters::from_default()),
+        }
+    }
+}
+
+/// 解析并执行命令行参数，没有参数时返回`None`，否则返回进程退出码
+pub fn run() -> Option<i32> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (command, rest) = args.split_first()?;
+    let result = match command.as_str() {
+        "export-csv" => Options::parse(rest).and_then(|options| export_csv(&options)),
+        "sensitivity" => Options::parse(rest).and_then(|options| sensitivity(&options)),
+        "help" | "-h" | "--help" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(format!("未知命令: {command}")),
+    };
+    match result {
+        Ok(_) => Some(0),
+        Err(error) => {
+            eprintln!("错误: {error}\n\n{USAGE}");
+            Some(1)
+        }
+    }
+}
+
+fn export_csv(options: &Options) -> Result<(), String> {
+    let params = options.load_params()?;
+    for warning in calc::fields::check_input_ranges(&params) {
+        eprintln!("警告: {}", warning.message(&options.units));
+    }
+    let mut calculator = Calculator::new(params);
+    calculator
+        .calculate()
+        .map_err(|error| format!("计算失败: {error}"))?;
+    calculator
+        .save_result_tables_to_files(&options.output, options.delimiter, &options.units)
+        .map_err(|error| format!("导出表格失败: {error}"))?;
+    println!("已导出计算结果表格至 {}", options.output);
+    Ok(())
+}
+
+fn sensitivity(options: &Options) -> Result<(), String> {
+    let params = options.load_params()?;
+    let report = analyze_sensitivity(&params, DEFAULT_STEP)?;
+    let extension = if options.delimiter == '\t' {
+        "tsv"
+    } else {
+        "csv"
+    };
+    let write = |name: String, content: String| {
+        std::fs::write(format!("{}/{name}", options.output), content)
+            .map_err(|error| format!("导出灵敏度分析失败: {error}"))
+    };
+    write(
+        String::from("sensitivity.md"),
+        report.to_markdown(&options.units),
+    )?;
+    write(
+        format!("sensitivity.{extension}"),
+        report.to_csv(options.delimiter, &options.units),
+    )?;
+    for row in report.rows.iter().take(5) {
+        if let Some(derivative) = row.derivative() {
+            println!(
+                "{} ({}): η_eNPP弹性 {:+.4}",
+                row.field.label, row.field.symbol, derivative.elasticity_eta
+            );
+        }
+    }
+    println!("已导出灵敏度分析至 {}", options.output);
+    Ok(())
+}
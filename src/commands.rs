@@ -0,0 +1,229 @@
+//! 快捷键与命令面板
+use calc::{
+    presets::Preset,
+    units::{EnthalpyUnit, FractionUnit, PressureUnit, TemperatureUnit, UnitSystem},
+};
+use iced::{
+    keyboard::{self, key::Named, Key},
+    widget::{button, column, container, row, scrollable, text, text_input},
+    Alignment, Element, Length, Theme,
+};
+
+use crate::{npp_tabs::input::InputTabMessage, Message, TabId};
+
+/// 命令面板中搜索框的ID，打开面板时聚焦
+pub const PALETTE_INPUT_ID: &str = "command-palette-input";
+
+/// 命令面板中的一项
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub name: String,
+    /// 快捷键说明，无快捷键时为空
+    pub shortcut: &'static str,
+    pub message: Message,
+}
+
+impl Command {
+    fn new(name: impl Into<String>, shortcut: &'static str, message: Message) -> Self {
+        Self {
+            name: name.into(),
+            shortcut,
+            message,
+        }
+    }
+}
+
+/// 快捷键对应的消息，与`commands`中的快捷键说明保持一致
+pub fn shortcut(key: Key, modifiers: keyboard::Modifiers) -> Option<Message> {
+    let command = modifiers.command();
+    let shift = modifiers.shift();
+    match key.as_ref() {
+        Key::Named(Named::F5) => Some(Message::Calculate),
+        Key::Named(Named::Escape) => Some(Message::CloseDialogs),
+        Key::Character(c) if command => match c.to_lowercase().as_str() {
+            "z" if shift => Some(Message::InputTab(InputTabMessage::Redo)),
+            "z" => Some(Message::InputTab(InputTabMessage::Undo)),
+            "s" if shift => Some(Message::SaveRun),
+            "s" => Some(Message::SaveInputParams),
+            "o" => Some(Message::LoadParamsFromFile),
+            "p" if shift => Some(Message::OpenPalette),
+            "h" => Some(Message::ToggleHistory),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// 所有可执行的命令
+pub fn commands(presets: &[Preset], units: UnitSystem) -> Vec<Command> {
+    let mut commands = vec![
+        Command::new("文件: 加载参数", "Ctrl+O", Message::LoadParamsFromFile),
+        Command::new("文件: 保存输入参数", "Ctrl+S", Message::SaveInputParams),
+        Command::new("文件: 选择输出目录", "", Message::SelectOutputDir),
+        Command::new("文件: 导出LaTeX计算书", "", Message::SaveReportTex),
+        Command::new("文件: 导出HTML报告", "", Message::SaveReportHtml),
+        Command::new("文件: 导出CSV表格", "", Message::SaveResultCsv),
+        Command::new("文件: 导出Excel工作簿", "", Message::SaveWorkbook),
+        Command::new(
+            "编辑: 撤销",
+            "Ctrl+Z",
+            Message::InputTab(InputTabMessage::Undo),
+        ),
+        Command::new(
+            "编辑: 重做",
+            "Ctrl+Shift+Z",
+            Message::InputTab(InputTabMessage::Redo),
+        ),
+        Command::new("计算: 开始计算", "F5", Message::Calculate),
+        Command::new("计算: 加载默认参数", "", Message::LoadDefaultParams),
+        Command::new("计算: 清空输入参数", "", Message::ClearInputParams),
+        Command::new("计算: 保存计算结果", "", Message::SaveResult),
+        Command::new("计算: 保存计算代码", "", Message::SaveCalcCode),
+        Command::new("计算: 保存计算记录", "Ctrl+Shift+S", Message::SaveRun),
+        Command::new("计算: 显示/隐藏计算历史", "Ctrl+H", Message::ToggleHistory),
+        Command::new("计算: 保存历史到磁盘", "", Message::ToggleHistoryOnDisk),
+        Command::new("对比: 添加当前结果", "", Message::CompareAddCurrent),
+        Command::new("对比: 从文件添加", "", Message::CompareAddFromFile),
+        Command::new("对比: 给水焓升分配方式", "", Message::CompareAllocations),
+        Command::new("对比: 导出Markdown", "", Message::SaveCompareMarkdown),
+        Command::new("对比: 导出CSV", "", Message::SaveCompareCsv),
+        Command::new("灵敏度: 分析当前参数", "", Message::RunSensitivity),
+        Command::new("灵敏度: 导出Markdown", "", Message::SaveSensitivityMarkdown),
+        Command::new("灵敏度: 导出CSV", "", Message::SaveSensitivityCsv),
+        Command::new("优化: 开始优化", "", Message::RunOptimization),
+        Command::new("优化: 应用最优参数", "", Message::ApplyOptimum),
+        Command::new("优化: 导出迭代过程CSV", "", Message::SaveOptimizeTrace),
+        Command::new("不确定性: 开始分析", "", Message::RunUncertainty),
+        Command::new(
+            "不确定性: 导出Markdown",
+            "",
+            Message::SaveUncertaintyMarkdown,
+        ),
+        Command::new("不确定性: 导出CSV", "", Message::SaveUncertaintyCsv),
+        Command::new("变工况: 开始计算", "", Message::RunOffDesign),
+        Command::new("变工况: 导出Markdown", "", Message::SaveOffDesignMarkdown),
+        Command::new("变工况: 导出CSV", "", Message::SaveOffDesignCsv),
+        Command::new("季节性: 加载温度曲线", "", Message::LoadSeasonalProfile),
+        Command::new("季节性: 开始计算", "", Message::RunSeasonal),
+        Command::new("季节性: 导出Markdown", "", Message::SaveSeasonalMarkdown),
+        Command::new("季节性: 导出CSV", "", Message::SaveSeasonalCsv),
+        Command::new("冷凝器: 开始计算", "", Message::RunCondenser),
+        Command::new("冷凝器: 导出Markdown", "", Message::SaveCondenserMarkdown),
+        Command::new("预设: 管理预设", "", Message::OpenPresetDialog),
+    ];
+    commands.extend(presets.iter().enumerate().map(|(index, preset)| {
+        Command::new(
+            format!("预设: 加载“{}”", preset.name),
+            "",
+            Message::LoadPreset(index),
+        )
+    }));
+    for (name, tab) in [
+        ("输入参数", TabId::Input),
+        ("计算结果", TabId::Result),
+        ("计算代码", TabId::CalcCode),
+        ("结果对比", TabId::Compare),
+        ("灵敏度分析", TabId::Sensitivity),
+        ("参数优化", TabId::Optimize),
+        ("不确定性分析", TabId::Uncertainty),
+        ("变工况", TabId::OffDesign),
+        ("季节性分析", TabId::Seasonal),
+        ("冷凝器", TabId::Condenser),
+    ] {
+        commands.push(Command::new(
+            format!("选项卡: {name}"),
+            "",
+            Message::TabSelected(tab),
+        ));
+    }
+    for theme in [
+        Theme::CatppuccinLatte,
+        Theme::TokyoNightLight,
+        Theme::CatppuccinMocha,
+        Theme::TokyoNightStorm,
+    ] {
+        commands.push(Command::new(
+            format!("主题: {theme}"),
+            "",
+            Message::ThemeSelect(theme),
+        ));
+    }
+    #[rustfmt::skip]
+    let unit_choices = [
+        ("压力 MPa", UnitSystem { pressure: PressureUnit::MPa, ..units }),
+        ("压力 bar", UnitSystem { pressure: PressureUnit::Bar, ..units }),
+        ("压力 kPa", UnitSystem { pressure: PressureUnit::KPa, ..units }),
+        ("温度 ℃", UnitSystem { temperature: TemperatureUnit::Celsius, ..units }),
+        ("温度 K", UnitSystem { temperature: TemperatureUnit::Kelvin, ..units }),
+        ("比焓 kJ/kg", UnitSystem { enthalpy: EnthalpyUnit::KjPerKg, ..units }),
+        ("比焓 BTU/lb", UnitSystem { enthalpy: EnthalpyUnit::BtuPerLb, ..units }),
+        ("比例 %", UnitSystem { fraction: FractionUnit::Percent, ..units }),
+        ("比例 小数", UnitSystem { fraction: FractionUnit::Fraction, ..units }),
+    ];
+    for (name, units) in unit_choices {
+        commands.push(Command::new(
+            format!("单位: {name}"),
+            "",
+            Message::UnitsSelect(units),
+        ));
+    }
+    commands.push(Command::new("帮助: 关于", "", Message::OpenHelpDialog));
+    commands.push(Command::new(
+        "帮助: 命令面板",
+        "Ctrl+Shift+P",
+        Message::OpenPalette,
+    ));
+    commands
+}
+
+/// 按关键字筛选命令，空格分隔的各关键字均需出现在名称或快捷键中，不区分大小写
+pub fn filter_commands(commands: Vec<Command>, query: &str) -> Vec<Command> {
+    let query = query.to_lowercase();
+    commands
+        .into_iter()
+        .filter(|command| {
+            let haystack = format!("{} {}", command.name, command.shortcut).to_lowercase();
+            query.split_whitespace().all(|word| haystack.contains(word))
+        })
+        .collect()
+}
+
+/// 命令面板：搜索框及匹配的命令列表，回车执行第一项
+pub fn command_palette<'a>(commands: Vec<Command>, query: &str) -> Element<'a, Message> {
+    let search = text_input("输入命令名称或快捷键搜索，回车执行第一项", query)
+        .id(text_input::Id::new(PALETTE_INPUT_ID))
+        .on_input(Message::PaletteQueryChanged)
+        .on_submit(Message::PaletteSubmit)
+        .width(Length::Fill);
+
+    let mut list = column![].spacing(2);
+    if commands.is_empty() {
+        list = list.push(text("没有匹配的命令"));
+    }
+    for command in commands {
+        let item = row![
+            text(command.name).width(Length::Fill),
+            text(command.shortcut).size(12),
+        ]
+        .align_y(Alignment::Center);
+        list = list.push(
+            button(item)
+                .style(button::text)
+                .width(Length::Fill)
+                .on_press(Message::RunCommand(Box::new(command.message))),
+        );
+    }
+
+    container(
+        column![
+            text("命令面板").size(20),
+            search,
+            scrollable(list).height(Length::Fixed(360.0))
+        ]
+        .spacing(10)
+        .padding(20),
+    )
+    .width(560)
+    .style(container::rounded_box)
+    .into()
+}
@@ -1,6 +1,10 @@
 #[derive(Debug, Clone)]
 pub enum Error {
     Io,
-    JsonParse,
+    Import(calc::import::ImportError),
+    /// 计算记录文件读取失败
+    RunFile(String),
+    /// 冷却水温度曲线文件读取失败
+    Profile(String),
     DialogClosed,
 }
@@ -12,20 +12,56 @@ pub async fn select_output_dir() -> Result<String, errors::Error> {
 }
 
 pub async fn load_input_params_from_file(
-) -> Result<Box<calc::parameters::CalcInputParameters>, errors::Error> {
+) -> Result<Box<calc::import::ImportedParams>, errors::Error> {
     let handle = AsyncFileDialog::new()
         .set_title("选择输入参数文件")
-        .add_filter("JSON", &["json"])
+        .add_filter("参数文件", &calc::import::ParamsFormat::EXTENSIONS)
         .pick_file()
         .await
         .ok_or(errors::Error::DialogClosed)?;
 
     let path = handle.path();
+    let format = calc::import::ParamsFormat::from_path(path).ok_or(errors::Error::Import(
+        calc::import::ImportError::UnknownFormat,
+    ))?;
 
     let contents = tokio::fs::read_to_string(path)
         .await
         .map_err(|_| errors::Error::Io)?;
-    let input_params = serde_json::from_str(&contents).map_err(|_| errors::Error::JsonParse)?;
+    let imported = calc::import::import_params(&contents, format).map_err(errors::Error::Import)?;
 
-    Ok(input_params)
+    Ok(Box::new(imported))
+}
+
+/// 选择一个或多个计算记录文件(run.json或results.json)并读取
+pub async fn load_runs_from_files() -> Result<Vec<calc::compare::CalcRun>, errors::Error> {
+    let handles = AsyncFileDialog::new()
+        .set_title("选择计算记录文件")
+        .add_filter("计算记录", &["json"])
+        .pick_files()
+        .await
+        .ok_or(errors::Error::DialogClosed)?;
+
+    handles
+        .iter()
+        .map(|handle| {
+            calc::compare::CalcRun::from_file(handle.path())
+                .map_err(|error| errors::Error::RunFile(error.to_string()))
+        })
+        .collect()
+}
+
+/// 选择冷却水温度曲线文件(csv或tsv)并读取
+pub async fn load_seasonal_profile() -> Result<Vec<calc::seasonal::ProfilePoint>, errors::Error> {
+    let handle = AsyncFileDialog::new()
+        .set_title("选择冷却水温度曲线文件")
+        .add_filter("温度曲线", &["csv", "tsv", "txt"])
+        .pick_file()
+        .await
+        .ok_or(errors::Error::DialogClosed)?;
+
+    let contents = tokio::fs::read_to_string(handle.path())
+        .await
+        .map_err(|error| errors::Error::Profile(error.to_string()))?;
+    calc::seasonal::parse_profile(&contents).map_err(errors::Error::Profile)
 }
@@ -1,14 +1,23 @@
-use crate::common::theme::background_button_style;
+use crate::common::theme::{background_button_style, MISANS_FONT};
 use iced::{
-    alignment, padding,
-    widget::{button, center, container, mouse_area, opaque, row, stack, text, text_input},
-    Element, Length, Renderer, Theme,
+    alignment, mouse, padding,
+    widget::{
+        button, canvas,
+        canvas::{Frame, Geometry, Path, Stroke},
+        center, container, mouse_area, opaque, pick_list, row, stack, text, text_input, Canvas,
+    },
+    Element, Length, Point, Rectangle, Renderer, Theme,
 };
 
+/// 折线图的尺寸与边距
+const CHART_WIDTH: f32 = 640.0;
+const CHART_HEIGHT: f32 = 360.0;
+const CHART_MARGIN: f32 = 56.0;
+
 pub fn input_field<'a, F, M>(
-    label: &'a str,
-    placeholder: &'a str,
-    value: &'a str,
+    label: impl text::IntoFragment<'a>,
+    placeholder: &str,
+    value: &str,
     on_input_message_creator: F,
 ) -> Element<'a, M>
 where
@@ -28,6 +37,32 @@ where
     .into()
 }
 
+/// 与`input_field`排版相同的下拉选择框，`selected`为`None`时显示`placeholder`
+pub fn choice_field<'a, T, F, M>(
+    label: impl text::IntoFragment<'a>,
+    placeholder: &str,
+    options: Vec<T>,
+    selected: Option<T>,
+    on_select_message_creator: F,
+) -> Element<'a, M>
+where
+    T: ToString + PartialEq + Clone + 'a,
+    F: Fn(T) -> M + 'a,
+    M: Clone + 'a,
+{
+    row![
+        container(text(label).align_x(alignment::Horizontal::Right))
+            .width(Length::Fixed(250.0))
+            .padding(padding::right(4)),
+        pick_list(options, selected, on_select_message_creator)
+            .placeholder(placeholder)
+            .width(Length::Fill)
+    ]
+    .spacing(10)
+    .align_y(alignment::Vertical::Center)
+    .into()
+}
+
 pub fn labeled_button<'a, M>(label: &'a str, msg: M) -> button::Button<'a, M, Theme, Renderer>
 where
     M: Clone + 'a,
@@ -51,3 +86,142 @@ where
     ]
     .into()
 }
+
+/// 折线图，数据点按横坐标排序后连线
+pub struct LineChart {
+    /// (横坐标, 纵坐标)，非有限值的点不绘制
+    pub points: Vec<(f64, f64)>,
+    pub x_label: String,
+    pub y_label: String,
+    /// 横坐标范围至少包含的区间
+    pub x_bounds: Option<(f64, f64)>,
+}
+
+/// 固定尺寸的折线图
+pub fn line_chart<'a, M: 'a>(chart: LineChart) -> Element<'a, M> {
+    Canvas::new(chart)
+        .width(Length::Fixed(CHART_WIDTH))
+        .height(Length::Fixed(CHART_HEIGHT))
+        .into()
+}
+
+impl<M> canvas::Program<M> for LineChart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let palette = theme.palette();
+        let finite: Vec<(f64, f64)> = self
+            .points
+            .iter()
+            .copied()
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .collect();
+        let range = |values: &mut dyn Iterator<Item = f64>| {
+            let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+                (min.min(v), max.max(v))
+            });
+            if !min.is_finite() || max <= min {
+                let center = if min.is_finite() { min } else { 0.0 };
+                (center - 1.0, center + 1.0)
+            } else {
+                let margin = 0.05 * (max - min);
+                (min - margin, max + margin)
+            }
+        };
+        let (x_min, x_max) = range(
+            &mut finite
+                .iter()
+                .map(|p| p.0)
+                .chain(self.x_bounds.into_iter().flat_map(|(min, max)| [min, max])),
+        );
+        let (y_min, y_max) = range(&mut finite.iter().map(|p| p.1));
+        let (width, height) = (bounds.width, bounds.height);
+        let to_point = |x: f64, y: f64| {
+            Point::new(
+                CHART_MARGIN
+                    + ((x - x_min) / (x_max - x_min)) as f32 * (width - 1.5 * CHART_MARGIN),
+                height
+                    - CHART_MARGIN
+                    - ((y - y_min) / (y_max - y_min)) as f32 * (height - 1.5 * CHART_MARGIN),
+            )
+        };
+        let label = |content: String, position: Point, horizontal| canvas::Text {
+            content,
+            position,
+            color: palette.text,
+            size: 12.0.into(),
+            font: MISANS_FONT,
+            horizontal_alignment: horizontal,
+            vertical_alignment: alignment::Vertical::Center,
+            shaping: iced::widget::text::Shaping::Advanced,
+            ..canvas::Text::default()
+        };
+
+        // 坐标轴与刻度
+        let origin = Point::new(CHART_MARGIN, height - CHART_MARGIN);
+        let axes = Path::new(|builder| {
+            builder.move_to(Point::new(origin.x, CHART_MARGIN / 2.0));
+            builder.line_to(origin);
+            builder.line_to(Point::new(width - CHART_MARGIN / 2.0, origin.y));
+        });
+        frame.stroke(
+            &axes,
+            Stroke::default().with_color(palette.text).with_width(1.0),
+        );
+        for i in 0..=5 {
+            let x = x_min + (x_max - x_min) * i as f64 / 5.0;
+            let y = y_min + (y_max - y_min) * i as f64 / 5.0;
+            frame.fill_text(label(
+                format!("{x:.1}"),
+                Point::new(to_point(x, y_min).x, origin.y + 12.0),
+                alignment::Horizontal::Center,
+            ));
+            frame.fill_text(label(
+                format!("{y:.4}"),
+                Point::new(origin.x - 4.0, to_point(x_min, y).y),
+                alignment::Horizontal::Right,
+            ));
+        }
+        frame.fill_text(label(
+            self.x_label.clone(),
+            Point::new(width / 2.0, height - 14.0),
+            alignment::Horizontal::Center,
+        ));
+        frame.fill_text(label(
+            self.y_label.clone(),
+            Point::new(origin.x, CHART_MARGIN / 4.0),
+            alignment::Horizontal::Center,
+        ));
+
+        // 按横坐标排序后连线
+        let mut sorted = finite;
+        sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let curve = Path::new(|builder| {
+            for (i, &(x, y)) in sorted.iter().enumerate() {
+                if i == 0 {
+                    builder.move_to(to_point(x, y));
+                } else {
+                    builder.line_to(to_point(x, y));
+                }
+            }
+        });
+        frame.stroke(
+            &curve,
+            Stroke::default()
+                .with_color(palette.primary)
+                .with_width(2.0),
+        );
+        for &(x, y) in &sorted {
+            frame.fill(&Path::circle(to_point(x, y), 3.5), palette.primary);
+        }
+        vec![frame.into_geometry()]
+    }
+}
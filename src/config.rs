@@ -1,10 +1,33 @@
+use calc::units::UnitSystem;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+/// 用户预设目录，相对于程序运行目录
+pub const PRESET_DIR: &str = "presets";
+/// 计算历史目录，相对于程序运行目录
+pub const HISTORY_DIR: &str = "history";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub output_path: String,
     pub theme: String,
+    /// 界面与导出使用的单位制
+    #[serde(default = "UnitSystem::percent")]
+    pub units: UnitSystem,
+    /// 是否将计算历史保存到磁盘
+    #[serde(default)]
+    pub history_on_disk: bool,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            output_path: String::new(),
+            theme: String::new(),
+            units: UnitSystem::percent(),
+            history_on_disk: false,
+        }
+    }
 }
 
 impl AppConfig {
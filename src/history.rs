@@ -0,0 +1,85 @@
+//! 计算历史侧栏
+use calc::{
+    history::History,
+    units::{Unit, UnitSystem},
+};
+use iced::{
+    widget::{column, container, horizontal_rule, row, scrollable, text},
+    Alignment, Element, Length,
+};
+
+use crate::{components::labeled_button, Message};
+
+/// 按时间倒序列出计算历史
+pub fn history_panel<'a>(
+    history: &'a History,
+    on_disk: bool,
+    units: &UnitSystem,
+) -> Element<'a, Message> {
+    let store = if on_disk {
+        "历史保存在history目录中"
+    } else {
+        "历史仅保存在本次运行中"
+    };
+    let header = column![
+        row![
+            text("计算历史").size(20).width(Length::Fill),
+            labeled_button("清除未固定", Message::HistoryClear),
+        ]
+        .align_y(Alignment::Center),
+        text(store).size(12),
+        horizontal_rule(1),
+    ]
+    .spacing(8);
+
+    let mut list = column![].spacing(10);
+    if history.entries().is_empty() {
+        list = list.push(text("暂无计算记录").size(14));
+    }
+    for entry in history.entries().iter().rev() {
+        let id = entry.id;
+        let pin_mark = if entry.pinned { "★ " } else { "" };
+        let mut info = column![
+            text(format!("{pin_mark}{}", entry.run.name)).size(16),
+            text(format!("{} UTC", entry.time())).size(12),
+            text(format!(
+                "迭代{}次，Δη={:.2e}，ΔG_cd={:.2e}",
+                entry.stats.iterations, entry.stats.eta_residual, entry.stats.g_cd_residual
+            ))
+            .size(12),
+        ]
+        .spacing(2);
+        if let Some(last) = entry.run.results.result1.last() {
+            info = info.push(
+                text(format!(
+                    "η_eNPP={} {}，Q_R={} {}",
+                    units.format(Unit::Fraction, last.eta_enpp),
+                    units.label(Unit::Fraction),
+                    units.format(Unit::GW, last.q_r),
+                    units.label(Unit::GW),
+                ))
+                .size(12),
+            );
+        }
+        let pin = if entry.pinned {
+            "取消固定"
+        } else {
+            "固定"
+        };
+        let actions = row![
+            labeled_button("恢复输入", Message::HistoryRestore(id)),
+            labeled_button("对比", Message::HistoryCompare(id)),
+            labeled_button(pin, Message::HistoryPin(id)),
+            labeled_button("删除", Message::HistoryDelete(id)),
+        ]
+        .spacing(4);
+        list = list.push(column![info, actions, horizontal_rule(1)].spacing(6));
+    }
+
+    container(column![header, scrollable(list).height(Length::Fill)].spacing(8))
+        .width(Length::Fixed(320.0))
+        .height(Length::Fill)
+        .padding(10)
+        .style(container::rounded_box)
+        .into()
+}
@@ -1,14 +1,25 @@
 #![windows_subsystem = "windows"]
 
+mod cli;
+mod commands;
 mod common;
 mod components;
 mod config;
+mod history;
 mod npp_tabs;
+mod presets;
 
 use npp_tabs::{
     calc_code::{CalcCodeTab, CalcCodeTabMessage},
+    compare::{CompareMessage, CompareTab},
+    condenser::{CondenserMessage, CondenserTab},
     input::{InputTab, InputTabMessage},
+    offdesign::{OffDesignMessage, OffDesignTab},
+    optimize::{OptimizeMessage, OptimizeTab},
     result::{ResultMessage, ResultTab},
+    seasonal::{SeasonalMessage, SeasonalTab},
+    sensitivity::{SensitivityMessage, SensitivityTab},
+    uncertainty::{UncertaintyMessage, UncertaintyTab},
 };
 
 use common::{
@@ -16,15 +27,32 @@ use common::{
     theme::{MISANS_FONT, TAB_PADDING},
 };
 
+use commands::{command_palette, commands, filter_commands, PALETTE_INPUT_ID};
 use components::{labeled_button, modal};
 
-use config::AppConfig;
+use config::{AppConfig, HISTORY_DIR, PRESET_DIR};
+use history::history_panel;
+use presets::{preset_dialog, PresetDiff};
 
-use calc::parameters;
+use calc::{
+    allocation::{sweep as allocation_sweep, Allocation},
+    compare::CalcRun,
+    condenser::{detailed_condenser, CondenserReport},
+    history::History,
+    offdesign::{off_design, OffDesignReport},
+    optimize::{optimize, Optimum},
+    parameters,
+    presets::Preset,
+    seasonal::{seasonal_study, ProfilePoint, SeasonalReport},
+    sensitivity::{analyze_sensitivity, Outcome, SensitivityReport, DEFAULT_STEP},
+    uncertainty::{propagate, UncertaintyReport},
+    units::{EnthalpyUnit, FractionUnit, PressureUnit, TemperatureUnit, UnitSystem},
+};
 
 use iced::{
-    widget::{column as col, container, horizontal_space, row, text},
-    window, Alignment, Element, Length, Padding, Settings, Task, Theme,
+    event, keyboard,
+    widget::{column as col, container, horizontal_space, row, text, text_input},
+    window, Alignment, Element, Event, Length, Padding, Settings, Subscription, Task, Theme,
 };
 use iced_aw::{
     menu::{Item, Menu},
@@ -32,6 +60,9 @@ use iced_aw::{
 };
 
 fn main() -> iced::Result {
+    if let Some(code) = cli::run() {
+        std::process::exit(code);
+    }
     let settings = Settings {
         default_font: MISANS_FONT,
         ..Settings::default()
@@ -39,6 +70,7 @@ fn main() -> iced::Result {
     let window_icon = window::icon::from_file("../assets/logo.png").ok();
     iced::application(App::get_title, App::update, App::view)
         .theme(App::get_theme)
+        .subscription(App::subscription)
         .settings(settings)
         .window(window::Settings {
             icon: window_icon,
@@ -53,6 +85,13 @@ enum TabId {
     Input,
     Result,
     CalcCode,
+    Compare,
+    Sensitivity,
+    Optimize,
+    Uncertainty,
+    OffDesign,
+    Seasonal,
+    Condenser,
 }
 
 struct App {
@@ -66,7 +105,28 @@ struct App {
     input_tab: InputTab,
     result_tab: ResultTab,
     calc_code_tab: CalcCodeTab,
+    compare_tab: CompareTab,
+    sensitivity_tab: SensitivityTab,
+    optimize_tab: OptimizeTab,
+    uncertainty_tab: UncertaintyTab,
+    offdesign_tab: OffDesignTab,
+    seasonal_tab: SeasonalTab,
+    condenser_tab: CondenserTab,
+    /// 最近一次成功计算的输入参数与结果
+    last_run: Option<CalcRun>,
+    /// 计算历史
+    history: History,
+    show_history: bool,
     show_help_dialog: bool,
+    show_palette: bool,
+    /// 命令面板中的搜索关键字
+    palette_query: String,
+    /// 内置预设与用户预设
+    presets: Vec<Preset>,
+    show_preset_dialog: bool,
+    /// 预设对话框中输入的名称
+    preset_name: String,
+    preset_diff: Option<PresetDiff>,
 }
 
 #[derive(Debug, Clone)]
@@ -74,7 +134,7 @@ enum Message {
     FontLoaded(Result<(), iced::font::Error>),
     OpenSubMenu,
     LoadParamsFromFile,
-    LoadedParamsFromFile(Result<Box<parameters::CalcInputParameters>, errors::Error>),
+    LoadedParamsFromFile(Result<Box<calc::import::ImportedParams>, errors::Error>),
     SaveInputParams,
     SelectOutputDir,
     SelectedOutputDir(Result<String, errors::Error>),
@@ -82,15 +142,90 @@ enum Message {
     ClearInputParams,
     SaveResult,
     SaveCalcCode,
+    SaveReportTex,
+    SaveReportHtml,
+    SaveResultCsv,
+    SaveWorkbook,
+    SaveRun,
     Calculate,
     ThemeSelect(Theme),
+    UnitsSelect(UnitSystem),
     OpenHelpDialog,
     HideHelpDialog,
+    // 命令面板
+    OpenPalette,
+    PaletteQueryChanged(String),
+    PaletteSubmit,
+    RunCommand(Box<Message>),
+    /// 关闭所有对话框
+    CloseDialogs,
+    // 预设
+    LoadPreset(usize),
+    OpenPresetDialog,
+    HidePresetDialog,
+    PresetNameChanged(String),
+    SavePreset,
+    RenamePreset(usize),
+    DeletePreset(usize),
+    DiffPreset(usize),
+    // 计算历史
+    ToggleHistory,
+    ToggleHistoryOnDisk,
+    HistoryRestore(u64),
+    HistoryCompare(u64),
+    HistoryPin(u64),
+    HistoryDelete(u64),
+    HistoryClear,
+    // 结果对比
+    CompareAddCurrent,
+    CompareAddFromFile,
+    CompareLoadedRuns(Result<Vec<CalcRun>, errors::Error>),
+    SaveCompareMarkdown,
+    SaveCompareCsv,
+    CompareAllocations,
+    AllocationsCompared(Vec<(Allocation, Result<CalcRun, String>)>),
+    // 灵敏度分析
+    RunSensitivity,
+    SensitivityFinished(Result<Box<SensitivityReport>, String>),
+    SaveSensitivityMarkdown,
+    SaveSensitivityCsv,
+    // 参数优化
+    RunOptimization,
+    OptimizationFinished(Result<Box<Optimum>, String>),
+    ApplyOptimum,
+    SaveOptimizeTrace,
+    // 不确定性分析
+    RunUncertainty,
+    UncertaintyFinished(Result<Box<UncertaintyReport>, String>),
+    SaveUncertaintyMarkdown,
+    SaveUncertaintyCsv,
+    // 变工况计算
+    RunOffDesign,
+    OffDesignFinished(Result<Box<OffDesignReport>, String>),
+    SaveOffDesignMarkdown,
+    SaveOffDesignCsv,
+    // 季节性分析
+    LoadSeasonalProfile,
+    SeasonalProfileLoaded(Result<Vec<ProfilePoint>, errors::Error>),
+    RunSeasonal,
+    SeasonalFinished(Result<Box<SeasonalReport>, String>),
+    SaveSeasonalMarkdown,
+    SaveSeasonalCsv,
+    // 冷凝器详细模型
+    RunCondenser,
+    CondenserFinished(Result<Box<CondenserReport>, String>),
+    SaveCondenserMarkdown,
     // Tab消息
     TabSelected(TabId),
     InputTab(InputTabMessage),
     ResultTab(ResultMessage),
     CalcCodeTab(CalcCodeTabMessage),
+    CompareTab(CompareMessage),
+    OptimizeTab(OptimizeMessage),
+    UncertaintyTab(UncertaintyMessage),
+    OffDesignTab(OffDesignMessage),
+    SeasonalTab(SeasonalMessage),
+    CondenserTab(CondenserMessage),
 }
 
 #[derive(Debug, Clone)]
@@ -98,6 +233,23 @@ enum PendingAction {
     InputParams,
     Result,
     CalcCode,
+    ReportTex,
+    ReportHtml,
+    ResultCsv,
+    Workbook,
+    Run,
+    CompareMarkdown,
+    CompareCsv,
+    SensitivityMarkdown,
+    SensitivityCsv,
+    OptimizeTrace,
+    UncertaintyMarkdown,
+    UncertaintyCsv,
+    OffDesignMarkdown,
+    OffDesignCsv,
+    SeasonalMarkdown,
+    SeasonalCsv,
+    CondenserMarkdown,
 }
 
 impl App {
@@ -110,7 +262,8 @@ impl App {
             "TokyoNightStorm" => Theme::TokyoNightStorm,
             _ => Theme::CatppuccinMocha,
         };
-        let app = Self {
+        let units = config.units;
+        let mut app = Self {
             app_name: String::from("核电厂热力计算程序"),
             theme,
             config,
@@ -121,8 +274,41 @@ impl App {
             input_tab: InputTab::default(),
             result_tab: ResultTab::default(),
             calc_code_tab: CalcCodeTab::new(true),
+            compare_tab: CompareTab::default(),
+            sensitivity_tab: SensitivityTab::default(),
+            optimize_tab: OptimizeTab::default(),
+            uncertainty_tab: UncertaintyTab::default(),
+            offdesign_tab: OffDesignTab::default(),
+            seasonal_tab: SeasonalTab::default(),
+            condenser_tab: CondenserTab::default(),
+            last_run: None,
+            history: History::default(),
+            show_history: false,
             show_help_dialog: false,
+            show_palette: false,
+            palette_query: String::new(),
+            presets: Vec::new(),
+            show_preset_dialog: false,
+            preset_name: String::new(),
+            preset_diff: None,
         };
+        // 各选项卡的其余字段为私有，创建后再设置单位制
+        app.input_tab.units = units;
+        app.result_tab.units = units;
+        app.compare_tab.units = units;
+        app.sensitivity_tab.units = units;
+        app.optimize_tab.set_units(units);
+        app.uncertainty_tab.set_units(units);
+        app.offdesign_tab.units = units;
+        app.seasonal_tab.units = units;
+        app.condenser_tab.set_units(units);
+        app.reload_presets();
+        if app.config.history_on_disk {
+            match History::open(HISTORY_DIR) {
+                Ok(history) => app.history = history,
+                Err(error) => app.status = format!("读取计算历史失败: {error}"),
+            }
+        }
         let command = Task::batch(vec![iced::font::load(
             include_bytes!("../fonts/MiSans VF.ttf").as_slice(),
         )
@@ -148,33 +334,29 @@ impl App {
                 )
             }
             Message::LoadedParamsFromFile(result) => {
-                if let Ok(input_params) = result {
-                    self.caculator.set_input_params(*input_params);
-                    self.input_tab
-                        .update(InputTabMessage::UpdateParams(Box::new(
-                            self.caculator.params.clone(),
-                        )));
-                    self.status = String::from("加载参数成功");
-                } else {
-                    self.status = String::from("加载参数失败");
+                match result {
+                    Ok(imported) => {
+                        self.status = match imported.summary() {
+                            Some(summary) => format!("加载参数成功，{summary}"),
+                            None => String::from("加载参数成功"),
+                        };
+                        self.caculator.set_input_params(imported.params);
+                        self.input_tab
+                            .update(InputTabMessage::UpdateParams(Box::new(
+                                self.caculator.params.clone(),
+                            )));
+                    }
+                    Err(errors::Error::Import(error)) => {
+                        self.status = format!("加载参数失败，{error}");
+                    }
+                    Err(_) => {
+                        self.status = String::from("加载参数失败");
+                    }
                 }
 
                 Task::none()
             }
-            Message::SaveInputParams => {
-                if self.config.output_path.is_empty() {
-                    self.pending_action = Some(PendingAction::InputParams);
-                    return Task::perform(helpers::select_output_dir(), Message::SelectedOutputDir);
-                }
-                match self
-                    .caculator
-                    .save_parameters_to_file(&self.config.output_path)
-                {
-                    Ok(_) => self.status = "保存输入参数成功".to_string(),
-                    Err(error) => self.status = format!("保存输入参数失败{error}"),
-                }
-                Task::none()
-            }
+            Message::SaveInputParams => self.save_or_select_output_dir(PendingAction::InputParams),
             Message::SelectOutputDir => {
                 Task::perform(helpers::select_output_dir(), Message::SelectedOutputDir)
             }
@@ -183,43 +365,8 @@ impl App {
                     Ok(path) => {
                         self.config.output_path = path.clone();
                         self.config.to_file("config.json").unwrap();
-                        if let Some(action) = self.pending_action.clone() {
-                            match action {
-                                PendingAction::InputParams => {
-                                    self.pending_action = None;
-                                    match self
-                                        .caculator
-                                        .save_parameters_to_file(&self.config.output_path)
-                                    {
-                                        Ok(_) => self.status = "保存输入参数成功".to_string(),
-                                        Err(error) => {
-                                            self.status = format!("保存输入参数失败{error}")
-                                        }
-                                    }
-                                }
-                                PendingAction::Result => {
-                                    self.pending_action = None;
-                                    match self
-                                        .caculator
-                                        .save_results_to_file(&self.config.output_path)
-                                    {
-                                        Ok(_) => self.status = "保存计算结果成功".to_string(),
-                                        Err(error) => {
-                                            self.status = format!("保存计算结果失败{error}")
-                                        }
-                                    }
-                                }
-                                PendingAction::CalcCode => {
-                                    self.pending_action = None;
-                                    match self.caculator.save_code_to_file(&self.config.output_path)
-                                    {
-                                        Ok(_) => self.status = "保存计算代码成功".to_string(),
-                                        Err(error) => {
-                                            self.status = format!("保存计算代码失败{error}")
-                                        }
-                                    }
-                                }
-                            }
+                        if let Some(action) = self.pending_action.take() {
+                            self.save_to_output_dir(action);
                         }
                     }
                     Err(_) => {
@@ -244,36 +391,64 @@ impl App {
                 self.status = "清除输入参数成功".to_string();
                 Task::none()
             }
-            Message::SaveResult => {
-                if self.config.output_path.is_empty() {
-                    self.pending_action = Some(PendingAction::Result);
-                    return Task::perform(helpers::select_output_dir(), Message::SelectedOutputDir);
-                }
-                match self
-                    .caculator
-                    .save_results_to_file(&self.config.output_path)
-                {
-                    Ok(_) => self.status = "保存计算结果成功".to_string(),
-                    Err(error) => self.status = format!("保存计算结果失败{error}"),
-                }
-                Task::none()
-            }
-            Message::SaveCalcCode => {
-                if self.config.output_path.is_empty() {
-                    self.pending_action = Some(PendingAction::CalcCode);
-                    return Task::perform(helpers::select_output_dir(), Message::SelectedOutputDir);
+            Message::SaveResult => self.save_or_select_output_dir(PendingAction::Result),
+            Message::SaveCalcCode => self.save_or_select_output_dir(PendingAction::CalcCode),
+            Message::SaveReportTex => self.save_or_select_output_dir(PendingAction::ReportTex),
+            Message::SaveReportHtml => self.save_or_select_output_dir(PendingAction::ReportHtml),
+            Message::SaveResultCsv => self.save_or_select_output_dir(PendingAction::ResultCsv),
+            Message::SaveWorkbook => self.save_or_select_output_dir(PendingAction::Workbook),
+            Message::SaveRun => self.save_or_select_output_dir(PendingAction::Run),
+            Message::Calculate => {
+                // 输入框中有未填写或无法解析的参数时不计算
+                if self.input_params().is_none() {
+                    return Task::none();
                 }
-                match self.caculator.save_code_to_file(&self.config.output_path) {
-                    Ok(_) => self.status = String::from("保存计算代码成功"),
-                    Err(error) => self.status = format!("保存计算代码失败{error}"),
+                // 计算过程会修改部分输入参数，计算历史记录计算前的用户输入
+                let input = self.caculator.params.clone();
+                // 启用冷凝器详细模型时，以其计算的温升与端差代替输入值参与计算，不改动用户输入
+                let mut params = input.clone();
+                if self.condenser_tab.enabled() {
+                    let result = self
+                        .condenser_tab
+                        .design()
+                        .and_then(|design| detailed_condenser(&input, &design));
+                    match result {
+                        Ok(report) => {
+                            params = report.params.clone();
+                            self.condenser_tab
+                                .update(CondenserMessage::UpdateReport(Box::new(report)));
+                        }
+                        Err(error) => {
+                            self.status = format!("冷凝器详细模型计算失败: {error}");
+                            return Task::none();
+                        }
+                    }
                 }
-
-                Task::none()
-            }
-            Message::Calculate => {
-                match self.caculator.calculate() {
+                let warnings = calc::fields::check_input_ranges(&params);
+                let start = self.caculator.results.result1.len();
+                self.caculator.set_input_params(params);
+                let result = self.caculator.calculate();
+                self.caculator.set_input_params(input.clone());
+                match result {
                     Ok(_) => {
-                        self.status = String::from("计算成功");
+                        // 迭代过程会累积此前各次计算，只记录本次的迭代
+                        let mut results = self.caculator.results.clone();
+                        results.result1.drain(..start);
+                        let run = CalcRun {
+                            name: format!("第{}次计算", self.history.next_id()),
+                            params: input,
+                            results,
+                        };
+                        let saved = self.history.push(run.clone()).map(|_| ());
+                        self.last_run = Some(run);
+                        self.status = match warnings.first() {
+                            None => String::from("计算成功"),
+                            Some(warning) => format!(
+                                "计算成功，{}个参数超出推荐范围: {}",
+                                warnings.len(),
+                                warning.message(&self.config.units)
+                            ),
+                        };
                         self.calc_code_tab.update(CalcCodeTabMessage::UpdatePyCode(
                             self.caculator.calc_code_py.clone(),
                         ));
@@ -283,6 +458,9 @@ impl App {
                         self.result_tab.update(ResultMessage::UpdateResult(Box::new(
                             self.caculator.results.clone(),
                         )));
+                        if let Err(error) = saved {
+                            self.status = format!("{}，保存计算历史失败: {error}", self.status);
+                        }
                     }
                     Err(err) => self.status = format!("计算失败: {err}"),
                 }
@@ -300,6 +478,29 @@ impl App {
                 self.config.to_file("config.json").unwrap();
                 Task::none()
             }
+            Message::UnitsSelect(units) => {
+                // 切换单位制前先按原单位制读取输入框，再按新单位制重新显示
+                if let Ok(params) = self.input_tab.params() {
+                    self.caculator.params = params;
+                }
+                self.config.units = units;
+                self.input_tab.set_units(units);
+                self.result_tab.units = units;
+                self.compare_tab.units = units;
+                self.sensitivity_tab.units = units;
+                self.optimize_tab.set_units(units);
+                self.uncertainty_tab.set_units(units);
+                self.offdesign_tab.units = units;
+                self.seasonal_tab.units = units;
+                self.condenser_tab.set_units(units);
+                if self.caculator.get_results().is_some() {
+                    self.result_tab.update(ResultMessage::UpdateResult(Box::new(
+                        self.caculator.results.clone(),
+                    )));
+                }
+                self.config.to_file("config.json").unwrap();
+                Task::none()
+            }
             Message::OpenHelpDialog => {
                 self.show_help_dialog = true;
                 Task::none()
@@ -308,6 +509,496 @@ impl App {
                 self.show_help_dialog = false;
                 Task::none()
             }
+            Message::OpenPalette => {
+                self.show_palette = true;
+                self.palette_query.clear();
+                text_input::focus(text_input::Id::new(PALETTE_INPUT_ID))
+            }
+            Message::PaletteQueryChanged(query) => {
+                self.palette_query = query;
+                Task::none()
+            }
+            Message::PaletteSubmit => {
+                let commands = commands(&self.presets, self.config.units);
+                match filter_commands(commands, &self.palette_query)
+                    .into_iter()
+                    .next()
+                {
+                    Some(command) => self.update(Message::RunCommand(Box::new(command.message))),
+                    None => Task::none(),
+                }
+            }
+            Message::RunCommand(message) => {
+                self.show_palette = false;
+                self.update(*message)
+            }
+            Message::CloseDialogs => {
+                self.show_help_dialog = false;
+                self.show_preset_dialog = false;
+                self.preset_diff = None;
+                self.show_palette = false;
+                Task::none()
+            }
+            Message::LoadPreset(index) => {
+                if let Some(preset) = self.presets.get(index) {
+                    self.caculator.set_input_params(preset.params.clone());
+                    self.input_tab
+                        .update(InputTabMessage::UpdateParams(Box::new(
+                            self.caculator.params.clone(),
+                        )));
+                    self.status = format!("加载预设“{}”成功", preset.name);
+                }
+                Task::none()
+            }
+            Message::OpenPresetDialog => {
+                self.reload_presets();
+                self.show_preset_dialog = true;
+                Task::none()
+            }
+            Message::HidePresetDialog => {
+                self.show_preset_dialog = false;
+                self.preset_diff = None;
+                Task::none()
+            }
+            Message::PresetNameChanged(name) => {
+                self.preset_name = name;
+                Task::none()
+            }
+            Message::SavePreset => {
+                let Some(params) = self.input_params() else {
+                    return Task::none();
+                };
+                let name = self.preset_name.trim();
+                let preset = Preset::new(name, "", params);
+                self.status = match calc::presets::save_user_preset(PRESET_DIR, &preset) {
+                    Ok(_) => format!("保存预设“{name}”成功"),
+                    Err(error) => format!("保存预设失败: {error}"),
+                };
+                self.reload_presets();
+                Task::none()
+            }
+            Message::RenamePreset(index) => {
+                if let Some(preset) = self.presets.get(index) {
+                    let new_name = self.preset_name.trim();
+                    self.status =
+                        match calc::presets::rename_user_preset(PRESET_DIR, &preset.name, new_name)
+                        {
+                            Ok(_) => format!("预设“{}”已重命名为“{new_name}”", preset.name),
+                            Err(error) => format!("重命名预设失败: {error}"),
+                        };
+                    self.reload_presets();
+                }
+                Task::none()
+            }
+            Message::DeletePreset(index) => {
+                if let Some(preset) = self.presets.get(index) {
+                    self.status = match calc::presets::delete_user_preset(PRESET_DIR, &preset.name)
+                    {
+                        Ok(_) => format!("删除预设“{}”成功", preset.name),
+                        Err(error) => format!("删除预设失败: {error}"),
+                    };
+                    self.preset_diff = None;
+                    self.reload_presets();
+                }
+                Task::none()
+            }
+            Message::DiffPreset(index) => {
+                if let Some(params) = self.input_params() {
+                    if let Some(preset) = self.presets.get(index) {
+                        let diff = calc::presets::diff_params(&preset.params, &params);
+                        self.preset_diff = Some((preset.name.clone(), diff));
+                    }
+                }
+                Task::none()
+            }
+            Message::ToggleHistory => {
+                self.show_history = !self.show_history;
+                Task::none()
+            }
+            Message::ToggleHistoryOnDisk => {
+                self.config.history_on_disk = !self.config.history_on_disk;
+                let dir = self.config.history_on_disk.then(|| HISTORY_DIR.into());
+                self.status = match self.history.set_dir(dir) {
+                    Ok(_) if self.config.history_on_disk => {
+                        format!("计算历史将保存到{HISTORY_DIR}目录")
+                    }
+                    Ok(_) => String::from("计算历史不再保存到磁盘"),
+                    Err(error) => format!("保存计算历史失败: {error}"),
+                };
+                self.config.to_file("config.json").unwrap();
+                Task::none()
+            }
+            Message::HistoryRestore(id) => {
+                if let Some(entry) = self.history.get(id) {
+                    self.caculator.set_input_params(entry.run.params.clone());
+                    self.input_tab
+                        .update(InputTabMessage::UpdateParams(Box::new(
+                            self.caculator.params.clone(),
+                        )));
+                    self.status = format!("已恢复“{}”的输入参数", entry.run.name);
+                }
+                Task::none()
+            }
+            Message::HistoryCompare(id) => {
+                if let Some(entry) = self.history.get(id) {
+                    self.compare_tab
+                        .update(CompareMessage::AddRuns(vec![entry.run.clone()]));
+                    self.status = format!("已添加“{}”到结果对比", entry.run.name);
+                }
+                Task::none()
+            }
+            Message::HistoryPin(id) => {
+                if let Err(error) = self.history.toggle_pin(id) {
+                    self.status = format!("保存计算历史失败: {error}");
+                }
+                Task::none()
+            }
+            Message::HistoryDelete(id) => {
+                if let Err(error) = self.history.delete(id) {
+                    self.status = format!("删除计算历史失败: {error}");
+                }
+                Task::none()
+            }
+            Message::HistoryClear => {
+                if let Err(error) = self.history.clear_unpinned() {
+                    self.status = format!("删除计算历史失败: {error}");
+                }
+                Task::none()
+            }
+            Message::CompareAddCurrent => {
+                match &self.last_run {
+                    Some(run) => {
+                        self.compare_tab
+                            .update(CompareMessage::AddRuns(vec![run.clone()]));
+                        self.status = format!("已添加“{}”到结果对比", run.name);
+                    }
+                    None => self.status = String::from("尚未计算，无法添加当前结果"),
+                }
+                Task::none()
+            }
+            Message::CompareAddFromFile => {
+                Task::perform(helpers::load_runs_from_files(), Message::CompareLoadedRuns)
+            }
+            Message::CompareLoadedRuns(result) => {
+                match result {
+                    Ok(runs) => {
+                        self.status = format!("已添加{}组计算记录到结果对比", runs.len());
+                        self.compare_tab.update(CompareMessage::AddRuns(runs));
+                    }
+                    Err(errors::Error::RunFile(error)) => {
+                        self.status = format!("读取计算记录失败，{error}");
+                    }
+                    Err(_) => self.status = String::from("读取计算记录失败"),
+                }
+                Task::none()
+            }
+            Message::CompareAllocations => {
+                let Some(params) = self.input_params() else {
+                    return Task::none();
+                };
+                self.status = String::from("给水焓升分配方式对比计算中");
+                Task::perform(
+                    async move { allocation_sweep(&params) },
+                    Message::AllocationsCompared,
+                )
+            }
+            Message::AllocationsCompared(sweep) => {
+                let mut runs = Vec::new();
+                let mut failures = Vec::new();
+                for (allocation, run) in sweep {
+                    match run {
+                        Ok(run) => runs.push(run),
+                        Err(error) => failures.push(format!("{allocation}: {error}")),
+                    }
+                }
+                self.status = if failures.is_empty() {
+                    format!("已添加{}种给水焓升分配方式到结果对比", runs.len())
+                } else {
+                    format!(
+                        "已添加{}种给水焓升分配方式到结果对比，计算失败: {}",
+                        runs.len(),
+                        failures.join("；")
+                    )
+                };
+                if !runs.is_empty() {
+                    self.compare_tab.update(CompareMessage::AddRuns(runs));
+                    self.active_tab = TabId::Compare;
+                }
+                Task::none()
+            }
+            Message::SaveCompareMarkdown => {
+                self.save_or_select_output_dir(PendingAction::CompareMarkdown)
+            }
+            Message::SaveCompareCsv => self.save_or_select_output_dir(PendingAction::CompareCsv),
+            Message::RunSensitivity => {
+                let Some(params) = self.input_params() else {
+                    return Task::none();
+                };
+                self.status = String::from("灵敏度分析进行中");
+                Task::perform(
+                    async move { analyze_sensitivity(&params, DEFAULT_STEP).map(Box::new) },
+                    Message::SensitivityFinished,
+                )
+            }
+            Message::SensitivityFinished(result) => {
+                match result {
+                    Ok(report) => {
+                        let failed = report
+                            .rows
+                            .iter()
+                            .filter(|row| matches!(row.outcome, Outcome::Failed(_)))
+                            .count();
+                        self.status = if failed == 0 {
+                            String::from("灵敏度分析完成")
+                        } else {
+                            format!("灵敏度分析完成，{failed}个参数的扰动计算失败")
+                        };
+                        self.sensitivity_tab
+                            .update(SensitivityMessage::UpdateReport(report));
+                        self.active_tab = TabId::Sensitivity;
+                    }
+                    Err(error) => self.status = format!("灵敏度分析失败: {error}"),
+                }
+                Task::none()
+            }
+            Message::SaveSensitivityMarkdown => {
+                self.save_or_select_output_dir(PendingAction::SensitivityMarkdown)
+            }
+            Message::SaveSensitivityCsv => {
+                self.save_or_select_output_dir(PendingAction::SensitivityCsv)
+            }
+            Message::RunOptimization => {
+                let Some(params) = self.input_params() else {
+                    return Task::none();
+                };
+                let objective = self.optimize_tab.objective();
+                match self.optimize_tab.variables() {
+                    Ok(variables) => {
+                        self.status = String::from("参数优化进行中");
+                        Task::perform(
+                            async move { optimize(&params, &variables, objective).map(Box::new) },
+                            Message::OptimizationFinished,
+                        )
+                    }
+                    Err(error) => {
+                        self.status = format!("参数优化失败: {error}");
+                        Task::none()
+                    }
+                }
+            }
+            Message::OptimizationFinished(result) => {
+                match result {
+                    Ok(optimum) => {
+                        self.status = if optimum.converged {
+                            format!("参数优化完成，迭代{}次", optimum.trace.len())
+                        } else {
+                            String::from("参数优化达到最大迭代次数仍未收敛")
+                        };
+                        self.optimize_tab
+                            .update(OptimizeMessage::UpdateResult(optimum));
+                        self.active_tab = TabId::Optimize;
+                    }
+                    Err(error) => self.status = format!("参数优化失败: {error}"),
+                }
+                Task::none()
+            }
+            Message::ApplyOptimum => {
+                match self.optimize_tab.result() {
+                    Some(optimum) => {
+                        self.caculator.set_input_params(optimum.params.clone());
+                        self.input_tab
+                            .update(InputTabMessage::UpdateParams(Box::new(
+                                self.caculator.params.clone(),
+                            )));
+                        self.status = String::from("已将最优参数应用到输入参数");
+                    }
+                    None => self.status = String::from("尚未进行参数优化"),
+                }
+                Task::none()
+            }
+            Message::SaveOptimizeTrace => {
+                self.save_or_select_output_dir(PendingAction::OptimizeTrace)
+            }
+            Message::RunUncertainty => {
+                let Some(params) = self.input_params() else {
+                    return Task::none();
+                };
+                let setup = self.uncertainty_tab.inputs().and_then(|inputs| {
+                    let (samples, seed) = self.uncertainty_tab.sampling()?;
+                    Ok((inputs, samples, seed))
+                });
+                match setup {
+                    Ok((inputs, samples, seed)) => {
+                        self.status = format!("不确定性分析进行中，共{samples}个样本");
+                        // 样本较多时耗时较长，在后台执行
+                        Task::perform(
+                            async move { propagate(&params, &inputs, samples, seed).map(Box::new) },
+                            Message::UncertaintyFinished,
+                        )
+                    }
+                    Err(error) => {
+                        self.status = format!("不确定性分析失败: {error}");
+                        Task::none()
+                    }
+                }
+            }
+            Message::UncertaintyFinished(result) => {
+                match result {
+                    Ok(report) => {
+                        self.status = if report.failures == 0 {
+                            String::from("不确定性分析完成")
+                        } else {
+                            format!("不确定性分析完成，{}个样本计算失败", report.failures)
+                        };
+                        self.uncertainty_tab
+                            .update(UncertaintyMessage::UpdateReport(report));
+                        self.active_tab = TabId::Uncertainty;
+                    }
+                    Err(error) => self.status = format!("不确定性分析失败: {error}"),
+                }
+                Task::none()
+            }
+            Message::SaveUncertaintyMarkdown => {
+                self.save_or_select_output_dir(PendingAction::UncertaintyMarkdown)
+            }
+            Message::SaveUncertaintyCsv => {
+                self.save_or_select_output_dir(PendingAction::UncertaintyCsv)
+            }
+            Message::RunOffDesign => {
+                let Some(params) = self.input_params() else {
+                    return Task::none();
+                };
+                match self.offdesign_tab.settings() {
+                    Ok((loads, coefficient)) => {
+                        self.status = format!("变工况计算进行中，共{}个负荷", loads.len());
+                        Task::perform(
+                            async move { off_design(&params, &loads, coefficient).map(Box::new) },
+                            Message::OffDesignFinished,
+                        )
+                    }
+                    Err(error) => {
+                        self.status = format!("变工况计算失败: {error}");
+                        Task::none()
+                    }
+                }
+            }
+            Message::OffDesignFinished(result) => {
+                match result {
+                    Ok(report) => {
+                        self.status = if report.failures.is_empty() {
+                            format!("变工况计算完成，共{}个负荷", report.points.len())
+                        } else {
+                            format!("变工况计算完成，{}个负荷计算失败", report.failures.len())
+                        };
+                        self.offdesign_tab
+                            .update(OffDesignMessage::UpdateReport(report));
+                        self.active_tab = TabId::OffDesign;
+                    }
+                    Err(error) => self.status = format!("变工况计算失败: {error}"),
+                }
+                Task::none()
+            }
+            Message::SaveOffDesignMarkdown => {
+                self.save_or_select_output_dir(PendingAction::OffDesignMarkdown)
+            }
+            Message::SaveOffDesignCsv => {
+                self.save_or_select_output_dir(PendingAction::OffDesignCsv)
+            }
+            Message::LoadSeasonalProfile => Task::perform(
+                helpers::load_seasonal_profile(),
+                Message::SeasonalProfileLoaded,
+            ),
+            Message::SeasonalProfileLoaded(result) => {
+                match result {
+                    Ok(profile) => {
+                        self.status = format!("已加载冷却水温度曲线，共{}个时段", profile.len());
+                        self.seasonal_tab
+                            .update(SeasonalMessage::UpdateProfile(profile));
+                        self.active_tab = TabId::Seasonal;
+                    }
+                    Err(errors::Error::Profile(error)) => {
+                        self.status = format!("读取冷却水温度曲线失败，{error}");
+                    }
+                    Err(_) => self.status = String::from("读取冷却水温度曲线失败"),
+                }
+                Task::none()
+            }
+            Message::RunSeasonal => {
+                let Some(params) = self.input_params() else {
+                    return Task::none();
+                };
+                match self.seasonal_tab.settings() {
+                    Ok((profile, basis)) => {
+                        self.status = format!("季节性分析进行中，共{}个时段", profile.len());
+                        let profile = profile.to_vec();
+                        Task::perform(
+                            async move { seasonal_study(&params, &profile, basis).map(Box::new) },
+                            Message::SeasonalFinished,
+                        )
+                    }
+                    Err(error) => {
+                        self.status = format!("季节性分析失败: {error}");
+                        Task::none()
+                    }
+                }
+            }
+            Message::SeasonalFinished(result) => {
+                match result {
+                    Ok(report) => {
+                        self.status = if report.failures.is_empty() {
+                            format!("季节性分析完成，共{}个时段", report.points.len())
+                        } else {
+                            format!("季节性分析完成，{}个时段计算失败", report.failures.len())
+                        };
+                        self.seasonal_tab
+                            .update(SeasonalMessage::UpdateReport(report));
+                        self.active_tab = TabId::Seasonal;
+                    }
+                    Err(error) => self.status = format!("季节性分析失败: {error}"),
+                }
+                Task::none()
+            }
+            Message::SaveSeasonalMarkdown => {
+                self.save_or_select_output_dir(PendingAction::SeasonalMarkdown)
+            }
+            Message::SaveSeasonalCsv => self.save_or_select_output_dir(PendingAction::SeasonalCsv),
+            Message::RunCondenser => {
+                let Some(params) = self.input_params() else {
+                    return Task::none();
+                };
+                match self.condenser_tab.design() {
+                    Ok(design) => {
+                        self.status = String::from("冷凝器详细模型计算中");
+                        Task::perform(
+                            async move { detailed_condenser(&params, &design).map(Box::new) },
+                            Message::CondenserFinished,
+                        )
+                    }
+                    Err(error) => {
+                        self.status = format!("冷凝器详细模型计算失败: {error}");
+                        Task::none()
+                    }
+                }
+            }
+            Message::CondenserFinished(result) => {
+                match result {
+                    Ok(report) => {
+                        self.status = if report.converged {
+                            format!("冷凝器详细模型计算完成，迭代{}次", report.iterations)
+                        } else {
+                            format!("冷凝器详细模型迭代{}次仍未收敛", report.iterations)
+                        };
+                        self.condenser_tab
+                            .update(CondenserMessage::UpdateReport(report));
+                        self.active_tab = TabId::Condenser;
+                    }
+                    Err(error) => self.status = format!("冷凝器详细模型计算失败: {error}"),
+                }
+                Task::none()
+            }
+            Message::SaveCondenserMarkdown => {
+                self.save_or_select_output_dir(PendingAction::CondenserMarkdown)
+            }
             // Tab消息
             Message::TabSelected(selected) => {
                 self.active_tab = selected;
@@ -315,8 +1006,14 @@ impl App {
             }
             Message::InputTab(msg) => {
                 self.input_tab.update(msg.clone());
-                if let InputTabMessage::ValueChanged(_) = msg {
-                    self.caculator.params = self.input_tab.input_strings.clone().into()
+                if let InputTabMessage::ValueChanged(..)
+                | InputTabMessage::Undo
+                | InputTabMessage::Redo = msg
+                {
+                    match self.input_tab.params() {
+                        Ok(params) => self.caculator.params = params,
+                        Err(error) => self.status = format!("输入参数无效: {error}"),
+                    }
                 }
                 Task::none()
             }
@@ -328,18 +1025,76 @@ impl App {
                 self.calc_code_tab.update(msg);
                 Task::none()
             }
+            Message::CompareTab(msg) => {
+                self.compare_tab.update(msg);
+                Task::none()
+            }
+            Message::OptimizeTab(msg) => {
+                self.optimize_tab.update(msg);
+                Task::none()
+            }
+            Message::UncertaintyTab(msg) => {
+                self.uncertainty_tab.update(msg);
+                Task::none()
+            }
+            Message::OffDesignTab(msg) => {
+                self.offdesign_tab.update(msg);
+                Task::none()
+            }
+            Message::SeasonalTab(msg) => {
+                self.seasonal_tab.update(msg);
+                Task::none()
+            }
+            Message::CondenserTab(msg) => {
+                self.condenser_tab.update(msg);
+                Task::none()
+            }
         }
     }
 
     fn view(&self) -> Element<Message> {
+        let units = self.config.units;
+        let mut preset_items: Vec<_> = self
+            .presets
+            .iter()
+            .enumerate()
+            .map(|(index, preset)| {
+                Item::new(
+                    labeled_button(&preset.name, Message::LoadPreset(index)).width(Length::Fill),
+                )
+            })
+            .collect();
+        preset_items.push(Item::new(
+            labeled_button("管理预设...", Message::OpenPresetDialog).width(Length::Fill),
+        ));
+        let history_label = if self.show_history {
+            "隐藏计算历史 (Ctrl+H)"
+        } else {
+            "显示计算历史 (Ctrl+H)"
+        };
+        let history_store_label = if self.config.history_on_disk {
+            "✓ 保存历史到磁盘"
+        } else {
+            "保存历史到磁盘"
+        };
         #[rustfmt::skip]
         let menubar = menu_bar!(
             (labeled_button("文件", Message::OpenSubMenu).width(Length::Shrink), {
                 Menu::new(menu_items!(
-                    (labeled_button("加载参数", Message::LoadParamsFromFile).width(Length::Fill))
-                    (labeled_button("保存输入参数", Message::SaveInputParams).width(Length::Fill))
+                    (labeled_button("加载参数 (Ctrl+O)", Message::LoadParamsFromFile).width(Length::Fill))
+                    (labeled_button("保存输入参数 (Ctrl+S)", Message::SaveInputParams).width(Length::Fill))
                     (labeled_button("选择输出目录", Message::SelectOutputDir).width(Length::Fill))
-                )).max_width(180.0)
+                    (labeled_button("导出LaTeX计算书", Message::SaveReportTex).width(Length::Fill))
+                    (labeled_button("导出HTML报告", Message::SaveReportHtml).width(Length::Fill))
+                    (labeled_button("导出CSV表格", Message::SaveResultCsv).width(Length::Fill))
+                    (labeled_button("导出Excel工作簿", Message::SaveWorkbook).width(Length::Fill))
+                )).max_width(200.0)
+            })
+            (labeled_button("编辑", Message::OpenSubMenu).width(Length::Shrink), {
+                Menu::new(menu_items!(
+                    (labeled_button("撤销 (Ctrl+Z)", Message::InputTab(InputTabMessage::Undo)).width(Length::Fill))
+                    (labeled_button("重做 (Ctrl+Shift+Z)", Message::InputTab(InputTabMessage::Redo)).width(Length::Fill))
+                )).max_width(200.0)
             })
             (labeled_button("计算", Message::OpenSubMenu).width(Length::Shrink), {
                 Menu::new(menu_items!(
@@ -347,8 +1102,20 @@ impl App {
                     (labeled_button("清空输入参数", Message::ClearInputParams).width(Length::Fill))
                     (labeled_button("保存计算结果", Message::SaveResult).width(Length::Fill))
                     (labeled_button("保存计算代码", Message::SaveCalcCode).width(Length::Fill))
-                    (labeled_button("开始计算", Message::Calculate).width(Length::Fill))
-                )).max_width(180.0)
+                    (labeled_button("保存计算记录 (Ctrl+Shift+S)", Message::SaveRun).width(Length::Fill))
+                    (labeled_button(history_label, Message::ToggleHistory).width(Length::Fill))
+                    (labeled_button(history_store_label, Message::ToggleHistoryOnDisk).width(Length::Fill))
+                    (labeled_button("开始计算 (F5)", Message::Calculate).width(Length::Fill))
+                    (labeled_button("灵敏度分析", Message::RunSensitivity).width(Length::Fill))
+                    (labeled_button("参数优化", Message::TabSelected(TabId::Optimize)).width(Length::Fill))
+                    (labeled_button("不确定性分析", Message::TabSelected(TabId::Uncertainty)).width(Length::Fill))
+                    (labeled_button("变工况计算", Message::RunOffDesign).width(Length::Fill))
+                    (labeled_button("季节性分析", Message::TabSelected(TabId::Seasonal)).width(Length::Fill))
+                    (labeled_button("冷凝器详细模型", Message::RunCondenser).width(Length::Fill))
+                )).max_width(240.0)
+            })
+            (labeled_button("预设", Message::OpenSubMenu).width(Length::Shrink), {
+                Menu::new(preset_items).max_width(220.0)
             })
             (labeled_button("主题", Message::OpenSubMenu).width(Length::Shrink), {
                 Menu::new(menu_items!(
@@ -358,10 +1125,24 @@ impl App {
                     (labeled_button("TokyoNightStorm", Message::ThemeSelect(Theme::TokyoNightStorm)).width(Length::Fill))
                 )).max_width(180.0)
             })
+            (labeled_button("单位", Message::OpenSubMenu).width(Length::Shrink), {
+                Menu::new(menu_items!(
+                    (labeled_button("压力 MPa", Message::UnitsSelect(UnitSystem { pressure: PressureUnit::MPa, ..units })).width(Length::Fill))
+                    (labeled_button("压力 bar", Message::UnitsSelect(UnitSystem { pressure: PressureUnit::Bar, ..units })).width(Length::Fill))
+                    (labeled_button("压力 kPa", Message::UnitsSelect(UnitSystem { pressure: PressureUnit::KPa, ..units })).width(Length::Fill))
+                    (labeled_button("温度 ℃", Message::UnitsSelect(UnitSystem { temperature: TemperatureUnit::Celsius, ..units })).width(Length::Fill))
+                    (labeled_button("温度 K", Message::UnitsSelect(UnitSystem { temperature: TemperatureUnit::Kelvin, ..units })).width(Length::Fill))
+                    (labeled_button("比焓 kJ/kg", Message::UnitsSelect(UnitSystem { enthalpy: EnthalpyUnit::KjPerKg, ..units })).width(Length::Fill))
+                    (labeled_button("比焓 BTU/lb", Message::UnitsSelect(UnitSystem { enthalpy: EnthalpyUnit::BtuPerLb, ..units })).width(Length::Fill))
+                    (labeled_button("比例 %", Message::UnitsSelect(UnitSystem { fraction: FractionUnit::Percent, ..units })).width(Length::Fill))
+                    (labeled_button("比例 小数", Message::UnitsSelect(UnitSystem { fraction: FractionUnit::Fraction, ..units })).width(Length::Fill))
+                )).max_width(180.0)
+            })
             (labeled_button("帮助", Message::OpenSubMenu).width(Length::Shrink), {
                 Menu::new(menu_items!(
+                    (labeled_button("命令面板 (Ctrl+Shift+P)", Message::OpenPalette).width(Length::Fill))
                     (labeled_button("关于", Message::OpenHelpDialog).width(Length::Fill))
-                )).max_width(180.0)
+                )).max_width(220.0)
             })
         ).padding(Padding::from([8.0, 0.0]));
 
@@ -381,11 +1162,55 @@ impl App {
                 self.calc_code_tab.tab_label(),
                 self.calc_code_tab.view(),
             )
+            .push(
+                TabId::Compare,
+                self.compare_tab.tab_label(),
+                self.compare_tab.view(),
+            )
+            .push(
+                TabId::Sensitivity,
+                self.sensitivity_tab.tab_label(),
+                self.sensitivity_tab.view(),
+            )
+            .push(
+                TabId::Optimize,
+                self.optimize_tab.tab_label(),
+                self.optimize_tab.view(),
+            )
+            .push(
+                TabId::Uncertainty,
+                self.uncertainty_tab.tab_label(),
+                self.uncertainty_tab.view(),
+            )
+            .push(
+                TabId::OffDesign,
+                self.offdesign_tab.tab_label(),
+                self.offdesign_tab.view(),
+            )
+            .push(
+                TabId::Seasonal,
+                self.seasonal_tab.tab_label(),
+                self.seasonal_tab.view(),
+            )
+            .push(
+                TabId::Condenser,
+                self.condenser_tab.tab_label(),
+                self.condenser_tab.view(),
+            )
             .height(Length::Fill)
             .set_active_tab(&self.active_tab)
             .tab_bar_position(TabBarPosition::Top);
 
-        let content = container(ts).center(Length::Fill);
+        let content: Element<Message> = if self.show_history {
+            row![
+                history_panel(&self.history, self.config.history_on_disk, &units),
+                container(ts).center(Length::Fill)
+            ]
+            .spacing(8)
+            .into()
+        } else {
+            container(ts).center(Length::Fill).into()
+        };
 
         let output_dir_status = if self.config.output_path.is_empty() {
             "未选择".to_string()
@@ -413,10 +1238,27 @@ impl App {
 
             let features_title = text("主要功能:").size(18);
             let features_list = col![
-                text("- 参数输入、加载、保存与清空"),
+                text("- 参数输入、加载、保存与清空，支持撤销与重做"),
                 text("- 热力循环计算"),
                 text("- 计算结果展示与保存"),
                 text("- 计算过程代码生成 (Rust & Python) 与保存"),
+                text("- 内置及用户参数预设的加载、保存与对比"),
+                text("- 多组计算结果对比及差异导出"),
+                text("- 输入参数灵敏度分析及龙卷风图"),
+                text("- 设计参数优化 (黄金分割法/Nelder–Mead)"),
+                text("- 蒙特卡洛不确定性分析"),
+                text("- 部分负荷变工况计算 (斯托多拉锥体定律)"),
+                text("- 冷却水温度季节性分析 (全年发电量加权平均)"),
+                text("- 冷凝器详细模型 (HEI传热系数、清洁系数与所需冷却水流量)"),
+                text("- 给水泵电动或汽动，给水泵汽轮机汽源与排汽去向可选"),
+                text("- 各级加热器疏水可逐级自流、由疏水泵打入下游或经疏水冷却器"),
+                text("- 各级加热器出口端差与疏水冷却器端差可逐级给定，留空时取全局值"),
+                text(
+                    "- 给水焓升可按等焓升、等温升、几何分配或自定义权重分配，并可在结果对比中比较"
+                ),
+                text("- 一回路冷却剂流量与主泵功率 (可计入热平衡)"),
+                text("- 蒸汽发生器热力设计 (传热面积与传热管数)"),
+                text("- 计算历史的浏览、恢复、固定与删除"),
                 text("- 多主题选择"),
                 text("- 输出目录选择与配置保存")
             ]
@@ -428,7 +1270,8 @@ impl App {
                 text("2. 通过“文件”菜单选择输出目录。"),
                 text("3. 点击“计算”菜单中的“开始计算”。"),
                 text("4. 在“计算结果”和“计算代码”页查看详情。"),
-                text("5. 使用菜单栏进行参数、结果、代码的保存。")
+                text("5. 使用菜单栏进行参数、结果、代码的保存。"),
+                text("6. 按 Ctrl+Shift+P 打开命令面板搜索并执行所有命令。")
             ]
             .spacing(5);
 
@@ -455,11 +1298,232 @@ impl App {
                 .width(450) // 调整宽度以适应更多内容
                 .style(container::rounded_box);
             modal(v, help_content_styled, Message::HideHelpDialog)
+        } else if self.show_palette {
+            let commands = filter_commands(
+                commands(&self.presets, self.config.units),
+                &self.palette_query,
+            );
+            modal(
+                v,
+                command_palette(commands, &self.palette_query),
+                Message::CloseDialogs,
+            )
+        } else if self.show_preset_dialog {
+            let dialog = preset_dialog(
+                &self.presets,
+                &self.preset_name,
+                self.preset_diff.as_ref(),
+                &self.config.units,
+            );
+            modal(v, dialog, Message::HidePresetDialog)
         } else {
             v.into()
         }
     }
 
+    /// 读取输入界面的参数，输入无效时在状态栏提示并返回`None`
+    fn input_params(&mut self) -> Option<parameters::CalcInputParameters> {
+        self.input_tab
+            .params()
+            .map_err(|error| self.status = format!("输入参数无效: {error}"))
+            .ok()
+    }
+
+    /// 重新读取预设目录，读取失败时只保留内置预设
+    fn reload_presets(&mut self) {
+        self.presets = calc::presets::load_presets(PRESET_DIR).unwrap_or_else(|error| {
+            self.status = format!("读取用户预设失败: {error}");
+            calc::presets::builtin_presets()
+        });
+    }
+
+    /// 已选择输出目录时直接保存，否则先选择输出目录
+    fn save_or_select_output_dir(&mut self, action: PendingAction) -> Task<Message> {
+        if self.config.output_path.is_empty() {
+            self.pending_action = Some(action);
+            return Task::perform(helpers::select_output_dir(), Message::SelectedOutputDir);
+        }
+        self.save_to_output_dir(action);
+        Task::none()
+    }
+
+    /// 将对应内容保存到输出目录
+    fn save_to_output_dir(&mut self, action: PendingAction) {
+        let output_path = &self.config.output_path;
+        let (result, name) = match action {
+            PendingAction::InputParams => (
+                self.caculator.save_parameters_to_file(output_path),
+                "保存输入参数",
+            ),
+            PendingAction::Result => (
+                self.caculator.save_results_to_file(output_path),
+                "保存计算结果",
+            ),
+            PendingAction::CalcCode => (
+                self.caculator.save_code_to_file(output_path),
+                "保存计算代码",
+            ),
+            PendingAction::ReportTex => (
+                self.caculator.save_report_tex_to_file(output_path),
+                "导出LaTeX计算书",
+            ),
+            PendingAction::ReportHtml => (
+                self.caculator
+                    .save_report_html_to_file(output_path, &self.config.units),
+                "导出HTML报告",
+            ),
+            PendingAction::ResultCsv => (
+                self.caculator
+                    .save_result_tables_to_files(output_path, ',', &self.config.units),
+                "导出CSV表格",
+            ),
+            PendingAction::Workbook => (
+                self.caculator
+                    .save_workbook_to_file(output_path, &self.config.units),
+                "导出Excel工作簿",
+            ),
+            PendingAction::Run => (
+                match &self.last_run {
+                    Some(run) => run.save_to_file(format!("{output_path}/run.json")),
+                    None => Err(std::io::Error::other("计算结果为空")),
+                },
+                "保存计算记录",
+            ),
+            PendingAction::CompareMarkdown => (
+                std::fs::write(
+                    format!("{output_path}/compare.md"),
+                    self.compare_tab
+                        .comparison()
+                        .to_markdown(&self.config.units),
+                ),
+                "导出对比Markdown",
+            ),
+            PendingAction::CompareCsv => (
+                std::fs::write(
+                    format!("{output_path}/compare.csv"),
+                    self.compare_tab
+                        .comparison()
+                        .to_csv(',', &self.config.units),
+                ),
+                "导出对比CSV",
+            ),
+            PendingAction::SensitivityMarkdown => (
+                match self.sensitivity_tab.report() {
+                    Some(report) => std::fs::write(
+                        format!("{output_path}/sensitivity.md"),
+                        report.to_markdown(&self.config.units),
+                    ),
+                    None => Err(std::io::Error::other("尚未进行灵敏度分析")),
+                },
+                "导出灵敏度Markdown",
+            ),
+            PendingAction::SensitivityCsv => (
+                match self.sensitivity_tab.report() {
+                    Some(report) => std::fs::write(
+                        format!("{output_path}/sensitivity.csv"),
+                        report.to_csv(',', &self.config.units),
+                    ),
+                    None => Err(std::io::Error::other("尚未进行灵敏度分析")),
+                },
+                "导出灵敏度CSV",
+            ),
+            PendingAction::OptimizeTrace => (
+                match self.optimize_tab.result() {
+                    Some(optimum) => std::fs::write(
+                        format!("{output_path}/optimize.csv"),
+                        optimum.trace_to_csv(',', &self.config.units),
+                    ),
+                    None => Err(std::io::Error::other("尚未进行参数优化")),
+                },
+                "导出优化迭代过程",
+            ),
+            PendingAction::UncertaintyMarkdown => (
+                match self.uncertainty_tab.report() {
+                    Some(report) => std::fs::write(
+                        format!("{output_path}/uncertainty.md"),
+                        report.to_markdown(&self.config.units),
+                    ),
+                    None => Err(std::io::Error::other("尚未进行不确定性分析")),
+                },
+                "导出不确定性Markdown",
+            ),
+            PendingAction::UncertaintyCsv => (
+                match self.uncertainty_tab.report() {
+                    Some(report) => std::fs::write(
+                        format!("{output_path}/uncertainty.csv"),
+                        report.to_csv(',', &self.config.units),
+                    ),
+                    None => Err(std::io::Error::other("尚未进行不确定性分析")),
+                },
+                "导出不确定性CSV",
+            ),
+            PendingAction::OffDesignMarkdown => (
+                match self.offdesign_tab.report() {
+                    Some(report) => std::fs::write(
+                        format!("{output_path}/offdesign.md"),
+                        report.to_markdown(&self.config.units),
+                    ),
+                    None => Err(std::io::Error::other("尚未进行变工况计算")),
+                },
+                "导出变工况Markdown",
+            ),
+            PendingAction::OffDesignCsv => (
+                match self.offdesign_tab.report() {
+                    Some(report) => std::fs::write(
+                        format!("{output_path}/offdesign.csv"),
+                        report.to_csv(',', &self.config.units),
+                    ),
+                    None => Err(std::io::Error::other("尚未进行变工况计算")),
+                },
+                "导出变工况CSV",
+            ),
+            PendingAction::SeasonalMarkdown => (
+                match self.seasonal_tab.report() {
+                    Some(report) => std::fs::write(
+                        format!("{output_path}/seasonal.md"),
+                        report.to_markdown(&self.config.units),
+                    ),
+                    None => Err(std::io::Error::other("尚未进行季节性分析")),
+                },
+                "导出季节性分析Markdown",
+            ),
+            PendingAction::SeasonalCsv => (
+                match self.seasonal_tab.report() {
+                    Some(report) => std::fs::write(
+                        format!("{output_path}/seasonal.csv"),
+                        report.to_csv(',', &self.config.units),
+                    ),
+                    None => Err(std::io::Error::other("尚未进行季节性分析")),
+                },
+                "导出季节性分析CSV",
+            ),
+            PendingAction::CondenserMarkdown => (
+                match self.condenser_tab.report() {
+                    Some(report) => std::fs::write(
+                        format!("{output_path}/condenser.md"),
+                        report.to_markdown(&self.config.units),
+                    ),
+                    None => Err(std::io::Error::other("尚未进行冷凝器详细模型计算")),
+                },
+                "导出冷凝器计算Markdown",
+            ),
+        };
+        self.status = match result {
+            Ok(_) => format!("{name}成功"),
+            Err(error) => format!("{name}失败{error}"),
+        };
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        // 输入框获得焦点时也响应快捷键
+        event::listen_with(|event, _status, _window| match event {
+            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                commands::shortcut(key, modifiers)
+            }
+            _ => None,
+        })
+    }
+
     fn get_theme(&self) -> Theme {
         self.theme.clone()
     }
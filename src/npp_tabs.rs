@@ -1,3 +1,10 @@
 pub mod calc_code;
+pub mod compare;
+pub mod condenser;
 pub mod input;
+pub mod offdesign;
+pub mod optimize;
 pub mod result;
+pub mod seasonal;
+pub mod sensitivity;
+pub mod uncertainty;
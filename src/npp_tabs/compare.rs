@@ -0,0 +1,206 @@
+use calc::{
+    compare::{compare_runs, CalcRun, CompareRow, Comparison, HIGHLIGHT_COUNT},
+    units::UnitSystem,
+};
+use iced::{
+    widget::{column, container, horizontal_rule, row, scrollable, text, Row},
+    Alignment, Length,
+};
+
+use crate::{components::labeled_button, Message, Tab};
+
+#[derive(Debug, Clone)]
+pub enum CompareMessage {
+    AddRuns(Vec<CalcRun>),
+    RemoveRun(usize),
+    Clear,
+}
+
+#[derive(Default)]
+pub struct CompareTab {
+    runs: Vec<CalcRun>,
+    comparison: Comparison,
+    /// 对比显示使用的单位制
+    pub units: UnitSystem,
+}
+
+impl CompareTab {
+    pub fn update(&mut self, message: CompareMessage) {
+        match message {
+            CompareMessage::AddRuns(runs) => self.runs.extend(runs),
+            CompareMessage::RemoveRun(index) => {
+                if index < self.runs.len() {
+                    self.runs.remove(index);
+                }
+            }
+            CompareMessage::Clear => self.runs.clear(),
+        }
+        self.comparison = compare_runs(&self.runs);
+    }
+
+    /// 当前的对比结果
+    pub fn comparison(&self) -> &Comparison {
+        &self.comparison
+    }
+
+    /// 对比表中的一行：前几列为说明，其后为各次计算的数值、变化量与相对变化
+    fn table_row<'a>(
+        &self,
+        leading: Vec<String>,
+        row: Option<&CompareRow>,
+        highlighted: bool,
+    ) -> Row<'a, Message> {
+        let units = &self.units;
+        let cell = |content: String, width: f32| {
+            let content = if highlighted {
+                text(content).style(text::danger)
+            } else {
+                text(content)
+            };
+            container(content.size(13)).width(Length::Fixed(width))
+        };
+        let mut cells = Row::new().spacing(8);
+        for (i, content) in leading.into_iter().enumerate() {
+            cells = cells.push(cell(content, if i == 0 { 160.0 } else { 240.0 }));
+        }
+        match row {
+            // 表头
+            None => {
+                for (i, name) in self.comparison.names.iter().enumerate() {
+                    if i == 0 {
+                        cells = cells.push(cell(format!("{name}(基准)"), 110.0));
+                    } else {
+                        cells = cells
+                            .push(cell(name.clone(), 110.0))
+                            .push(cell(String::from("Δ"), 90.0))
+                            .push(cell(String::from("Δ%"), 80.0));
+                    }
+                }
+            }
+            Some(row) => {
+                let unit = row.field.unit;
+                let value = |v: f64| {
+                    if v.is_nan() {
+                        String::from("-")
+                    } else {
+                        units.format(unit, v)
+                    }
+                };
+                cells = cells.push(cell(value(row.values[0]), 110.0));
+                for i in 1..row.values.len() {
+                    let delta = units.to_display(unit, row.values[i])
+                        - units.to_display(unit, row.values[0]);
+                    let relative = row
+                        .relative(i)
+                        .map_or(String::from("-"), |r| format!("{:+.3}%", r * 100.0));
+                    cells = cells
+                        .push(cell(value(row.values[i]), 110.0))
+                        .push(cell(format!("{delta:+.4}"), 90.0))
+                        .push(cell(relative, 80.0));
+                }
+            }
+        }
+        cells
+    }
+}
+
+impl Tab for CompareTab {
+    type Message = Message;
+
+    fn tab_label(&self) -> iced_aw::TabLabel {
+        iced_aw::TabLabel::Text(String::from("结果对比"))
+    }
+
+    fn content(&self) -> iced::Element<Self::Message> {
+        let toolbar = row![
+            labeled_button("添加当前结果", Message::CompareAddCurrent),
+            labeled_button("从文件添加", Message::CompareAddFromFile),
+            labeled_button("对比给水焓升分配", Message::CompareAllocations),
+            labeled_button("导出Markdown", Message::SaveCompareMarkdown),
+            labeled_button("导出CSV", Message::SaveCompareCsv),
+            labeled_button("清空", Message::CompareTab(CompareMessage::Clear)),
+        ]
+        .spacing(10);
+
+        let mut runs = column![].spacing(5);
+        for (index, run) in self.runs.iter().enumerate() {
+            let role = if index == 0 { "基准" } else { "对比" };
+            runs = runs.push(
+                row![
+                    text(format!("{}. {} ({role})", index + 1, run.name)).width(Length::Fill),
+                    labeled_button(
+                        "移除",
+                        Message::CompareTab(CompareMessage::RemoveRun(index))
+                    ),
+                ]
+                .align_y(Alignment::Center),
+            );
+        }
+
+        let mut content = column![toolbar, runs].spacing(15);
+        if self.runs.len() < 2 {
+            content = content.push(text(
+                "添加至少两组计算结果进行对比：计算后点击“添加当前结果”，\
+                 或从文件添加计算记录(run.json)或输出目录中的results.json。",
+            ));
+        } else {
+            let comparison = &self.comparison;
+            let largest = comparison.largest_changes(HIGHLIGHT_COUNT);
+            let is_largest = |row: &CompareRow| largest.iter().any(|r| std::ptr::eq(*r, row));
+
+            content = content
+                .push(text("输入参数差异").size(18))
+                .push(horizontal_rule(1));
+            if comparison.inputs.is_empty() {
+                content = content.push(text("各组输入参数相同"));
+            } else {
+                let mut inputs = column![self.table_row(
+                    vec![String::from("表格"), String::from("参数")],
+                    None,
+                    false
+                )]
+                .spacing(4);
+                for row in &comparison.inputs {
+                    inputs = inputs.push(self.table_row(
+                        vec![row.table.clone(), row.field.label_with_unit(&self.units)],
+                        Some(row),
+                        false,
+                    ));
+                }
+                content = content.push(inputs);
+            }
+
+            content = content
+                .push(
+                    text(format!(
+                        "计算结果 (变化最大的{HIGHLIGHT_COUNT}项以红色显示)"
+                    ))
+                    .size(18),
+                )
+                .push(horizontal_rule(1));
+            let mut results = column![self.table_row(
+                vec![String::from("表格"), String::from("参数")],
+                None,
+                false
+            )]
+            .spacing(4);
+            for row in &comparison.results {
+                results = results.push(self.table_row(
+                    vec![row.table.clone(), row.field.label_with_unit(&self.units)],
+                    Some(row),
+                    is_largest(row),
+                ));
+            }
+            content = content.push(results);
+        }
+
+        scrollable(content.padding(10))
+            .direction(scrollable::Direction::Both {
+                vertical: scrollable::Scrollbar::default(),
+                horizontal: scrollable::Scrollbar::default(),
+            })
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,228 @@
+use calc::{
+    condenser::{CondenserDesign, CondenserReport, TubeMaterial},
+    units::{Unit, UnitSystem},
+};
+use iced::{
+    widget::{
+        checkbox, column, container, horizontal_rule, pick_list, row, scrollable, text, text_input,
+        Row,
+    },
+    Alignment, Element, Length,
+};
+
+use crate::{components::labeled_button, Message, Tab};
+
+#[derive(Debug, Clone)]
+pub enum CondenserMessage {
+    AreaChanged(String),
+    MaterialSelected(TubeMaterial),
+    VelocityChanged(String),
+    CleanlinessChanged(String),
+    FlowChanged(String),
+    ToggleEnabled(bool),
+    UpdateReport(Box<CondenserReport>),
+}
+
+pub struct CondenserTab {
+    area: String,
+    material: TubeMaterial,
+    velocity: String,
+    cleanliness: String,
+    /// 冷却水流量，按当前单位制显示
+    flow: String,
+    /// 主计算是否使用详细模型
+    enabled: bool,
+    report: Option<CondenserReport>,
+    units: UnitSystem,
+}
+
+impl Default for CondenserTab {
+    fn default() -> Self {
+        let design = CondenserDesign::default();
+        let units = UnitSystem::default();
+        Self {
+            area: design.area.to_string(),
+            material: design.material,
+            velocity: design.velocity.to_string(),
+            cleanliness: design.cleanliness.to_string(),
+            flow: units.format(Unit::KgPerS, design.flow),
+            enabled: false,
+            report: None,
+            units,
+        }
+    }
+}
+
+impl CondenserTab {
+    pub fn update(&mut self, message: CondenserMessage) {
+        match message {
+            CondenserMessage::AreaChanged(area) => self.area = area,
+            CondenserMessage::MaterialSelected(material) => self.material = material,
+            CondenserMessage::VelocityChanged(velocity) => self.velocity = velocity,
+            CondenserMessage::CleanlinessChanged(cleanliness) => self.cleanliness = cleanliness,
+            CondenserMessage::FlowChanged(flow) => self.flow = flow,
+            CondenserMessage::ToggleEnabled(enabled) => self.enabled = enabled,
+            CondenserMessage::UpdateReport(report) => self.report = Some(*report),
+        }
+    }
+
+    /// 切换单位制，冷却水流量按新单位制重新显示
+    pub fn set_units(&mut self, units: UnitSystem) {
+        if let Ok(flow) = self.flow.trim().parse::<f64>() {
+            let flow = self.units.from_display(Unit::KgPerS, flow);
+            self.flow = units.format(Unit::KgPerS, flow);
+        }
+        self.units = units;
+    }
+
+    /// 当前的计算结果
+    pub fn report(&self) -> Option<&CondenserReport> {
+        self.report.as_ref()
+    }
+
+    /// 主计算是否使用详细模型
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 冷凝器的结构与运行参数
+    pub fn design(&self) -> Result<CondenserDesign, String> {
+        let number = |value: &str, name: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("{name}无效"))
+        };
+        Ok(CondenserDesign {
+            area: number(&self.area, "传热面积")?,
+            material: self.material,
+            velocity: number(&self.velocity, "管内流速")?,
+            cleanliness: number(&self.cleanliness, "清洁系数")?,
+            flow: self
+                .units
+                .from_display(Unit::KgPerS, number(&self.flow, "冷却水流量")?),
+        })
+    }
+
+    /// 表格中的一行
+    fn table_row<'a>(cells: Vec<String>, width: f32) -> Row<'a, Message> {
+        let mut row = Row::new().spacing(8);
+        for content in cells {
+            row = row.push(container(text(content).size(13)).width(Length::Fixed(width)));
+        }
+        row
+    }
+
+    fn report_view<'a>(&'a self, report: &'a CondenserReport) -> Element<'a, Message> {
+        let units = &self.units;
+        let mut content = column![text("计算结果").size(18), horizontal_rule(1)].spacing(8);
+        if !report.converged {
+            content = content.push(text(format!(
+                "与全厂热平衡迭代{}次热负荷仍未收敛",
+                report.iterations
+            )));
+        }
+        for (name, value) in report.heat_transfer_rows(units) {
+            content = content.push(Self::table_row(vec![name, value], 280.0));
+        }
+
+        let mut table = column![Self::table_row(
+            vec![
+                String::from("参数"),
+                String::from("简化模型"),
+                String::from("详细模型"),
+            ],
+            180.0
+        )]
+        .spacing(4);
+        for (name, simple, detailed) in report.comparison_rows(units) {
+            table = table.push(Self::table_row(vec![name, simple, detailed], 180.0));
+        }
+        content
+            .push(text("与简化模型对比").size(16))
+            .push(table)
+            .into()
+    }
+}
+
+impl Tab for CondenserTab {
+    type Message = Message;
+
+    fn tab_label(&self) -> iced_aw::TabLabel {
+        iced_aw::TabLabel::Text(String::from("冷凝器"))
+    }
+
+    fn content(&self) -> iced::Element<Self::Message> {
+        let input = |label: String, value: &str, on_input: fn(String) -> CondenserMessage| {
+            row![
+                container(text(label)).width(Length::Fixed(200.0)),
+                text_input("", value)
+                    .on_input(move |value| Message::CondenserTab(on_input(value)))
+                    .width(Length::Fixed(120.0)),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center)
+        };
+        let flow_label = match self.units.label(Unit::KgPerS) {
+            "" => String::from("冷却水流量"),
+            label => format!("冷却水流量({label})"),
+        };
+        let settings = column![
+            input(
+                String::from("传热面积(m²)"),
+                &self.area,
+                CondenserMessage::AreaChanged
+            ),
+            row![
+                container(text("冷凝管材料")).width(Length::Fixed(200.0)),
+                pick_list(TubeMaterial::ALL, Some(self.material), |material| {
+                    Message::CondenserTab(CondenserMessage::MaterialSelected(material))
+                }),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+            input(
+                String::from("管内流速(m/s)"),
+                &self.velocity,
+                CondenserMessage::VelocityChanged
+            ),
+            input(
+                String::from("清洁系数"),
+                &self.cleanliness,
+                CondenserMessage::CleanlinessChanged
+            ),
+            input(flow_label, &self.flow, CondenserMessage::FlowChanged),
+        ]
+        .spacing(8);
+
+        let toolbar = row![
+            checkbox("主计算使用详细模型", self.enabled).on_toggle(
+                |enabled| Message::CondenserTab(CondenserMessage::ToggleEnabled(enabled))
+            ),
+            labeled_button("开始计算", Message::RunCondenser),
+            labeled_button("导出Markdown", Message::SaveCondenserMarkdown),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        let mut content = column![
+            toolbar,
+            text("简化模型由输入参数给定冷却水温升与传热端差。详细模型按HEI标准计算总传热系数 U = C√v·F_m·F_t·F_c，由冷凝器热负荷与冷却水流量求温升、端差及背压，并与全厂热平衡迭代至热负荷收敛；管内流速为给定冷却水流量下的流速。勾选“主计算使用详细模型”后，“计算”菜单中的开始计算 (F5) 以详细模型的温升与端差代替输入值。")
+                .size(13),
+            settings,
+        ]
+        .spacing(15);
+        if let Some(report) = &self.report {
+            content = content.push(self.report_view(report));
+        }
+
+        scrollable(content.padding(10))
+            .direction(scrollable::Direction::Both {
+                vertical: scrollable::Scrollbar::default(),
+                horizontal: scrollable::Scrollbar::default(),
+            })
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
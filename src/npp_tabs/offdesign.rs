@@ -0,0 +1,248 @@
+use std::fmt::Display;
+
+use calc::{
+    offdesign::{OffDesignReport, COLUMNS, DEFAULT_EFFICIENCY_COEFFICIENT, DEFAULT_LOADS},
+    units::UnitSystem,
+};
+use iced::{
+    widget::{
+        column, container, horizontal_rule, pick_list, row, scrollable, text, text_input, Row,
+    },
+    Alignment, Element, Length,
+};
+
+use crate::{
+    components::{labeled_button, line_chart, LineChart},
+    Message, Tab,
+};
+
+/// 默认作为曲线纵坐标的列
+const DEFAULT_CHART_COLUMN: &str = "η_eNPP";
+
+/// 曲线图纵坐标所用的列，按`COLUMNS`中的序号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChartColumn(usize);
+
+impl ChartColumn {
+    /// 除负荷外的各列
+    fn all() -> Vec<Self> {
+        (1..COLUMNS.len()).map(Self).collect()
+    }
+}
+
+impl Default for ChartColumn {
+    fn default() -> Self {
+        Self(
+            COLUMNS
+                .iter()
+                .position(|column| column.symbol == DEFAULT_CHART_COLUMN)
+                .unwrap_or(1),
+        )
+    }
+}
+
+impl Display for ChartColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let column = &COLUMNS[self.0];
+        write!(f, "{} ({})", column.label, column.symbol)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum OffDesignMessage {
+    LoadsChanged(String),
+    CoefficientChanged(String),
+    ChartColumnSelected(ChartColumn),
+    UpdateReport(Box<OffDesignReport>),
+}
+
+pub struct OffDesignTab {
+    /// 以逗号分隔的负荷百分数
+    loads: String,
+    coefficient: String,
+    chart_column: ChartColumn,
+    report: Option<OffDesignReport>,
+    /// 结果显示使用的单位制
+    pub units: UnitSystem,
+}
+
+impl Default for OffDesignTab {
+    fn default() -> Self {
+        Self {
+            loads: DEFAULT_LOADS
+                .iter()
+                .map(|load| (load * 100.0).to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            coefficient: DEFAULT_EFFICIENCY_COEFFICIENT.to_string(),
+            chart_column: ChartColumn::default(),
+            report: None,
+            units: UnitSystem::default(),
+        }
+    }
+}
+
+impl OffDesignTab {
+    pub fn update(&mut self, message: OffDesignMessage) {
+        match message {
+            OffDesignMessage::LoadsChanged(loads) => self.loads = loads,
+            OffDesignMessage::CoefficientChanged(coefficient) => self.coefficient = coefficient,
+            OffDesignMessage::ChartColumnSelected(column) => self.chart_column = column,
+            OffDesignMessage::UpdateReport(report) => self.report = Some(*report),
+        }
+    }
+
+    /// 当前的计算结果
+    pub fn report(&self) -> Option<&OffDesignReport> {
+        self.report.as_ref()
+    }
+
+    /// 负荷(占设计功率的比例)及内效率修正系数
+    pub fn settings(&self) -> Result<(Vec<f64>, f64), String> {
+        let loads = self
+            .loads
+            .split([',', '，', ' '])
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| {
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .map(|load| load / 100.0)
+                    .map_err(|_| format!("负荷“{value}”无效"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let coefficient = self
+            .coefficient
+            .trim()
+            .parse()
+            .map_err(|_| String::from("内效率修正系数无效"))?;
+        Ok((loads, coefficient))
+    }
+
+    /// 表格中的一行
+    fn table_row<'a>(cells: Vec<String>, width: f32) -> Row<'a, Message> {
+        let mut row = Row::new().spacing(8);
+        for content in cells {
+            row = row.push(container(text(content).size(13)).width(Length::Fixed(width)));
+        }
+        row
+    }
+
+    fn report_view<'a>(&'a self, report: &'a OffDesignReport) -> Element<'a, Message> {
+        let units = &self.units;
+        let mut content = column![text("计算结果").size(18), horizontal_rule(1)].spacing(8);
+        for (load, error) in &report.failures {
+            content = content.push(text(format!("负荷 {:.1}% 计算失败: {error}", load * 100.0)));
+        }
+        for point in report.points.iter().filter(|point| !point.converged) {
+            content = content.push(text(format!(
+                "负荷 {:.1}% 迭代{}次流量比仍未收敛",
+                point.load * 100.0,
+                point.iterations
+            )));
+        }
+        if report.points.is_empty() {
+            return content.into();
+        }
+
+        let chart = LineChart {
+            points: report
+                .points
+                .iter()
+                .map(|point| {
+                    let column = &COLUMNS[self.chart_column.0];
+                    (
+                        point.load * 100.0,
+                        units.to_display(column.unit, (column.value)(point)),
+                    )
+                })
+                .collect(),
+            x_label: String::from("负荷(%)"),
+            y_label: COLUMNS[self.chart_column.0].header(units),
+            x_bounds: Some((0.0, 100.0)),
+        };
+        content = content
+            .push(
+                row![
+                    text("曲线纵坐标"),
+                    pick_list(ChartColumn::all(), Some(self.chart_column), |column| {
+                        Message::OffDesignTab(OffDesignMessage::ChartColumnSelected(column))
+                    }),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+            )
+            .push(line_chart(chart));
+
+        // 每个负荷一列，每个参数一行
+        let mut headers = vec![String::from("参数")];
+        headers.extend(
+            report
+                .points
+                .iter()
+                .map(|point| format!("{}%", point.load * 100.0)),
+        );
+        let mut table = column![Self::table_row(headers, 110.0)].spacing(4);
+        for column in COLUMNS.iter().skip(1) {
+            let mut row = Row::new().spacing(8).push(
+                container(text(format!("{} {}", column.label, column.header(units))).size(13))
+                    .width(Length::Fixed(260.0)),
+            );
+            for point in &report.points {
+                row = row.push(
+                    container(text(column.format(point, units)).size(13))
+                        .width(Length::Fixed(110.0)),
+                );
+            }
+            table = table.push(row);
+        }
+        content.push(table).into()
+    }
+}
+
+impl Tab for OffDesignTab {
+    type Message = Message;
+
+    fn tab_label(&self) -> iced_aw::TabLabel {
+        iced_aw::TabLabel::Text(String::from("变工况"))
+    }
+
+    fn content(&self) -> iced::Element<Self::Message> {
+        let toolbar = row![
+            text("负荷(%)"),
+            text_input("100, 75, 50, 30", &self.loads)
+                .on_input(|value| Message::OffDesignTab(OffDesignMessage::LoadsChanged(value)))
+                .width(Length::Fixed(200.0)),
+            text("内效率修正系数C"),
+            text_input("0.2", &self.coefficient)
+                .on_input(|value| {
+                    Message::OffDesignTab(OffDesignMessage::CoefficientChanged(value))
+                })
+                .width(Length::Fixed(70.0)),
+            labeled_button("开始计算", Message::RunOffDesign),
+            labeled_button("导出Markdown", Message::SaveOffDesignMarkdown),
+            labeled_button("导出CSV", Message::SaveOffDesignCsv),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        let mut content = column![
+            toolbar,
+            text("以当前输入参数的计算结果为设计工况：汽缸进排汽压力与最高一级抽汽压力按斯托多拉锥体定律随流量变化，蒸汽发生器压力不变、调节阀节流，高低压缸内效率按 η = η₀[1 - C(1 - G/G₀)²] 修正，冷却水流量不变。")
+                .size(13),
+        ]
+        .spacing(15);
+        if let Some(report) = &self.report {
+            content = content.push(self.report_view(report));
+        }
+
+        scrollable(content.padding(10))
+            .direction(scrollable::Direction::Both {
+                vertical: scrollable::Scrollbar::default(),
+                horizontal: scrollable::Scrollbar::default(),
+            })
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,317 @@
+use calc::{
+    fields::FieldMeta,
+    optimize::{candidate_fields, DesignVariable, Objective, Optimum, DEFAULT_VARIABLES},
+    units::{Unit, UnitSystem},
+};
+use iced::{
+    widget::{
+        checkbox, column, container, horizontal_rule, pick_list, row, scrollable, text, text_input,
+        Row,
+    },
+    Alignment, Element, Length,
+};
+
+use crate::{components::labeled_button, Message, Tab};
+
+#[derive(Debug, Clone)]
+pub enum OptimizeMessage {
+    ObjectiveSelected(Objective),
+    /// 字段名及是否作为优化变量
+    ToggleVariable(&'static str, bool),
+    LowerChanged(&'static str, String),
+    UpperChanged(&'static str, String),
+    UpdateResult(Box<Optimum>),
+}
+
+/// 一个候选优化变量的输入框内容
+struct VariableInput {
+    field: &'static FieldMeta,
+    enabled: bool,
+    lower: String,
+    upper: String,
+}
+
+pub struct OptimizeTab {
+    objective: Objective,
+    inputs: Vec<VariableInput>,
+    result: Option<Optimum>,
+    /// 上下限输入框使用的单位制
+    units: UnitSystem,
+}
+
+impl Default for OptimizeTab {
+    fn default() -> Self {
+        let units = UnitSystem::default();
+        let inputs = candidate_fields()
+            .map(|field| {
+                let (lower, upper) = field.range.unwrap_or_default();
+                VariableInput {
+                    field,
+                    enabled: DEFAULT_VARIABLES.contains(&field.key),
+                    lower: units.format(field.unit, lower),
+                    upper: units.format(field.unit, upper),
+                }
+            })
+            .collect();
+        Self {
+            objective: Objective::default(),
+            inputs,
+            result: None,
+            units,
+        }
+    }
+}
+
+impl OptimizeTab {
+    pub fn update(&mut self, message: OptimizeMessage) {
+        match message {
+            OptimizeMessage::ObjectiveSelected(objective) => self.objective = objective,
+            OptimizeMessage::ToggleVariable(key, enabled) => {
+                if let Some(input) = self.input_mut(key) {
+                    input.enabled = enabled;
+                }
+            }
+            OptimizeMessage::LowerChanged(key, value) => {
+                if let Some(input) = self.input_mut(key) {
+                    input.lower = value;
+                }
+            }
+            OptimizeMessage::UpperChanged(key, value) => {
+                if let Some(input) = self.input_mut(key) {
+                    input.upper = value;
+                }
+            }
+            OptimizeMessage::UpdateResult(result) => self.result = Some(*result),
+        }
+    }
+
+    fn input_mut(&mut self, key: &str) -> Option<&mut VariableInput> {
+        self.inputs.iter_mut().find(|input| input.field.key == key)
+    }
+
+    /// 切换单位制，按新单位制重新显示上下限，无法解析的输入保持不变
+    pub fn set_units(&mut self, units: UnitSystem) {
+        for input in &mut self.inputs {
+            let unit = input.field.unit;
+            for value in [&mut input.lower, &mut input.upper] {
+                if let Ok(parsed) = value.trim().parse::<f64>() {
+                    *value = units.format(unit, self.units.from_display(unit, parsed));
+                }
+            }
+        }
+        self.units = units;
+    }
+
+    pub fn objective(&self) -> Objective {
+        self.objective
+    }
+
+    /// 当前的优化结果
+    pub fn result(&self) -> Option<&Optimum> {
+        self.result.as_ref()
+    }
+
+    /// 按当前单位制读取选中的优化变量及其上下限
+    pub fn variables(&self) -> Result<Vec<DesignVariable>, String> {
+        self.inputs
+            .iter()
+            .filter(|input| input.enabled)
+            .map(|input| {
+                let unit = input.field.unit;
+                let parse = |value: &str| {
+                    value
+                        .trim()
+                        .parse::<f64>()
+                        .map(|value| self.units.from_display(unit, value))
+                        .map_err(|_| format!("{}的上下限无效", input.field.label))
+                };
+                Ok(DesignVariable {
+                    field: input.field,
+                    lower: parse(&input.lower)?,
+                    upper: parse(&input.upper)?,
+                })
+            })
+            .collect()
+    }
+
+    /// 表格中的一行
+    fn table_row<'a>(cells: Vec<String>, widths: &[f32]) -> Row<'a, Message> {
+        let mut row = Row::new().spacing(8);
+        for (content, &width) in cells.into_iter().zip(widths) {
+            row = row.push(container(text(content).size(13)).width(Length::Fixed(width)));
+        }
+        row
+    }
+
+    fn result_view(&self, result: &Optimum) -> Element<'_, Message> {
+        let units = &self.units;
+        let mut content = column![
+            text("优化结果").size(18),
+            horizontal_rule(1),
+            text(format!(
+                "{}，{}，迭代{}次，计算{}次，{}",
+                result.objective,
+                result.method.label(),
+                result.trace.len(),
+                result.evaluations,
+                if result.converged {
+                    "已收敛"
+                } else {
+                    "达到最大迭代次数仍未收敛"
+                }
+            )),
+            text(format!(
+                "η_eNPP: {} → {} {}，热耗率 {:.2} kJ/kWh",
+                units.format(Unit::Fraction, result.initial_eta_enpp),
+                units.format(Unit::Fraction, result.eta_enpp),
+                units.label(Unit::Fraction),
+                result.heat_rate
+            )),
+        ]
+        .spacing(8);
+
+        let widths = [260.0, 100.0, 100.0, 100.0, 80.0];
+        let mut table = column![Self::table_row(
+            ["参数", "下限", "最优值", "上限", "边界"]
+                .map(String::from)
+                .to_vec(),
+            &widths
+        )]
+        .spacing(4);
+        let active = result.active_bounds();
+        for (variable, &value) in result.variables.iter().zip(&result.values) {
+            let unit = variable.field.unit;
+            let bound = active
+                .iter()
+                .find(|(v, _)| v.field.key == variable.field.key)
+                .map_or("", |(_, bound)| bound.label());
+            table = table.push(Self::table_row(
+                vec![
+                    variable.field.label_with_unit(units),
+                    units.format(unit, variable.lower),
+                    units.format(unit, value),
+                    units.format(unit, variable.upper),
+                    bound.to_string(),
+                ],
+                &widths,
+            ));
+        }
+        content = content.push(table);
+        content = content.push(text(if active.is_empty() {
+            String::from("最优点位于范围内部，没有起作用的边界")
+        } else {
+            format!(
+                "起作用的边界: {}",
+                active
+                    .iter()
+                    .map(|(variable, bound)| format!("{} {}", variable.field.symbol, bound.label()))
+                    .collect::<Vec<_>>()
+                    .join("，")
+            )
+        }));
+
+        content = content
+            .push(text("迭代过程").size(18))
+            .push(horizontal_rule(1));
+        let mut widths = vec![60.0];
+        widths.extend(result.variables.iter().map(|_| 110.0));
+        widths.extend([110.0, 130.0]);
+        let mut headers = vec![String::from("迭代")];
+        headers.extend(
+            result
+                .variables
+                .iter()
+                .map(|variable| variable.field.symbol.to_string()),
+        );
+        headers.extend([
+            format!("η_eNPP({})", units.label(Unit::Fraction)),
+            String::from("热耗率(kJ/kWh)"),
+        ]);
+        let mut trace = column![Self::table_row(headers, &widths)].spacing(4);
+        for point in &result.trace {
+            let mut cells = vec![point.iteration.to_string()];
+            cells.extend(
+                result
+                    .variables
+                    .iter()
+                    .zip(&point.values)
+                    .map(|(variable, &value)| units.format(variable.field.unit, value)),
+            );
+            cells.extend([
+                units.format(Unit::Fraction, point.eta_enpp),
+                format!("{:.2}", point.heat_rate),
+            ]);
+            trace = trace.push(Self::table_row(cells, &widths));
+        }
+        content.push(trace).into()
+    }
+}
+
+impl Tab for OptimizeTab {
+    type Message = Message;
+
+    fn tab_label(&self) -> iced_aw::TabLabel {
+        iced_aw::TabLabel::Text(String::from("参数优化"))
+    }
+
+    fn content(&self) -> iced::Element<Self::Message> {
+        let toolbar = row![
+            pick_list(Objective::ALL, Some(self.objective), |objective| {
+                Message::OptimizeTab(OptimizeMessage::ObjectiveSelected(objective))
+            }),
+            labeled_button("开始优化", Message::RunOptimization),
+            labeled_button("应用最优参数", Message::ApplyOptimum),
+            labeled_button("导出迭代过程CSV", Message::SaveOptimizeTrace),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        let units = &self.units;
+        let mut variables = column![
+            text("优化变量及上下限").size(18),
+            horizontal_rule(1),
+            text("勾选需要优化的参数，以当前输入参数为初始点；单个变量使用黄金分割法，多个变量使用Nelder–Mead单纯形法。")
+                .size(13),
+        ]
+        .spacing(6);
+        for input in &self.inputs {
+            let key = input.field.key;
+            variables = variables.push(
+                row![
+                    checkbox(input.field.label_with_unit(units), input.enabled)
+                        .on_toggle(move |enabled| {
+                            Message::OptimizeTab(OptimizeMessage::ToggleVariable(key, enabled))
+                        })
+                        .width(Length::Fixed(300.0)),
+                    text_input("下限", &input.lower)
+                        .on_input(move |value| {
+                            Message::OptimizeTab(OptimizeMessage::LowerChanged(key, value))
+                        })
+                        .width(Length::Fixed(110.0)),
+                    text("~"),
+                    text_input("上限", &input.upper)
+                        .on_input(move |value| {
+                            Message::OptimizeTab(OptimizeMessage::UpperChanged(key, value))
+                        })
+                        .width(Length::Fixed(110.0)),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        let mut content = column![toolbar, variables].spacing(15);
+        if let Some(result) = &self.result {
+            content = content.push(self.result_view(result));
+        }
+
+        scrollable(content.padding(10))
+            .direction(scrollable::Direction::Both {
+                vertical: scrollable::Scrollbar::default(),
+                horizontal: scrollable::Scrollbar::default(),
+            })
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -1,7 +1,7 @@
 mod result_formatter;
 use result_formatter::format_result_to_markdown;
 
-use calc::parameters;
+use calc::{parameters, units::UnitSystem};
 use iced::{
     widget::markdown,
     widget::{container, scrollable},
@@ -20,13 +20,15 @@ pub enum ResultMessage {
 pub struct ResultTab {
     theme: Theme,
     result_markdown: Vec<markdown::Item>,
+    /// 结果显示使用的单位制
+    pub units: UnitSystem,
 }
 
 impl ResultTab {
     pub fn update(&mut self, message: ResultMessage) {
         match message {
             ResultMessage::UpdateResult(result) => {
-                let result_markdown_string = format_result_to_markdown(&result);
+                let result_markdown_string = format_result_to_markdown(&result, &self.units);
                 self.result_markdown = markdown::parse(&result_markdown_string).collect();
             }
             ResultMessage::LinkClicked(url) => {
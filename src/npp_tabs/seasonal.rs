@@ -0,0 +1,169 @@
+use calc::{
+    seasonal::{Basis, ProfilePoint, SeasonalReport},
+    units::{Unit, UnitSystem},
+};
+use iced::{
+    widget::{column, container, horizontal_rule, pick_list, row, scrollable, text, Row},
+    Alignment, Element, Length,
+};
+
+use crate::{
+    components::{labeled_button, line_chart, LineChart},
+    Message, Tab,
+};
+
+#[derive(Debug, Clone)]
+pub enum SeasonalMessage {
+    UpdateProfile(Vec<ProfilePoint>),
+    BasisSelected(Basis),
+    UpdateReport(Box<SeasonalReport>),
+}
+
+#[derive(Default)]
+pub struct SeasonalTab {
+    /// 冷却水温度曲线
+    profile: Vec<ProfilePoint>,
+    basis: Basis,
+    report: Option<SeasonalReport>,
+    /// 结果显示使用的单位制
+    pub units: UnitSystem,
+}
+
+impl SeasonalTab {
+    pub fn update(&mut self, message: SeasonalMessage) {
+        match message {
+            SeasonalMessage::UpdateProfile(profile) => {
+                self.profile = profile;
+                self.report = None;
+            }
+            SeasonalMessage::BasisSelected(basis) => self.basis = basis,
+            SeasonalMessage::UpdateReport(report) => self.report = Some(*report),
+        }
+    }
+
+    /// 当前的计算结果
+    pub fn report(&self) -> Option<&SeasonalReport> {
+        self.report.as_ref()
+    }
+
+    /// 冷却水温度曲线及计算基准
+    pub fn settings(&self) -> Result<(&[ProfilePoint], Basis), String> {
+        if self.profile.is_empty() {
+            return Err(String::from("尚未加载冷却水温度曲线"));
+        }
+        Ok((&self.profile, self.basis))
+    }
+
+    /// 表格中的一行
+    fn table_row<'a>(cells: Vec<String>, width: f32) -> Row<'a, Message> {
+        let mut row = Row::new().spacing(8);
+        for content in cells {
+            row = row.push(container(text(content).size(13)).width(Length::Fixed(width)));
+        }
+        row
+    }
+
+    fn report_view<'a>(&'a self, report: &'a SeasonalReport) -> Element<'a, Message> {
+        let units = &self.units;
+        let mut content = column![
+            text(format!("计算结果 ({})", report.basis)).size(18),
+            horizontal_rule(1)
+        ]
+        .spacing(8);
+        for (label, error) in &report.failures {
+            content = content.push(text(format!("时段“{label}”计算失败: {error}")));
+        }
+        if report.points.is_empty() {
+            return content.into();
+        }
+
+        let mut summary = column![].spacing(4);
+        for (name, value) in report.annual_rows(units) {
+            summary = summary.push(Self::table_row(vec![name, value], 200.0));
+        }
+        content = content.push(summary);
+
+        // 电功率不变时效率随水温变化，热功率不变时电功率随水温变化
+        let (unit, y_label) = match report.basis {
+            Basis::ConstantElectric => (Unit::Fraction, "η_eNPP"),
+            Basis::ConstantThermal => (Unit::MW, "N_e"),
+        };
+        let y_label = match units.label(unit) {
+            "" => y_label.to_string(),
+            label => format!("{y_label}({label})"),
+        };
+        let chart = LineChart {
+            points: report
+                .points
+                .iter()
+                .enumerate()
+                .map(|(i, point)| {
+                    let value = match report.basis {
+                        Basis::ConstantElectric => point.eta_enpp,
+                        Basis::ConstantThermal => point.ne,
+                    };
+                    ((i + 1) as f64, units.to_display(unit, value))
+                })
+                .collect(),
+            x_label: String::from("时段序号"),
+            y_label,
+            x_bounds: None,
+        };
+        content = content.push(line_chart(chart));
+
+        let mut table = column![Self::table_row(SeasonalReport::headers(units), 110.0)].spacing(4);
+        for point in &report.points {
+            table = table.push(Self::table_row(SeasonalReport::cells(point, units), 110.0));
+        }
+        content.push(table).into()
+    }
+}
+
+impl Tab for SeasonalTab {
+    type Message = Message;
+
+    fn tab_label(&self) -> iced_aw::TabLabel {
+        iced_aw::TabLabel::Text(String::from("季节性"))
+    }
+
+    fn content(&self) -> iced::Element<Self::Message> {
+        let toolbar = row![
+            labeled_button("加载温度曲线", Message::LoadSeasonalProfile),
+            text("计算基准"),
+            pick_list(Basis::ALL, Some(self.basis), |basis| {
+                Message::SeasonalTab(SeasonalMessage::BasisSelected(basis))
+            }),
+            labeled_button("开始计算", Message::RunSeasonal),
+            labeled_button("导出Markdown", Message::SaveSeasonalMarkdown),
+            labeled_button("导出CSV", Message::SaveSeasonalCsv),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        let profile = if self.profile.is_empty() {
+            String::from("尚未加载温度曲线")
+        } else {
+            let hours: f64 = self.profile.iter().map(|point| point.hours).sum();
+            format!("已加载{}个时段，共{hours}小时", self.profile.len())
+        };
+        let mut content = column![
+            toolbar,
+            text("温度曲线文件每行为“时段,冷却水进口温度(℃)[,小时数]”，可含表头与#注释；未给出小时数时，12行视为逐月，否则每行为1小时。以当前输入参数为基础，逐时段替换冷却水进口温度求解全厂热平衡。")
+                .size(13),
+            text(profile),
+        ]
+        .spacing(15);
+        if let Some(report) = &self.report {
+            content = content.push(self.report_view(report));
+        }
+
+        scrollable(content.padding(10))
+            .direction(scrollable::Direction::Both {
+                vertical: scrollable::Scrollbar::default(),
+                horizontal: scrollable::Scrollbar::default(),
+            })
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,198 @@
+use calc::{
+    sensitivity::{Outcome, SensitivityReport, SensitivityRow},
+    units::{Unit, UnitSystem},
+};
+use iced::{
+    widget::{column, container, horizontal_rule, row, scrollable, text, Row},
+    Alignment, Background, Color, Element, Length, Theme,
+};
+
+use crate::{components::labeled_button, Message, Tab};
+
+/// 龙卷风图中单侧条形的最大宽度
+const BAR_WIDTH: f32 = 240.0;
+
+#[derive(Debug, Clone)]
+pub enum SensitivityMessage {
+    UpdateReport(Box<SensitivityReport>),
+}
+
+#[derive(Default)]
+pub struct SensitivityTab {
+    report: Option<SensitivityReport>,
+    /// 基准值显示使用的单位制
+    pub units: UnitSystem,
+}
+
+impl SensitivityTab {
+    pub fn update(&mut self, message: SensitivityMessage) {
+        match message {
+            SensitivityMessage::UpdateReport(report) => self.report = Some(*report),
+        }
+    }
+
+    /// 当前的分析结果
+    pub fn report(&self) -> Option<&SensitivityReport> {
+        self.report.as_ref()
+    }
+
+    /// 龙卷风图中的一行：负弹性系数向左，正弹性系数向右
+    fn tornado_row<'a>(row: &SensitivityRow, elasticity: f64, max: f64) -> Row<'a, Message> {
+        let width = if max > 0.0 {
+            (elasticity.abs() / max) as f32 * BAR_WIDTH
+        } else {
+            0.0
+        };
+        let bar = move |color: fn(&Theme) -> Color| {
+            container(text(""))
+                .width(Length::Fixed(width.max(1.0)))
+                .height(Length::Fixed(14.0))
+                .style(move |theme: &Theme| container::Style {
+                    background: Some(Background::Color(color(theme))),
+                    ..container::Style::default()
+                })
+        };
+        let side = |content: Option<Element<'a, Message>>, align: Alignment| {
+            let inner: Element<'a, Message> = content.unwrap_or_else(|| text("").into());
+            container(inner)
+                .width(Length::Fixed(BAR_WIDTH))
+                .align_x(align)
+        };
+        let (left, right) = if elasticity < 0.0 {
+            (
+                Some(bar(|theme| theme.extended_palette().danger.base.color).into()),
+                None,
+            )
+        } else {
+            (
+                None,
+                Some(bar(|theme| theme.extended_palette().primary.base.color).into()),
+            )
+        };
+        row![
+            container(text(format!("{} ({})", row.field.label, row.field.symbol)).size(13))
+                .width(Length::Fixed(260.0)),
+            side(left, Alignment::End),
+            side(right, Alignment::Start),
+            text(format!("{elasticity:+.4}")).size(13),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center)
+    }
+
+    /// 明细表中的一行
+    fn table_row<'a>(cells: Vec<String>) -> Row<'a, Message> {
+        let widths = [40.0, 260.0, 100.0, 110.0, 110.0, 90.0, 90.0, 60.0, 240.0];
+        let mut row = Row::new().spacing(8);
+        for (content, width) in cells.into_iter().zip(widths) {
+            row = row.push(container(text(content).size(13)).width(Length::Fixed(width)));
+        }
+        row
+    }
+}
+
+impl Tab for SensitivityTab {
+    type Message = Message;
+
+    fn tab_label(&self) -> iced_aw::TabLabel {
+        iced_aw::TabLabel::Text(String::from("灵敏度分析"))
+    }
+
+    fn content(&self) -> iced::Element<Self::Message> {
+        let toolbar = row![
+            labeled_button("分析当前参数", Message::RunSensitivity),
+            labeled_button("导出Markdown", Message::SaveSensitivityMarkdown),
+            labeled_button("导出CSV", Message::SaveSensitivityCsv),
+        ]
+        .spacing(10);
+
+        let mut content = column![toolbar].spacing(15);
+        let Some(report) = &self.report else {
+            content = content.push(text(
+                "点击“分析当前参数”，逐个扰动输入参数并计算核电厂效率与反应堆热功率的偏导数及弹性系数。",
+            ));
+            return scrollable(content.padding(10))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into();
+        };
+        let units = &self.units;
+
+        content = content.push(text(format!(
+            "基准：η_eNPP = {} {}，Q_R = {} {}。相对扰动步长 {:e}，弹性系数为 ∂y/∂x · x/y，偏导数按内部单位计算。",
+            units.format(Unit::Fraction, report.eta_enpp),
+            units.label(Unit::Fraction),
+            units.format(Unit::GW, report.q_r),
+            units.label(Unit::GW),
+            report.step,
+        )));
+
+        content = content
+            .push(text("η_eNPP 弹性系数").size(18))
+            .push(horizontal_rule(1));
+        let max = report.max_elasticity();
+        let mut tornado = column![].spacing(4);
+        for row in &report.rows {
+            if let Some(derivative) = row.derivative() {
+                tornado = tornado.push(Self::tornado_row(row, derivative.elasticity_eta, max));
+            }
+        }
+        content = content.push(tornado);
+
+        content = content
+            .push(text("全部参数").size(18))
+            .push(horizontal_rule(1));
+        let mut table = column![Self::table_row(
+            [
+                "排名",
+                "参数",
+                "基准值",
+                "∂η_eNPP/∂x",
+                "∂Q_R/∂x",
+                "η_eNPP弹性",
+                "Q_R弹性",
+                "差分",
+                "说明",
+            ]
+            .map(String::from)
+            .to_vec()
+        )]
+        .spacing(4);
+        for (i, row) in report.rows.iter().enumerate() {
+            let mut cells = vec![
+                (i + 1).to_string(),
+                row.field.label_with_unit(units),
+                units.format(row.field.unit, row.value),
+            ];
+            match &row.outcome {
+                Outcome::Derivative(d) => cells.extend([
+                    format!("{:.4e}", d.d_eta),
+                    format!("{:.4e}", d.d_q_r),
+                    format!("{:+.4}", d.elasticity_eta),
+                    format!("{:+.4}", d.elasticity_q_r),
+                    d.method.label().to_string(),
+                    row.note.clone().unwrap_or_default(),
+                ]),
+                Outcome::Skipped(reason) => {
+                    cells.extend(["-", "-", "-", "-", "-"].map(String::from));
+                    cells.push(format!("未分析: {reason}"));
+                }
+                Outcome::Failed(error) => {
+                    cells.extend(["-", "-", "-", "-", "-"].map(String::from));
+                    cells.push(format!("计算失败: {error}"));
+                }
+            }
+            table = table.push(Self::table_row(cells));
+        }
+        content = content.push(table);
+
+        scrollable(content.padding(10))
+            .direction(scrollable::Direction::Both {
+                vertical: scrollable::Scrollbar::default(),
+                horizontal: scrollable::Scrollbar::default(),
+            })
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,480 @@
+use std::fmt::Display;
+
+use calc::{
+    fields::FieldMeta,
+    parameters::CalcInputParameters,
+    uncertainty::{
+        candidate_fields, default_inputs, Distribution, Statistics, UncertainInput,
+        UncertaintyReport, DEFAULT_SAMPLES, DEFAULT_SEED, PERCENTILES,
+    },
+    units::UnitSystem,
+};
+use iced::{
+    widget::{
+        checkbox, column, container, horizontal_rule, pick_list, row, scrollable, text, text_input,
+        Row,
+    },
+    Alignment, Background, Element, Length, Theme,
+};
+
+use crate::{components::labeled_button, Message, Tab};
+
+/// 直方图条形的最大宽度
+const BAR_WIDTH: f32 = 300.0;
+/// 未选中的字段默认以名义值为众值、上下浮动2%的三角分布
+const DEFAULT_RELATIVE: f64 = 0.02;
+
+/// 分布类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistributionKind {
+    Uniform,
+    Normal,
+    Triangular,
+}
+
+impl DistributionKind {
+    pub const ALL: [Self; 3] = [Self::Uniform, Self::Normal, Self::Triangular];
+
+    /// 各分布参数的名称
+    fn parameter_labels(&self) -> &'static [&'static str] {
+        match self {
+            Self::Uniform => &["下限", "上限"],
+            Self::Normal => &["均值", "标准差"],
+            Self::Triangular => &["下限", "众值", "上限"],
+        }
+    }
+}
+
+impl Display for DistributionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Uniform => "均匀分布",
+            Self::Normal => "正态分布",
+            Self::Triangular => "三角分布",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum UncertaintyMessage {
+    /// 字段名及是否参与抽样
+    ToggleInput(&'static str, bool),
+    KindSelected(&'static str, DistributionKind),
+    /// 字段名、分布参数序号及输入内容
+    ValueChanged(&'static str, usize, String),
+    SamplesChanged(String),
+    SeedChanged(String),
+    UpdateReport(Box<UncertaintyReport>),
+}
+
+/// 一个候选输入参数的分布设置，数值按显示单位
+struct DistributionInput {
+    field: &'static FieldMeta,
+    enabled: bool,
+    kind: DistributionKind,
+    values: [String; 3],
+}
+
+impl DistributionInput {
+    fn new(field: &'static FieldMeta, distribution: Distribution, units: &UnitSystem) -> Self {
+        let mut input = Self {
+            field,
+            enabled: false,
+            kind: DistributionKind::Triangular,
+            values: Default::default(),
+        };
+        input.set_distribution(distribution, units);
+        input
+    }
+
+    /// 按显示单位填入分布参数，正态分布的标准差按差值换算
+    fn set_distribution(&mut self, distribution: Distribution, units: &UnitSystem) {
+        let unit = self.field.unit;
+        let format = |value: f64| units.format(unit, value);
+        let (kind, values) = match distribution {
+            Distribution::Uniform { lower, upper } => (
+                DistributionKind::Uniform,
+                [format(lower), format(upper), String::new()],
+            ),
+            Distribution::Normal { mean, std_dev } => {
+                let std_dev = units.to_display(unit, mean + std_dev) - units.to_display(unit, mean);
+                (
+                    DistributionKind::Normal,
+                    [format(mean), format!("{}", round(std_dev)), String::new()],
+                )
+            }
+            Distribution::Triangular { lower, mode, upper } => (
+                DistributionKind::Triangular,
+                [format(lower), format(mode), format(upper)],
+            ),
+        };
+        self.kind = kind;
+        self.values = values;
+    }
+
+    /// 按显示单位读取分布参数
+    fn distribution(&self, units: &UnitSystem) -> Result<Distribution, String> {
+        let unit = self.field.unit;
+        let mut values = [0.0; 3];
+        for (i, value) in values
+            .iter_mut()
+            .enumerate()
+            .take(self.kind.parameter_labels().len())
+        {
+            *value = self.values[i].trim().parse::<f64>().map_err(|_| {
+                format!(
+                    "{}的{}无效",
+                    self.field.label,
+                    self.kind.parameter_labels()[i]
+                )
+            })?;
+        }
+        let internal = |value: f64| units.from_display(unit, value);
+        let distribution = match self.kind {
+            DistributionKind::Uniform => Distribution::Uniform {
+                lower: internal(values[0]),
+                upper: internal(values[1]),
+            },
+            DistributionKind::Normal => Distribution::Normal {
+                mean: internal(values[0]),
+                std_dev: internal(values[0] + values[1]) - internal(values[0]),
+            },
+            DistributionKind::Triangular => Distribution::Triangular {
+                lower: internal(values[0]),
+                mode: internal(values[1]),
+                upper: internal(values[2]),
+            },
+        };
+        Ok(distribution)
+    }
+
+    /// 切换分布类型时以原分布的中心和范围换算新分布的参数
+    fn convert(&mut self, kind: DistributionKind, units: &UnitSystem) {
+        let Ok(distribution) = self.distribution(units) else {
+            self.kind = kind;
+            return;
+        };
+        let (lower, center, upper) = match distribution {
+            Distribution::Uniform { lower, upper } => (lower, (lower + upper) / 2.0, upper),
+            Distribution::Normal { mean, std_dev } => {
+                (mean - 2.0 * std_dev, mean, mean + 2.0 * std_dev)
+            }
+            Distribution::Triangular { lower, mode, upper } => (lower, mode, upper),
+        };
+        let distribution = match kind {
+            DistributionKind::Uniform => Distribution::Uniform { lower, upper },
+            DistributionKind::Normal => Distribution::Normal {
+                mean: center,
+                std_dev: (upper - lower) / 4.0,
+            },
+            DistributionKind::Triangular => Distribution::Triangular {
+                lower,
+                mode: center,
+                upper,
+            },
+        };
+        self.set_distribution(distribution, units);
+    }
+}
+
+/// 保留6位小数
+fn round(value: f64) -> f64 {
+    (value * 1e6).round() / 1e6
+}
+
+pub struct UncertaintyTab {
+    inputs: Vec<DistributionInput>,
+    samples: String,
+    seed: String,
+    report: Option<UncertaintyReport>,
+    /// 分布参数与统计结果使用的单位制
+    units: UnitSystem,
+}
+
+impl Default for UncertaintyTab {
+    fn default() -> Self {
+        let units = UnitSystem::default();
+        let params = CalcInputParameters::from_default();
+        let defaults = default_inputs(&params);
+        let inputs = candidate_fields()
+            .map(
+                |field| match defaults.iter().find(|input| input.field.key == field.key) {
+                    Some(input) => {
+                        let mut input = DistributionInput::new(field, input.distribution, &units);
+                        input.enabled = true;
+                        input
+                    }
+                    None => {
+                        let value = field.default.unwrap_or_default();
+                        let distribution = Distribution::Triangular {
+                            lower: value * (1.0 - DEFAULT_RELATIVE),
+                            mode: value,
+                            upper: value * (1.0 + DEFAULT_RELATIVE),
+                        };
+                        DistributionInput::new(field, distribution, &units)
+                    }
+                },
+            )
+            .collect();
+        Self {
+            inputs,
+            samples: DEFAULT_SAMPLES.to_string(),
+            seed: DEFAULT_SEED.to_string(),
+            report: None,
+            units,
+        }
+    }
+}
+
+impl UncertaintyTab {
+    pub fn update(&mut self, message: UncertaintyMessage) {
+        let units = self.units;
+        match message {
+            UncertaintyMessage::ToggleInput(key, enabled) => {
+                if let Some(input) = self.input_mut(key) {
+                    input.enabled = enabled;
+                }
+            }
+            UncertaintyMessage::KindSelected(key, kind) => {
+                if let Some(input) = self.input_mut(key) {
+                    input.convert(kind, &units);
+                }
+            }
+            UncertaintyMessage::ValueChanged(key, index, value) => {
+                if let Some(input) = self.input_mut(key) {
+                    input.values[index] = value;
+                }
+            }
+            UncertaintyMessage::SamplesChanged(samples) => self.samples = samples,
+            UncertaintyMessage::SeedChanged(seed) => self.seed = seed,
+            UncertaintyMessage::UpdateReport(report) => self.report = Some(*report),
+        }
+    }
+
+    fn input_mut(&mut self, key: &str) -> Option<&mut DistributionInput> {
+        self.inputs.iter_mut().find(|input| input.field.key == key)
+    }
+
+    /// 切换单位制，按新单位制重新显示分布参数，无法解析的输入保持不变
+    pub fn set_units(&mut self, units: UnitSystem) {
+        for input in &mut self.inputs {
+            if let Ok(distribution) = input.distribution(&self.units) {
+                input.set_distribution(distribution, &units);
+            }
+        }
+        self.units = units;
+    }
+
+    /// 当前的分析结果
+    pub fn report(&self) -> Option<&UncertaintyReport> {
+        self.report.as_ref()
+    }
+
+    /// 按当前单位制读取选中的不确定输入参数
+    pub fn inputs(&self) -> Result<Vec<UncertainInput>, String> {
+        self.inputs
+            .iter()
+            .filter(|input| input.enabled)
+            .map(|input| {
+                Ok(UncertainInput {
+                    field: input.field,
+                    distribution: input.distribution(&self.units)?,
+                })
+            })
+            .collect()
+    }
+
+    /// 样本数与随机数种子
+    pub fn sampling(&self) -> Result<(usize, u64), String> {
+        let samples = self
+            .samples
+            .trim()
+            .parse()
+            .map_err(|_| String::from("样本数无效"))?;
+        let seed = self
+            .seed
+            .trim()
+            .parse()
+            .map_err(|_| String::from("随机数种子无效"))?;
+        Ok((samples, seed))
+    }
+
+    /// 表格中的一行
+    fn table_row<'a>(cells: Vec<String>, widths: &[f32]) -> Row<'a, Message> {
+        let mut row = Row::new().spacing(8);
+        for (content, &width) in cells.into_iter().zip(widths) {
+            row = row.push(container(text(content).size(13)).width(Length::Fixed(width)));
+        }
+        row
+    }
+
+    fn histogram_view<'a>(&self, stats: &Statistics) -> Element<'a, Message> {
+        let units = &self.units;
+        let unit = stats.field.unit;
+        let max = stats.histogram.counts.iter().copied().max().unwrap_or(0);
+        let mut histogram = column![text(format!(
+            "{} ({}) 直方图",
+            stats.field.label, stats.field.symbol
+        ))
+        .size(15)]
+        .spacing(2);
+        for (i, &count) in stats.histogram.counts.iter().enumerate() {
+            let (lower, upper) = stats.histogram.bin(i);
+            let width = if max > 0 {
+                count as f32 / max as f32 * BAR_WIDTH
+            } else {
+                0.0
+            };
+            histogram = histogram.push(
+                row![
+                    container(
+                        text(format!(
+                            "{} ~ {}",
+                            units.format(unit, lower),
+                            units.format(unit, upper)
+                        ))
+                        .size(12)
+                    )
+                    .width(Length::Fixed(220.0)),
+                    container(text(""))
+                        .width(Length::Fixed(width.max(1.0)))
+                        .height(Length::Fixed(12.0))
+                        .style(|theme: &Theme| container::Style {
+                            background: Some(Background::Color(theme.palette().primary)),
+                            ..container::Style::default()
+                        }),
+                    text(count.to_string()).size(12),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            );
+        }
+        histogram.into()
+    }
+
+    fn report_view<'a>(&self, report: &UncertaintyReport) -> Element<'a, Message> {
+        let units = &self.units;
+        let mut content = column![
+            text("分析结果").size(18),
+            horizontal_rule(1),
+            text(format!(
+                "种子 {}，样本数 {}，计算失败 {} 个",
+                report.seed, report.samples, report.failures
+            )),
+        ]
+        .spacing(8);
+        for (reason, count) in &report.failure_reasons {
+            content = content.push(text(format!("{reason}: {count} 个")).size(13));
+        }
+        if report.outputs.is_empty() {
+            return content.push(text("全部样本计算失败，没有统计结果")).into();
+        }
+
+        let mut widths = vec![220.0, 100.0, 100.0, 100.0, 100.0];
+        widths.extend(PERCENTILES.iter().map(|_| 100.0));
+        let mut headers: Vec<String> = ["参数", "均值", "标准差", "最小值", "最大值"]
+            .map(String::from)
+            .to_vec();
+        headers.extend(PERCENTILES.iter().map(|p| format!("P{p}")));
+        let mut table = column![Self::table_row(headers, &widths)].spacing(4);
+        for stats in &report.outputs {
+            let unit = stats.field.unit;
+            let std_dev = units.to_display(unit, stats.mean + stats.std_dev)
+                - units.to_display(unit, stats.mean);
+            let mut cells = vec![
+                stats.field.label_with_unit(units),
+                units.format(unit, stats.mean),
+                format!("{}", round(std_dev)),
+                units.format(unit, stats.min),
+                units.format(unit, stats.max),
+            ];
+            cells.extend(stats.percentiles.iter().map(|&p| units.format(unit, p)));
+            table = table.push(Self::table_row(cells, &widths));
+        }
+        content = content.push(table);
+        for stats in &report.outputs {
+            content = content.push(self.histogram_view(stats));
+        }
+        content.into()
+    }
+}
+
+impl Tab for UncertaintyTab {
+    type Message = Message;
+
+    fn tab_label(&self) -> iced_aw::TabLabel {
+        iced_aw::TabLabel::Text(String::from("不确定性分析"))
+    }
+
+    fn content(&self) -> iced::Element<Self::Message> {
+        let toolbar = row![
+            text("样本数"),
+            text_input("样本数", &self.samples)
+                .on_input(
+                    |value| Message::UncertaintyTab(UncertaintyMessage::SamplesChanged(value))
+                )
+                .width(Length::Fixed(90.0)),
+            text("种子"),
+            text_input("种子", &self.seed)
+                .on_input(|value| Message::UncertaintyTab(UncertaintyMessage::SeedChanged(value)))
+                .width(Length::Fixed(90.0)),
+            labeled_button("开始分析", Message::RunUncertainty),
+            labeled_button("导出Markdown", Message::SaveUncertaintyMarkdown),
+            labeled_button("导出CSV", Message::SaveUncertaintyCsv),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        let units = &self.units;
+        let mut inputs = column![
+            text("输入参数的概率分布").size(18),
+            horizontal_rule(1),
+            text("勾选需要抽样的参数，其余参数取当前输入值；同一种子与设置得到相同的结果。")
+                .size(13),
+        ]
+        .spacing(6);
+        for input in &self.inputs {
+            let key = input.field.key;
+            let mut line = row![
+                checkbox(input.field.label_with_unit(units), input.enabled)
+                    .on_toggle(move |enabled| {
+                        Message::UncertaintyTab(UncertaintyMessage::ToggleInput(key, enabled))
+                    })
+                    .width(Length::Fixed(300.0)),
+                pick_list(DistributionKind::ALL, Some(input.kind), move |kind| {
+                    Message::UncertaintyTab(UncertaintyMessage::KindSelected(key, kind))
+                })
+                .width(Length::Fixed(110.0)),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center);
+            for (index, &label) in input.kind.parameter_labels().iter().enumerate() {
+                line = line.push(
+                    text_input(label, &input.values[index])
+                        .on_input(move |value| {
+                            Message::UncertaintyTab(UncertaintyMessage::ValueChanged(
+                                key, index, value,
+                            ))
+                        })
+                        .width(Length::Fixed(100.0)),
+                );
+            }
+            inputs = inputs.push(line);
+        }
+
+        let mut content = column![toolbar].spacing(15);
+        if let Some(report) = &self.report {
+            content = content.push(self.report_view(report));
+        }
+        content = content.push(inputs);
+
+        scrollable(content.padding(10))
+            .direction(scrollable::Direction::Both {
+                vertical: scrollable::Scrollbar::default(),
+                horizontal: scrollable::Scrollbar::default(),
+            })
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,97 @@
+//! 预设管理对话框
+use calc::{
+    presets::{ParamDiff, Preset},
+    units::UnitSystem,
+};
+use iced::{
+    widget::{column, container, horizontal_rule, row, scrollable, text, text_input},
+    Alignment, Element, Length,
+};
+
+use crate::{components::labeled_button, Message};
+
+/// 预设与当前输入参数的对比结果：(预设名称, 差异)
+pub type PresetDiff = (String, Vec<ParamDiff>);
+
+/// 预设列表、保存与对比区域
+pub fn preset_dialog<'a>(
+    presets: &'a [Preset],
+    name: &str,
+    diff: Option<&'a PresetDiff>,
+    units: &UnitSystem,
+) -> Element<'a, Message> {
+    let save_row = row![
+        text_input("预设名称", name)
+            .on_input(Message::PresetNameChanged)
+            .width(Length::Fill),
+        labeled_button("保存当前参数", Message::SavePreset),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    let mut list = column![].spacing(10);
+    for (index, preset) in presets.iter().enumerate() {
+        let kind = if preset.builtin { "内置" } else { "用户" };
+        let info = column![
+            text(format!("{} ({kind})", preset.name)).size(16),
+            text(&preset.description).size(12),
+        ]
+        .width(Length::Fill);
+        let mut item = row![
+            info,
+            labeled_button("加载", Message::LoadPreset(index)),
+            labeled_button("对比", Message::DiffPreset(index)),
+        ]
+        .spacing(5)
+        .align_y(Alignment::Center);
+        // 内置预设不可修改
+        if !preset.builtin {
+            item = item
+                .push(labeled_button("重命名", Message::RenamePreset(index)))
+                .push(labeled_button("删除", Message::DeletePreset(index)));
+        }
+        list = list.push(item);
+    }
+
+    let mut content = column![
+        text("预设").size(24),
+        text("输入名称后可将当前参数保存为用户预设，或将所选用户预设重命名为该名称。").size(12),
+        save_row,
+        horizontal_rule(1),
+        scrollable(list).height(Length::Fixed(260.0)),
+    ]
+    .spacing(15)
+    .padding(20);
+
+    if let Some((preset_name, diffs)) = diff {
+        content = content
+            .push(horizontal_rule(1))
+            .push(text(format!("“{preset_name}” → 当前参数")).size(18));
+        if diffs.is_empty() {
+            content = content.push(text("参数完全相同"));
+        }
+        // 未给定的可选参数显示为“未给定”
+        let format = |field: &calc::fields::FieldMeta, value: f64| {
+            if value.is_nan() {
+                String::from("未给定")
+            } else {
+                units.format(field.unit, value)
+            }
+        };
+        let mut lines = column![].spacing(5);
+        for diff in diffs {
+            lines = lines.push(text(format!(
+                "{}: {} → {}",
+                diff.field.label_with_unit(units),
+                format(diff.field, diff.left),
+                format(diff.field, diff.right)
+            )));
+        }
+        content = content.push(scrollable(lines).height(Length::Shrink));
+    }
+
+    container(content)
+        .width(600)
+        .style(container::rounded_box)
+        .into()
+}